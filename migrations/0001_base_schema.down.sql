@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS plants;
+DROP TABLE IF EXISTS users;
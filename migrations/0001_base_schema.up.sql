@@ -0,0 +1,30 @@
+-- Base schema the repositories were written against.
+-- IF NOT EXISTS everywhere so databases created by hand before migrations existed can adopt it.
+CREATE TABLE IF NOT EXISTS users (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL,
+    password TEXT NOT NULL,
+    city TEXT,
+    birth_date TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS avatar TEXT;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS bio TEXT;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS plants (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    plant_type TEXT NOT NULL,
+    image TEXT,
+    planted_date TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    age INTEGER NOT NULL DEFAULT 0,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    status JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS plants_user_id_created_at_idx ON plants (user_id, created_at DESC);
@@ -0,0 +1,5 @@
+-- Emails are stored lowercased; fold existing rows before enforcing uniqueness.
+-- This fails if two accounts only differ by case, those have to be merged by hand first.
+UPDATE users SET email = lower(trim(email)) WHERE email <> lower(trim(email));
+
+CREATE UNIQUE INDEX IF NOT EXISTS users_email_lower_key ON users (lower(email));
@@ -0,0 +1,7 @@
+-- Failed login counters for LOGIN_THROTTLE_STORE=postgres, keyed by "email:<email>" or "ip:<address>"
+CREATE TABLE IF NOT EXISTS login_attempts (
+    key TEXT PRIMARY KEY,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_until TIMESTAMPTZ
+);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS plants_user_external_id_key;
+ALTER TABLE plants DROP COLUMN IF EXISTS external_id;
@@ -0,0 +1,4 @@
+-- Identifier from an outside system (spreadsheet row, other app), used to upsert on import
+ALTER TABLE plants ADD COLUMN IF NOT EXISTS external_id TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS plants_user_external_id_key ON plants (user_id, external_id);
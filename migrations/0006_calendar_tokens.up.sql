@@ -0,0 +1,4 @@
+-- Secret part of the per-user iCal feed URL, replaced when the user revokes the feed
+ALTER TABLE users ADD COLUMN IF NOT EXISTS calendar_token TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS users_calendar_token_key ON users (calendar_token);
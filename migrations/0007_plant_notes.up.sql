@@ -0,0 +1,19 @@
+-- Free-text care notes per plant, e.g. "yellow leaves, reduced watering"
+CREATE TABLE IF NOT EXISTS plant_notes (
+    id UUID PRIMARY KEY,
+    plant_id UUID NOT NULL REFERENCES plants (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    text TEXT NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    photo TEXT,
+    care_action TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    search TSVECTOR GENERATED ALWAYS AS (to_tsvector('english', text)) STORED
+);
+
+CREATE INDEX IF NOT EXISTS plant_notes_plant_id_date_idx ON plant_notes (plant_id, date DESC);
+CREATE INDEX IF NOT EXISTS plant_notes_user_id_date_idx ON plant_notes (user_id, date DESC);
+CREATE INDEX IF NOT EXISTS plant_notes_search_idx ON plant_notes USING GIN (search);
+CREATE INDEX IF NOT EXISTS plant_notes_tags_idx ON plant_notes USING GIN (tags);
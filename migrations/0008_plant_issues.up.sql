@@ -0,0 +1,18 @@
+-- Pest and disease incidents per plant, open while resolved_on is NULL
+CREATE TABLE IF NOT EXISTS plant_issues (
+    id UUID PRIMARY KEY,
+    plant_id UUID NOT NULL REFERENCES plants (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    issue_type TEXT NOT NULL,
+    severity TEXT NOT NULL CHECK (severity IN ('low', 'medium', 'high')),
+    description TEXT,
+    opened_on DATE NOT NULL,
+    resolved_on DATE CHECK (resolved_on >= opened_on),
+    resolution TEXT,
+    treatments JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS plant_issues_plant_id_idx ON plant_issues (plant_id, opened_on DESC);
+CREATE INDEX IF NOT EXISTS plant_issues_open_type_idx ON plant_issues (user_id, issue_type) WHERE resolved_on IS NULL;
@@ -0,0 +1 @@
+ALTER TABLE plants DROP COLUMN IF EXISTS indoor;
@@ -0,0 +1,2 @@
+-- Indoor plants are left out of weather based advice (rain, frost, heat)
+ALTER TABLE plants ADD COLUMN IF NOT EXISTS indoor BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,16 @@
+-- Frost and heat warnings raised by the weather alert job, one per plant, kind and day
+CREATE TABLE IF NOT EXISTS weather_alerts (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    plant_id UUID NOT NULL REFERENCES plants (id) ON DELETE CASCADE,
+    plant_name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('frost', 'heat')),
+    date DATE NOT NULL,
+    temperature_c DOUBLE PRECISION NOT NULL,
+    threshold_c DOUBLE PRECISION NOT NULL,
+    notified_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (plant_id, kind, date)
+);
+
+CREATE INDEX IF NOT EXISTS weather_alerts_user_date_idx ON weather_alerts (user_id, date DESC);
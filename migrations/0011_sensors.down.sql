@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS sensor_readings;
+DROP TABLE IF EXISTS sensor_devices;
@@ -0,0 +1,25 @@
+-- Sensor devices authenticate with an API key; only its SHA-256 hash is stored
+CREATE TABLE IF NOT EXISTS sensor_devices (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    key_prefix TEXT NOT NULL,
+    last_seen_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS sensor_devices_user_id_idx ON sensor_devices (user_id);
+
+-- One row per device, plant and measurement time; a resent batch does not create duplicates
+CREATE TABLE IF NOT EXISTS sensor_readings (
+    device_id UUID NOT NULL REFERENCES sensor_devices (id) ON DELETE CASCADE,
+    plant_id UUID NOT NULL REFERENCES plants (id) ON DELETE CASCADE,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    moisture_pct DOUBLE PRECISION CHECK (moisture_pct BETWEEN 0 AND 100),
+    temperature_c DOUBLE PRECISION,
+    light_lux DOUBLE PRECISION CHECK (light_lux >= 0),
+    PRIMARY KEY (device_id, plant_id, recorded_at)
+);
+
+CREATE INDEX IF NOT EXISTS sensor_readings_plant_time_idx ON sensor_readings (plant_id, recorded_at);
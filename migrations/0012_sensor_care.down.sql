@@ -0,0 +1,10 @@
+DROP TABLE IF EXISTS care_events;
+DROP TABLE IF EXISTS plant_thresholds;
+
+DELETE FROM plant_alerts WHERE kind = 'dry';
+ALTER INDEX IF EXISTS plant_alerts_user_date_idx RENAME TO weather_alerts_user_date_idx;
+ALTER TABLE plant_alerts DROP CONSTRAINT IF EXISTS plant_alerts_kind_check;
+ALTER TABLE plant_alerts ADD CONSTRAINT weather_alerts_kind_check CHECK (kind IN ('frost', 'heat'));
+ALTER TABLE plant_alerts RENAME COLUMN threshold TO threshold_c;
+ALTER TABLE plant_alerts RENAME COLUMN value TO temperature_c;
+ALTER TABLE plant_alerts RENAME TO weather_alerts;
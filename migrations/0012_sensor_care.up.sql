@@ -0,0 +1,30 @@
+-- Alerts are no longer only about the weather: "dry" comes from soil moisture readings
+ALTER TABLE weather_alerts RENAME TO plant_alerts;
+ALTER TABLE plant_alerts RENAME COLUMN temperature_c TO value;
+ALTER TABLE plant_alerts RENAME COLUMN threshold_c TO threshold;
+ALTER TABLE plant_alerts DROP CONSTRAINT IF EXISTS weather_alerts_kind_check;
+ALTER TABLE plant_alerts ADD CONSTRAINT plant_alerts_kind_check CHECK (kind IN ('frost', 'heat', 'dry'));
+ALTER INDEX IF EXISTS weather_alerts_user_date_idx RENAME TO plant_alerts_user_date_idx;
+
+-- Per-plant limits for sensor readings; NULL means the default applies (or no alert for moisture_min_pct)
+CREATE TABLE IF NOT EXISTS plant_thresholds (
+    plant_id UUID PRIMARY KEY REFERENCES plants (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    moisture_min_pct DOUBLE PRECISION CHECK (moisture_min_pct BETWEEN 0 AND 100),
+    watering_rise_pct DOUBLE PRECISION CHECK (watering_rise_pct > 0 AND watering_rise_pct <= 100),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Care actions with where they came from; the plant's status still holds the per-day flags
+CREATE TABLE IF NOT EXISTS care_events (
+    id UUID PRIMARY KEY,
+    plant_id UUID NOT NULL REFERENCES plants (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    action TEXT NOT NULL CHECK (action IN ('watered', 'fertilized', 'harvested')),
+    source TEXT NOT NULL CHECK (source IN ('manual', 'sensor')),
+    occurred_at TIMESTAMPTZ NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS care_events_plant_time_idx ON care_events (plant_id, occurred_at DESC);
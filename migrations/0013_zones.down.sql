@@ -0,0 +1,6 @@
+DELETE FROM care_events WHERE source = 'zone';
+ALTER TABLE care_events DROP CONSTRAINT IF EXISTS care_events_source_check;
+ALTER TABLE care_events ADD CONSTRAINT care_events_source_check CHECK (source IN ('manual', 'sensor'));
+
+DROP TABLE IF EXISTS zone_runs;
+DROP TABLE IF EXISTS zones;
@@ -0,0 +1,34 @@
+-- A valve or relay that waters a set of plants; `valve` is whatever the driver needs to address it
+CREATE TABLE IF NOT EXISTS zones (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    valve TEXT NOT NULL,
+    plant_ids UUID[] NOT NULL DEFAULT '{}',
+    run_minutes INTEGER NOT NULL DEFAULT 10 CHECK (run_minutes BETWEEN 1 AND 120),
+    scheduled BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS zones_user_id_idx ON zones (user_id);
+
+CREATE TABLE IF NOT EXISTS zone_runs (
+    id UUID PRIMARY KEY,
+    zone_id UUID NOT NULL REFERENCES zones (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    minutes INTEGER NOT NULL CHECK (minutes BETWEEN 1 AND 120),
+    triggered_by TEXT NOT NULL CHECK (triggered_by IN ('manual', 'schedule')),
+    status TEXT NOT NULL CHECK (status IN ('running', 'completed', 'failed')),
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS zone_runs_zone_time_idx ON zone_runs (zone_id, started_at DESC);
+-- A zone runs at most once at a time
+CREATE UNIQUE INDEX IF NOT EXISTS zone_runs_one_running_idx ON zone_runs (zone_id) WHERE status = 'running';
+
+-- Completed runs are logged as waterings of every plant in the zone
+ALTER TABLE care_events DROP CONSTRAINT IF EXISTS care_events_source_check;
+ALTER TABLE care_events ADD CONSTRAINT care_events_source_check CHECK (source IN ('manual', 'sensor', 'zone'));
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS garden_plants;
+DROP TABLE IF EXISTS garden_invitations;
+DROP TABLE IF EXISTS garden_members;
+DROP TABLE IF EXISTS gardens;
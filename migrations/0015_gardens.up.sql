@@ -0,0 +1,46 @@
+-- A garden is shared by its members; the owner manages members, editors look after the plants,
+-- viewers only look
+CREATE TABLE IF NOT EXISTS gardens (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE, -- also a member with role 'owner'
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS garden_members (
+    garden_id UUID NOT NULL REFERENCES gardens (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (garden_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS garden_members_user_id_idx ON garden_members (user_id);
+-- Exactly one owner per garden
+CREATE UNIQUE INDEX IF NOT EXISTS garden_members_one_owner_idx ON garden_members (garden_id) WHERE role = 'owner';
+
+CREATE TABLE IF NOT EXISTS garden_invitations (
+    id UUID PRIMARY KEY,
+    garden_id UUID NOT NULL REFERENCES gardens (id) ON DELETE CASCADE,
+    email TEXT NOT NULL, -- lowercased
+    role TEXT NOT NULL CHECK (role IN ('editor', 'viewer')),
+    invited_by UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    status TEXT NOT NULL CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    responded_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS garden_invitations_email_idx ON garden_invitations (email) WHERE status = 'pending';
+-- One open invitation per address and garden
+CREATE UNIQUE INDEX IF NOT EXISTS garden_invitations_one_pending_idx ON garden_invitations (garden_id, email) WHERE status = 'pending';
+
+-- A plant is in at most one garden; it keeps its owner (plants.user_id) while shared
+CREATE TABLE IF NOT EXISTS garden_plants (
+    plant_id UUID PRIMARY KEY REFERENCES plants (id) ON DELETE CASCADE,
+    garden_id UUID NOT NULL REFERENCES gardens (id) ON DELETE CASCADE,
+    added_by UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS garden_plants_garden_id_idx ON garden_plants (garden_id);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS plant_shares;
\ No newline at end of file
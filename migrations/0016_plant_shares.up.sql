@@ -0,0 +1,11 @@
+-- Public read-only links to a single plant; the token in the URL is the only credential
+CREATE TABLE IF NOT EXISTS plant_shares (
+    id UUID PRIMARY KEY,
+    plant_id UUID NOT NULL REFERENCES plants (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ, -- NULL: valid until revoked
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS plant_shares_plant_id_idx ON plant_shares (plant_id);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS garden_shares;
\ No newline at end of file
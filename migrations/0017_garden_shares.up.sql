@@ -0,0 +1,11 @@
+-- Public read-only links to a whole garden, listing its plants like a plant share does
+CREATE TABLE IF NOT EXISTS garden_shares (
+    id UUID PRIMARY KEY,
+    garden_id UUID NOT NULL REFERENCES gardens (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ, -- NULL: valid until revoked
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS garden_shares_garden_id_idx ON garden_shares (garden_id);
@@ -0,0 +1,361 @@
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use chrono::{Duration, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use uuid::Uuid;
+use employee::config::{self, AppConfig, Backend};
+use employee::dtos::auth_dto::{normalize_email, RegisterDTO};
+use employee::dtos::export_dto::{GardenExport, GARDEN_EXPORT_VERSION};
+use employee::dtos::validation::{self, ValidationErrors};
+use employee::errors::ServiceError;
+use employee::migrations;
+use employee::models::plant::Plant;
+use employee::models::user::{DeletedRows, SafeUser};
+use employee::repositories::auth_postgres::AuthPostgresRepo;
+use employee::repositories::auth_supabase::AuthSupabaseRepo;
+use employee::repositories::plant_postgres::PlantPostgresRepo;
+use employee::repositories::plant_supabase::PlantSupabaseRepo;
+use employee::repositories::supabase_client::SupabaseClient;
+use employee::secret::Secret;
+
+#[derive(Parser)]
+#[command(name = "gardenary-admin", about = "User and data management for Gardenary")]
+struct Cli {
+    #[arg(long, value_enum, help = "Backend to operate on, defaults to BACKEND from the config")]
+    backend: Option<BackendArg>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BackendArg {
+    Postgres,
+    Supabase,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "List users, optionally filtered by name or email")]
+    Users {
+        #[arg(long, short)]
+        search: Option<String>,
+        #[arg(long, default_value_t = 50)]
+        limit: i64,
+        #[arg(long, default_value_t = 0)]
+        offset: i64,
+    },
+    #[command(about = "Set a new password for a user (prompted, never passed as an argument)")]
+    ResetPassword { email: String },
+    #[command(about = "Delete a user together with all of their plants")]
+    DeleteUser {
+        email: String,
+        #[arg(long, help = "Do not ask for confirmation")]
+        yes: bool,
+    },
+    #[command(about = "Create a demo user with a few plants and some care history")]
+    Seed {
+        #[arg(long, default_value = "demo@gardenary.local")]
+        email: String,
+        #[arg(long, default_value = "demo-garden-1")]
+        password: String,
+    },
+    #[command(about = "Apply or revert Postgres migrations: up | down [steps] | status")]
+    Migrate {
+        #[arg(trailing_var_arg = true)]
+        args: Vec<String>,
+    },
+    #[command(about = "Write a user's profile and plants as JSON")]
+    Export {
+        email: String,
+        #[arg(long, short, help = "Output file, stdout when omitted")]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Load plants from an export file into a user's garden")]
+    Import { email: String, file: PathBuf },
+}
+
+// Same repositories as the server, for whichever backend was selected
+enum Repos {
+    Postgres { auth: AuthPostgresRepo, plants: PlantPostgresRepo },
+    Supabase { auth: AuthSupabaseRepo, plants: PlantSupabaseRepo },
+}
+
+impl Repos {
+    async fn list_users(&self, search: Option<&str>, limit: i64, offset: i64) -> Result<Vec<SafeUser>, String> {
+        match self {
+            Repos::Postgres { auth, .. } => auth.list_users(search, limit, offset).await,
+            Repos::Supabase { auth, .. } => auth.list_users(search, limit, offset).await,
+        }
+    }
+
+    async fn user_by_email(&self, email: &str) -> Result<SafeUser, String> {
+        let email = normalize_email(email);
+        match self {
+            Repos::Postgres { auth, .. } => auth.get_user_by_email(&email).await,
+            Repos::Supabase { auth, .. } => auth.get_user_by_email(&email).await,
+        }
+    }
+
+    async fn register(&self, dto: RegisterDTO) -> Result<SafeUser, ServiceError> {
+        match self {
+            Repos::Postgres { auth, .. } => auth.register(dto).await,
+            Repos::Supabase { auth, .. } => auth.register(dto).await,
+        }
+    }
+
+    async fn update_password(&self, id: Uuid, password: &str) -> Result<(), String> {
+        match self {
+            Repos::Postgres { auth, .. } => auth.update_password(id, password).await,
+            Repos::Supabase { auth, .. } => auth.update_password(id, password).await,
+        }
+    }
+
+    async fn delete_user(&self, id: Uuid) -> Result<DeletedRows, String> {
+        match self {
+            Repos::Postgres { auth, .. } => auth.delete_user(id).await,
+            Repos::Supabase { auth, .. } => auth.delete_user(id).await,
+        }
+    }
+
+    async fn plants(&self, user_id: Uuid) -> Result<Vec<Plant>, String> {
+        match self {
+            Repos::Postgres { plants, .. } => plants.get_all_by_user(user_id).await,
+            Repos::Supabase { plants, .. } => plants.get_all_by_user(user_id).await,
+        }
+    }
+
+    async fn restore_plant(&self, plant: &Plant) -> Result<Plant, String> {
+        match self {
+            Repos::Postgres { plants, .. } => plants.restore(plant).await,
+            Repos::Supabase { plants, .. } => plants.restore(plant).await,
+        }
+    }
+}
+
+fn confirm(question: &str) -> io::Result<String> {
+    print!("{} ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+fn check_password(password: &str) -> Result<(), String> {
+    let mut errors = ValidationErrors::default();
+    validation::password_strength(&mut errors, "password", password);
+    errors.into_result().map_err(|e| format!("Invalid password: {}", json!(e)))
+}
+
+fn print_users(users: &[SafeUser]) {
+    println!("{:<36}  {:<32}  {:<24}  {:<8}  created", "id", "email", "name", "verified");
+    for user in users {
+        println!(
+            "{:<36}  {:<32}  {:<24}  {:<8}  {}",
+            user.id,
+            user.email,
+            user.name,
+            if user.email_verified_at.is_some() { "yes" } else { "no" },
+            user.created_at.as_deref().unwrap_or("-")
+        );
+    }
+}
+
+// Demo garden: a handful of plants with a week of watering/fertilizing history
+fn demo_plants(user_id: Uuid) -> Vec<Plant> {
+    let now = Utc::now();
+    let demo = [
+        ("Tomat Cherry", "Vegetable", 45),
+        ("Cabai Rawit", "Vegetable", 30),
+        ("Basil", "Herb", 20),
+        ("Strawberry", "Fruit", 60),
+        ("Mawar", "Flower", 90),
+    ];
+
+    demo.iter().enumerate().map(|(i, (name, plant_type, age))| {
+        let mut status = serde_json::Map::new();
+        for day in 0..7u32 {
+            let date = (now - Duration::days(day as i64)).format("%Y-%m-%d").to_string();
+            status.insert(date, json!({
+                "watered": !(day as usize + i).is_multiple_of(3),
+                "fertilized": day.is_multiple_of(3),
+                "harvested": *plant_type == "Fruit" && day == 2,
+            }));
+        }
+        Plant {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            plant_type: plant_type.to_string(),
+            image: None,
+            planted_date: now - Duration::days(*age as i64),
+            age: *age,
+            user_id,
+            status: serde_json::Value::Object(status),
+            external_id: None,
+            indoor: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }).collect()
+}
+
+async fn run(cli: Cli, config: AppConfig) -> Result<(), String> {
+    let backend = match cli.backend {
+        Some(BackendArg::Postgres) => Backend::Postgres,
+        Some(BackendArg::Supabase) => Backend::Supabase,
+        None => config.backend,
+    };
+
+    // Migrations always target the Postgres connection, even when Supabase is the primary backend
+    if let Command::Migrate { args } = &cli.command {
+        let pool = config::get_pg_pool(&config.database)?;
+        return migrations::run_command(&pool, args).await;
+    }
+
+    let repos = match backend {
+        Backend::Postgres => {
+            let pool = config::get_pg_pool(&config.database)?;
+            migrations::ensure_up_to_date(&pool).await?;
+            Repos::Postgres {
+                auth: AuthPostgresRepo { pool: pool.clone() },
+                plants: PlantPostgresRepo { pool },
+            }
+        }
+        Backend::Supabase => {
+            let client = SupabaseClient::new(&config.supabase.url, config.supabase.key.clone())?;
+            Repos::Supabase {
+                auth: AuthSupabaseRepo { client: client.clone() },
+                plants: PlantSupabaseRepo { client },
+            }
+        }
+    };
+
+    match cli.command {
+        Command::Users { search, limit, offset } => {
+            let users = repos.list_users(search.as_deref(), limit, offset).await?;
+            print_users(&users);
+        }
+        Command::ResetPassword { email } => {
+            let user = repos.user_by_email(&email).await?;
+            let password = rpassword::prompt_password("New password: ").map_err(|e| e.to_string())?;
+            let again = rpassword::prompt_password("Repeat password: ").map_err(|e| e.to_string())?;
+            if password != again {
+                return Err("Passwords do not match".to_string());
+            }
+            check_password(&password)?;
+            repos.update_password(user.id, &password).await?;
+            println!("Password updated for {}", user.email);
+        }
+        Command::DeleteUser { email, yes } => {
+            let user = repos.user_by_email(&email).await?;
+            let plants = repos.plants(user.id).await?;
+            if !yes {
+                let answer = confirm(&format!(
+                    "Delete {} ({}) and {} plant(s)? Type the email to confirm:",
+                    user.name, user.email, plants.len()
+                )).map_err(|e| e.to_string())?;
+                if normalize_email(&answer) != user.email {
+                    return Err("Aborted".to_string());
+                }
+            }
+            let deleted = repos.delete_user(user.id).await?;
+            println!("Deleted {} with {}", user.email, deleted);
+        }
+        Command::Seed { email, password } => {
+            check_password(&password)?;
+            let dto = RegisterDTO {
+                name: "Demo Gardener".to_string(),
+                email: normalize_email(&email),
+                password: Secret::new(password.clone()),
+                confirm_password: Secret::new(password),
+                city: Some("Bandung".to_string()),
+                birth_date: None,
+            };
+            let user = match repos.register(dto).await {
+                Ok(user) => user,
+                Err(ServiceError::Conflict(_)) => repos.user_by_email(&email).await?,
+                Err(e) => return Err(e.to_string()),
+            };
+
+            if !repos.plants(user.id).await?.is_empty() {
+                println!("{} already has plants, nothing to seed", user.email);
+                return Ok(());
+            }
+            let plants = demo_plants(user.id);
+            for plant in &plants {
+                repos.restore_plant(plant).await?;
+            }
+            println!("Seeded {} with {} plants", user.email, plants.len());
+        }
+        Command::Export { email, output } => {
+            let user = repos.user_by_email(&email).await?;
+            let plants = repos.plants(user.id).await?;
+            let export = GardenExport {
+                version: GARDEN_EXPORT_VERSION,
+                exported_at: Utc::now(),
+                user,
+                plants,
+            };
+            let body = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, body).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+                    eprintln!("Exported {} plant(s) to {}", export.plants.len(), path.display());
+                }
+                None => println!("{}", body),
+            }
+        }
+        Command::Import { email, file } => {
+            let body = std::fs::read_to_string(&file).map_err(|e| format!("cannot read {}: {}", file.display(), e))?;
+            let export: GardenExport = serde_json::from_str(&body).map_err(|e| format!("invalid export file: {}", e))?;
+            if export.version > GARDEN_EXPORT_VERSION {
+                return Err(format!("export version {} is newer than this tool supports", export.version));
+            }
+
+            let user = repos.user_by_email(&email).await?;
+            // Restoring into the same account keeps ids so a re-import updates instead of duplicating
+            let same_user = user.id == export.user.id;
+            let mut imported = 0;
+            let mut failed = 0;
+            for mut plant in export.plants {
+                if !same_user {
+                    plant.id = Uuid::new_v4();
+                }
+                plant.user_id = user.id;
+                match repos.restore_plant(&plant).await {
+                    Ok(_) => imported += 1,
+                    Err(e) => {
+                        failed += 1;
+                        eprintln!("Plant {:?}: {}", plant.name, e);
+                    }
+                }
+            }
+            println!("Imported {} plant(s) into {}, {} failed", imported, user.email, failed);
+            if failed > 0 {
+                return Err("Some plants could not be imported".to_string());
+            }
+        }
+        Command::Migrate { .. } => unreachable!("handled above"),
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let cli = Cli::parse();
+    let config = match AppConfig::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(err) = run(cli, config).await {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
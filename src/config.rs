@@ -1,15 +1,499 @@
+use std::collections::HashMap;
 use std::env;
-use deadpool_postgres::{Config, Pool};
+use std::fmt;
+use std::path::Path;
+use deadpool_postgres::{Config, Pool, PoolConfig};
+use crate::middleware::rate_limit::{BucketConfig, RateLimits};
+use crate::secret::Secret;
 
-pub fn get_pg_pool() -> Pool {
-    let mut cfg = Config::new();
-    cfg.host = Some(env::var("PG_HOST").expect("PG_HOST not set"));
-    cfg.user = Some(env::var("PG_USER").expect("PG_USER not set"));
-    cfg.password = env::var("PG_PASS").ok();
-    cfg.dbname = Some(env::var("PG_DB").expect("PG_DB not set"));
-    cfg.create_pool(None, tokio_postgres::NoTls).unwrap()
+// Backend used by tooling and background jobs; the HTTP API keeps serving both /pg and /sb routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Postgres,
+    Supabase,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailerKind {
+    Log,
+    File,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherProviderKind {
+    None,
+    Http,
+    Fixture,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValveDriverKind {
+    Simulated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleStoreKind {
+    Memory,
+    Postgres,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    pub workers: Option<usize>,
+    pub allowed_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub user: String,
+    pub password: Option<Secret<String>>,
+    pub dbname: String,
+    pub pool_size: usize,
+    pub auto_migrate: bool, // apply pending migrations at startup instead of refusing to start
+}
+
+#[derive(Debug, Clone)]
+pub struct SupabaseConfig {
+    pub url: String,
+    pub key: Secret<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthConfig {
+    pub jwt_secret: Secret<String>,
+    pub jwt_ttl_hours: i64,
+    pub app_base_url: String,
+    pub require_email_verification: bool,
+    pub login_throttle_store: ThrottleStoreKind,
+    pub account_deletion_grace_days: i64, // 0 deletes accounts immediately
+}
+
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    pub mailer: MailerKind,
+    pub dir: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct WeatherConfig {
+    pub provider: WeatherProviderKind,
+    pub fixture_path: String,
+    pub geocoding_url: String,
+    pub forecast_url: String,
+    pub rain_threshold_mm: f64, // more rain than this on a day counts as watering outdoor plants
+    pub frost_threshold_c: f64,
+    pub heat_threshold_c: f64,
+    pub alert_horizon_days: i64,
+    pub alert_interval_minutes: u64, // 0 turns the frost/heat alert job off
+}
+
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    pub host: Option<String>, // the bridge only runs when a broker is set
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<Secret<String>>,
+    pub topic_prefix: String,
+    pub command_interval_minutes: u64, // 0 turns the "water now" commands off
+}
+
+#[derive(Debug, Clone)]
+pub struct ValvesConfig {
+    pub driver: ValveDriverKind,
+    pub simulated_seconds_per_minute: u64, // how long a simulated run minute takes
+    pub schedule_interval_minutes: u64, // 0 turns scheduled zone runs off
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhooksConfig {
+    pub max_attempts: u32, // a delivery is marked failed after this many
+    pub retry_base_seconds: u64, // delay before the first retry, doubled for every later one
+    pub timeout_seconds: u64,
+    pub poll_interval_seconds: u64, // how often the queue is checked for retries that are due
+}
+
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub supabase: SupabaseConfig,
+    pub auth: AuthConfig,
+    pub mail: MailConfig,
+    pub weather: WeatherConfig,
+    pub mqtt: MqttConfig,
+    pub valves: ValvesConfig,
+    pub webhooks: WebhooksConfig,
+    pub backend: Backend,
+    pub rate_limits: RateLimits,
+}
+
+// Every problem found while loading, so they can all be fixed in one go
+#[derive(Debug)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "invalid configuration:")?;
+        for problem in &self.0 {
+            writeln!(f, "  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+const DEFAULT_CONFIG_FILE: &str = "gardenary.toml";
+
+const DEFAULT_ALLOWED_ORIGINS: [&str; 5] = [
+    "http://localhost:3001",
+    "http://localhost:3000",
+    "http://127.0.0.1:3000",
+    "http://localhost:5173",
+    "http://127.0.0.1:5173",
+];
+
+// Settings come from the environment first, then from the optional TOML file.
+// TOML sections are flattened into the env names: [server] port = 8081 is SERVER_PORT,
+// [pg] host is PG_HOST, arrays become comma separated lists.
+struct Sources {
+    file: HashMap<String, String>,
+}
+
+impl Sources {
+    fn get(&self, name: &str) -> Option<String> {
+        env::var(name).ok()
+            .or_else(|| self.file.get(name).cloned())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
 }
 
-pub fn get_supabase_url() -> String {
-    env::var("SUPABASE_URL").expect("SUPABASE_URL not set")
-}
\ No newline at end of file
+fn flatten_toml(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let name = if prefix.is_empty() {
+            key.to_uppercase()
+        } else {
+            format!("{}_{}", prefix, key.to_uppercase())
+        };
+        match value {
+            toml::Value::Table(inner) => flatten_toml(&name, inner, out),
+            toml::Value::Array(items) => {
+                let joined = items.iter().map(toml_scalar).collect::<Vec<_>>().join(",");
+                out.insert(name, joined);
+            }
+            other => {
+                out.insert(name, toml_scalar(other));
+            }
+        }
+    }
+}
+
+fn toml_scalar(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn load_file(problems: &mut Vec<String>) -> HashMap<String, String> {
+    let (path, explicit) = match env::var("CONFIG_FILE") {
+        Ok(path) => (path, true),
+        Err(_) => (DEFAULT_CONFIG_FILE.to_string(), false),
+    };
+    if !explicit && !Path::new(&path).exists() {
+        return HashMap::new();
+    }
+
+    let table = std::fs::read_to_string(&path)
+        .map_err(|e| format!("cannot read config file {}: {}", path, e))
+        .and_then(|text| text.parse::<toml::Table>()
+            .map_err(|e| format!("cannot parse config file {}: {}", path, e)));
+
+    let mut out = HashMap::new();
+    match table {
+        Ok(table) => flatten_toml("", &table, &mut out),
+        Err(problem) => problems.push(problem),
+    }
+    out
+}
+
+// Small helpers that record a problem and fall back to a placeholder so loading can continue
+fn required(sources: &Sources, problems: &mut Vec<String>, name: &str) -> String {
+    sources.get(name).unwrap_or_else(|| {
+        problems.push(format!("{} is not set", name));
+        String::new()
+    })
+}
+
+fn parsed<T: std::str::FromStr>(sources: &Sources, problems: &mut Vec<String>, name: &str, default: T) -> T {
+    match sources.get(name) {
+        None => default,
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            problems.push(format!("{} has an invalid value {:?}", name, value));
+            default
+        }),
+    }
+}
+
+fn flag(sources: &Sources, problems: &mut Vec<String>, name: &str, default: bool) -> bool {
+    match sources.get(name).as_deref() {
+        None => default,
+        Some("true") | Some("1") => true,
+        Some("false") | Some("0") => false,
+        Some(other) => {
+            problems.push(format!("{} must be true or false, got {:?}", name, other));
+            default
+        }
+    }
+}
+
+fn is_http_url(value: &str) -> bool {
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+// HS256 tokens are only as strong as the secret; reject short and well-known placeholder values.
+// Placeholders are matched as whole values, a random secret may well contain "secret" somewhere.
+const MIN_JWT_SECRET_LEN: usize = 32;
+const PLACEHOLDER_SECRETS: [&str; 8] = [
+    "secret",
+    "your-secret-key",
+    "changeme",
+    "change-me",
+    "jwt-secret",
+    "password",
+    "your_super_secret_jwt_key_here_make_it_long_and_random", // the old .env default
+    "replace-with-output-of-openssl-rand-hex-32", // .env.example
+];
+
+fn weak_jwt_secret(secret: &str) -> Option<String> {
+    if secret.is_empty() {
+        return None; // already reported as missing
+    }
+    if PLACEHOLDER_SECRETS.iter().any(|p| secret.trim().eq_ignore_ascii_case(p)) {
+        return Some("JWT_SECRET looks like a placeholder value, generate a random one (e.g. openssl rand -hex 32)".to_string());
+    }
+    if secret.len() < MIN_JWT_SECRET_LEN {
+        return Some(format!("JWT_SECRET must be at least {} characters long", MIN_JWT_SECRET_LEN));
+    }
+    let distinct = secret.chars().collect::<std::collections::HashSet<_>>().len();
+    if distinct < 8 {
+        return Some("JWT_SECRET has too few distinct characters, generate a random one".to_string());
+    }
+    None
+}
+
+// Limits are written as "<requests>/<seconds>", e.g. RATE_LIMIT_WRITE=60/60
+fn bucket(sources: &Sources, problems: &mut Vec<String>, name: &str, default: BucketConfig) -> BucketConfig {
+    let Some(value) = sources.get(name) else {
+        return default;
+    };
+    match value.split_once('/').map(|(n, secs)| (n.trim().parse(), secs.trim().parse())) {
+        Some((Ok(capacity), Ok(period_secs))) if capacity > 0 && period_secs > 0 => {
+            BucketConfig { capacity, period_secs }
+        }
+        _ => {
+            problems.push(format!("{} must look like <requests>/<seconds>, got {:?}", name, value));
+            default
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn load() -> Result<AppConfig, ConfigError> {
+        let mut problems = Vec::new();
+        let sources = Sources { file: load_file(&mut problems) };
+        let p = &mut problems;
+
+        let server = ServerConfig {
+            host: sources.get("SERVER_HOST").unwrap_or_else(|| "127.0.0.1".to_string()),
+            port: parsed(&sources, p, "SERVER_PORT", 8081),
+            workers: sources.get("SERVER_WORKERS").map(|_| parsed(&sources, p, "SERVER_WORKERS", 1)),
+            allowed_origins: match sources.get("CORS_ALLOWED_ORIGINS") {
+                Some(list) => list.split(',').map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect(),
+                None => DEFAULT_ALLOWED_ORIGINS.iter().map(|o| o.to_string()).collect(),
+            },
+        };
+        if server.workers == Some(0) {
+            p.push("SERVER_WORKERS must be at least 1".to_string());
+        }
+        for origin in &server.allowed_origins {
+            if !is_http_url(origin) {
+                p.push(format!("CORS_ALLOWED_ORIGINS entry {:?} must start with http:// or https://", origin));
+            }
+        }
+
+        let database = DatabaseConfig {
+            host: required(&sources, p, "PG_HOST"),
+            user: required(&sources, p, "PG_USER"),
+            password: sources.get("PG_PASS").map(Secret::new),
+            dbname: required(&sources, p, "PG_DB"),
+            pool_size: parsed(&sources, p, "PG_POOL_SIZE", 16),
+            auto_migrate: flag(&sources, p, "PG_AUTO_MIGRATE", false),
+        };
+        if database.pool_size == 0 {
+            p.push("PG_POOL_SIZE must be at least 1".to_string());
+        }
+
+        let supabase = SupabaseConfig {
+            url: required(&sources, p, "SUPABASE_URL"),
+            key: Secret::new(required(&sources, p, "SUPABASE_KEY")),
+        };
+        if !supabase.url.is_empty() && !is_http_url(&supabase.url) {
+            p.push("SUPABASE_URL must start with http:// or https://".to_string());
+        }
+
+        let auth = AuthConfig {
+            jwt_secret: Secret::new(required(&sources, p, "JWT_SECRET")),
+            jwt_ttl_hours: parsed(&sources, p, "JWT_TTL_HOURS", 24),
+            app_base_url: sources.get("APP_BASE_URL").unwrap_or_else(|| format!("http://{}:{}", server.host, server.port)),
+            require_email_verification: flag(&sources, p, "REQUIRE_EMAIL_VERIFICATION", false),
+            login_throttle_store: match sources.get("LOGIN_THROTTLE_STORE").as_deref() {
+                None | Some("memory") => ThrottleStoreKind::Memory,
+                Some("postgres") => ThrottleStoreKind::Postgres,
+                Some(other) => {
+                    p.push(format!("LOGIN_THROTTLE_STORE must be memory or postgres, got {:?}", other));
+                    ThrottleStoreKind::Memory
+                }
+            },
+            account_deletion_grace_days: parsed(&sources, p, "ACCOUNT_DELETION_GRACE_DAYS", 0),
+        };
+        if let Some(problem) = weak_jwt_secret(auth.jwt_secret.expose()) {
+            p.push(problem);
+        }
+        if auth.account_deletion_grace_days < 0 {
+            p.push("ACCOUNT_DELETION_GRACE_DAYS must not be negative".to_string());
+        }
+        if auth.jwt_ttl_hours <= 0 {
+            p.push("JWT_TTL_HOURS must be a positive number of hours".to_string());
+        }
+
+        let mail = MailConfig {
+            mailer: match sources.get("MAILER").as_deref() {
+                None | Some("log") => MailerKind::Log,
+                Some("file") => MailerKind::File,
+                Some(other) => {
+                    p.push(format!("MAILER must be log or file, got {:?}", other));
+                    MailerKind::Log
+                }
+            },
+            dir: sources.get("MAIL_DIR").unwrap_or_else(|| "./mail".to_string()),
+        };
+
+        let weather = WeatherConfig {
+            provider: match sources.get("WEATHER_PROVIDER").as_deref() {
+                None | Some("none") => WeatherProviderKind::None,
+                Some("http") => WeatherProviderKind::Http,
+                Some("fixture") => WeatherProviderKind::Fixture,
+                Some(other) => {
+                    p.push(format!("WEATHER_PROVIDER must be none, http or fixture, got {:?}", other));
+                    WeatherProviderKind::None
+                }
+            },
+            fixture_path: sources.get("WEATHER_FIXTURE_PATH").unwrap_or_else(|| "./weather.json".to_string()),
+            geocoding_url: sources.get("WEATHER_GEOCODING_URL")
+                .unwrap_or_else(|| "https://geocoding-api.open-meteo.com/v1/search".to_string()),
+            forecast_url: sources.get("WEATHER_FORECAST_URL")
+                .unwrap_or_else(|| "https://api.open-meteo.com/v1/forecast".to_string()),
+            rain_threshold_mm: parsed(&sources, p, "WEATHER_RAIN_THRESHOLD_MM", 5.0),
+            frost_threshold_c: parsed(&sources, p, "WEATHER_FROST_THRESHOLD_C", 2.0),
+            heat_threshold_c: parsed(&sources, p, "WEATHER_HEAT_THRESHOLD_C", 32.0),
+            alert_horizon_days: parsed(&sources, p, "WEATHER_ALERT_HORIZON_DAYS", 3),
+            alert_interval_minutes: parsed(&sources, p, "WEATHER_ALERT_INTERVAL_MINUTES", 180),
+        };
+        if weather.provider == WeatherProviderKind::Http {
+            for (name, url) in [("WEATHER_GEOCODING_URL", &weather.geocoding_url), ("WEATHER_FORECAST_URL", &weather.forecast_url)] {
+                if !is_http_url(url) {
+                    p.push(format!("{} must start with http:// or https://", name));
+                }
+            }
+        }
+        if weather.rain_threshold_mm.is_nan() || weather.rain_threshold_mm < 0.0 {
+            p.push("WEATHER_RAIN_THRESHOLD_MM must not be negative".to_string());
+        }
+        if weather.frost_threshold_c >= weather.heat_threshold_c {
+            p.push("WEATHER_FROST_THRESHOLD_C must be below WEATHER_HEAT_THRESHOLD_C".to_string());
+        }
+        if !(0..=14).contains(&weather.alert_horizon_days) {
+            p.push("WEATHER_ALERT_HORIZON_DAYS must be between 0 and 14".to_string());
+        }
+
+        let mqtt = MqttConfig {
+            host: sources.get("MQTT_HOST"),
+            port: parsed(&sources, p, "MQTT_PORT", 1883),
+            client_id: sources.get("MQTT_CLIENT_ID").unwrap_or_else(|| "gardenary".to_string()),
+            username: sources.get("MQTT_USERNAME"),
+            password: sources.get("MQTT_PASSWORD").map(Secret::new),
+            topic_prefix: sources.get("MQTT_TOPIC_PREFIX").unwrap_or_else(|| "gardenary".to_string()),
+            command_interval_minutes: parsed(&sources, p, "MQTT_COMMAND_INTERVAL_MINUTES", 15),
+        };
+        if mqtt.topic_prefix.contains(['+', '#']) || mqtt.topic_prefix.starts_with('/') || mqtt.topic_prefix.ends_with('/') {
+            p.push("MQTT_TOPIC_PREFIX must not contain wildcards or start or end with /".to_string());
+        }
+        if mqtt.password.is_some() && mqtt.username.is_none() {
+            p.push("MQTT_PASSWORD is set without MQTT_USERNAME".to_string());
+        }
+
+        let valves = ValvesConfig {
+            driver: match sources.get("VALVES_DRIVER").as_deref() {
+                None | Some("simulated") => ValveDriverKind::Simulated,
+                Some(other) => {
+                    p.push(format!("VALVES_DRIVER must be simulated, got {:?}", other));
+                    ValveDriverKind::Simulated
+                }
+            },
+            simulated_seconds_per_minute: parsed(&sources, p, "VALVES_SIMULATED_SECONDS_PER_MINUTE", 60),
+            schedule_interval_minutes: parsed(&sources, p, "VALVES_SCHEDULE_INTERVAL_MINUTES", 15),
+        };
+
+        let webhooks = WebhooksConfig {
+            max_attempts: parsed(&sources, p, "WEBHOOKS_MAX_ATTEMPTS", 8),
+            retry_base_seconds: parsed(&sources, p, "WEBHOOKS_RETRY_BASE_SECONDS", 30),
+            timeout_seconds: parsed(&sources, p, "WEBHOOKS_TIMEOUT_SECONDS", 10),
+            poll_interval_seconds: parsed(&sources, p, "WEBHOOKS_POLL_INTERVAL_SECONDS", 30),
+        };
+        if !(1..=20).contains(&webhooks.max_attempts) {
+            p.push("WEBHOOKS_MAX_ATTEMPTS must be between 1 and 20".to_string());
+        }
+        if !(1..=60).contains(&webhooks.timeout_seconds) {
+            p.push("WEBHOOKS_TIMEOUT_SECONDS must be between 1 and 60".to_string());
+        }
+        if webhooks.poll_interval_seconds == 0 {
+            p.push("WEBHOOKS_POLL_INTERVAL_SECONDS must be at least 1".to_string());
+        }
+
+        let backend = match sources.get("BACKEND").as_deref() {
+            None | Some("postgres") => Backend::Postgres,
+            Some("supabase") => Backend::Supabase,
+            Some(other) => {
+                p.push(format!("BACKEND must be postgres or supabase, got {:?}", other));
+                Backend::Postgres
+            }
+        };
+
+        let rate_limits = RateLimits {
+            auth: bucket(&sources, p, "RATE_LIMIT_AUTH", BucketConfig { capacity: 20, period_secs: 60 }),
+            read: bucket(&sources, p, "RATE_LIMIT_READ", BucketConfig { capacity: 300, period_secs: 60 }),
+            write: bucket(&sources, p, "RATE_LIMIT_WRITE", BucketConfig { capacity: 60, period_secs: 60 }),
+        };
+
+        if !problems.is_empty() {
+            return Err(ConfigError(problems));
+        }
+
+        Ok(AppConfig { server, database, supabase, auth, mail, weather, mqtt, valves, webhooks, backend, rate_limits })
+    }
+}
+
+pub fn get_pg_pool(db: &DatabaseConfig) -> Result<Pool, String> {
+    let mut cfg = Config::new();
+    cfg.host = Some(db.host.clone());
+    cfg.user = Some(db.user.clone());
+    cfg.password = db.password.as_ref().map(|p| p.expose().clone());
+    cfg.dbname = Some(db.dbname.clone());
+    cfg.pool = Some(PoolConfig::new(db.pool_size));
+    cfg.create_pool(None, tokio_postgres::NoTls)
+        .map_err(|e| format!("cannot create Postgres pool: {}", e))
+}
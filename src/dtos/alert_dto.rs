@@ -0,0 +1,23 @@
+use serde::Deserialize;
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+
+pub const ALERT_KINDS: [&str; 3] = ["frost", "heat", "dry"];
+
+#[derive(Debug, Deserialize)]
+pub struct AlertListQuery {
+    pub from: Option<String>, // YYYY-MM-DD, defaults to today so only current warnings are listed
+    pub kind: Option<String>,
+}
+
+impl Validate for AlertListQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(from) = &self.from {
+            validation::date(&mut errors, "from", from);
+        }
+        if let Some(kind) = &self.kind {
+            validation::one_of(&mut errors, "kind", kind, &ALERT_KINDS);
+        }
+        errors.into_result()
+    }
+}
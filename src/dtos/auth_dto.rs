@@ -1,19 +1,94 @@
 use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+use crate::secret::Secret;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RegisterDTO {
     pub name: String,
     pub email: String,
-    pub password: String,
-    pub confirm_password: String,
+    pub password: Secret<String>,
+    pub confirm_password: Secret<String>,
     pub city: Option<String>,
     pub birth_date: Option<String>,
 }
 
+// Emails are compared case-insensitively, so they are always stored and looked up lowercased
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+impl Validate for RegisterDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        validation::required(&mut errors, "name", &self.name);
+        validation::max_length(&mut errors, "name", &self.name, 100);
+        validation::email(&mut errors, "email", &self.email);
+        validation::password_strength(&mut errors, "password", self.password.expose());
+        if self.password.expose() != self.confirm_password.expose() {
+            errors.add("confirm_password", "must match password");
+        }
+        if let Some(city) = &self.city {
+            validation::max_length(&mut errors, "city", city, 100);
+        }
+        if let Some(birth_date) = &self.birth_date {
+            validation::past_date(&mut errors, "birth_date", birth_date);
+        }
+
+        errors.into_result()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginDTO {
     pub email: String,
-    pub password: String,
+    pub password: Secret<String>,
+}
+
+impl Validate for LoginDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        validation::email(&mut errors, "email", &self.email);
+        validation::required(&mut errors, "password", self.password.expose());
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailQuery {
+    pub token: String,
+}
+
+impl Validate for VerifyEmailQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validation::required(&mut errors, "token", &self.token);
+        errors.into_result()
+    }
+}
+
+// Body of DELETE /me, the password is asked again so a stolen token cannot delete the account
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccountDTO {
+    pub password: Secret<String>,
+}
+
+impl Validate for DeleteAccountDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validation::required(&mut errors, "password", self.password.expose());
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountDeletionResponse {
+    pub deleted: bool,
+    pub plants_deleted: u64,
+    pub scheduled_for: Option<DateTime<Utc>>, // set when a grace period applies, log in before then to cancel
 }
 
 #[derive(Debug, Serialize)]
@@ -29,4 +104,5 @@ pub struct UserInfo {
     pub email: String,
     pub city: Option<String>,
     pub birth_date: Option<String>,
+    pub email_verified: bool,
 }
\ No newline at end of file
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct CalendarFeedResponse {
+    pub token: String,
+    pub url: String, // ready to paste into a calendar app's "subscribe by URL"
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarFeedQuery {
+    #[serde(default)]
+    pub todos: bool, // care tasks as VTODO instead of all-day VEVENTs
+}
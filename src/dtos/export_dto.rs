@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use crate::models::plant::Plant;
+use crate::models::user::SafeUser;
+
+// Bump when the shape changes so older files can still be recognised on import
+pub const GARDEN_EXPORT_VERSION: u32 = 1;
+
+// A user's whole garden, as written by `gardenary-admin export` and read back by `import`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GardenExport {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub user: SafeUser,
+    pub plants: Vec<Plant>,
+}
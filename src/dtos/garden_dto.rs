@@ -0,0 +1,48 @@
+use serde::Deserialize;
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+use crate::models::garden::{EDITOR, VIEWER};
+
+// Roles that can be handed out; every garden has exactly one owner, its creator
+pub const MEMBER_ROLES: [&str; 2] = [EDITOR, VIEWER];
+
+#[derive(Debug, Deserialize)]
+pub struct GardenDTO {
+    pub name: String,
+}
+
+impl Validate for GardenDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validation::required(&mut errors, "name", &self.name);
+        validation::max_length(&mut errors, "name", &self.name, 100);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InviteMemberDTO {
+    pub email: String,
+    pub role: String,
+}
+
+impl Validate for InviteMemberDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validation::email(&mut errors, "email", &self.email);
+        validation::one_of(&mut errors, "role", &self.role, &MEMBER_ROLES);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberDTO {
+    pub role: String,
+}
+
+impl Validate for UpdateMemberDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validation::one_of(&mut errors, "role", &self.role, &MEMBER_ROLES);
+        errors.into_result()
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::dtos::plant_dto::CreatePlantDTO;
+use crate::dtos::validation::ValidationErrors;
+use crate::models::plant::Plant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: TransferFormat,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    pub format: Option<TransferFormat>, // falls back to the Content-Type of the body
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// One row of an import file, CSV files use the same column names
+#[derive(Debug, Deserialize)]
+pub struct PlantImportRow {
+    pub external_id: Option<String>,
+    pub name: String,
+    pub plant_type: String,
+    pub image: Option<String>,
+    pub status: Option<serde_json::Value>, // care history, kept as-is when missing
+    #[serde(default)]
+    pub indoor: Option<bool>, // kept as-is when missing, new plants default to outdoor
+}
+
+impl PlantImportRow {
+    // Rows are validated with the same rules as plants created through the API
+    pub fn to_create_dto(&self, user_id: Uuid) -> CreatePlantDTO {
+        CreatePlantDTO {
+            name: self.name.clone(),
+            plant_type: self.plant_type.clone(),
+            image: self.image.clone(),
+            user_id,
+            external_id: self.external_id.clone(),
+            indoor: self.indoor.unwrap_or(false),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Created,
+    Updated,
+}
+
+// What an import did with one row and the plant it wrote, if it has one; Err is the row's database error
+pub type ImportOutcome = Result<(ImportAction, Option<Plant>), String>;
+
+#[derive(Debug, Serialize)]
+pub struct ImportRowError {
+    pub row: usize, // 1-based, data rows only (the CSV header is not counted)
+    pub external_id: Option<String>,
+    pub errors: ValidationErrors,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub committed: bool,
+    pub total: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportRowError>,
+}
@@ -0,0 +1,124 @@
+use serde::Deserialize;
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+
+pub const SEVERITIES: [&str; 3] = ["low", "medium", "high"];
+
+fn validate_issue_type(errors: &mut ValidationErrors, issue_type: &str) {
+    validation::required(errors, "issue_type", issue_type);
+    validation::max_length(errors, "issue_type", issue_type, 100);
+}
+
+// Issue types are free text but compared case-insensitively, "Aphids" and "aphids " are the same issue
+pub fn normalize_issue_type(issue_type: &str) -> String {
+    issue_type.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateIssueDTO {
+    pub issue_type: String,
+    pub severity: String, // "low", "medium", "high"
+    pub description: Option<String>,
+    pub opened_on: Option<String>, // format: "2025-07-15", defaults to today
+}
+
+impl Validate for CreateIssueDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        validate_issue_type(&mut errors, &self.issue_type);
+        validation::one_of(&mut errors, "severity", &self.severity, &SEVERITIES);
+        if let Some(description) = &self.description {
+            validation::max_length(&mut errors, "description", description, 2000);
+        }
+        if let Some(opened_on) = &self.opened_on {
+            validation::past_date(&mut errors, "opened_on", opened_on);
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateIssueDTO {
+    pub issue_type: Option<String>,
+    pub severity: Option<String>,
+    pub description: Option<String>,
+    pub opened_on: Option<String>,
+    pub resolved_on: Option<String>, // setting it resolves the issue
+    pub resolution: Option<String>,
+    #[serde(default)]
+    pub reopen: bool, // clears resolved_on and resolution
+}
+
+impl Validate for UpdateIssueDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if let Some(issue_type) = &self.issue_type {
+            validate_issue_type(&mut errors, issue_type);
+        }
+        if let Some(severity) = &self.severity {
+            validation::one_of(&mut errors, "severity", severity, &SEVERITIES);
+        }
+        if let Some(description) = &self.description {
+            validation::max_length(&mut errors, "description", description, 2000);
+        }
+        if let Some(opened_on) = &self.opened_on {
+            validation::past_date(&mut errors, "opened_on", opened_on);
+        }
+        if let Some(resolved_on) = &self.resolved_on {
+            validation::past_date(&mut errors, "resolved_on", resolved_on);
+        }
+        if let Some(resolution) = &self.resolution {
+            validation::max_length(&mut errors, "resolution", resolution, 2000);
+        }
+        if self.reopen && (self.resolved_on.is_some() || self.resolution.is_some()) {
+            errors.add("reopen", "cannot be combined with resolved_on or resolution");
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddTreatmentDTO {
+    pub date: Option<String>, // defaults to today
+    pub treatment: String,
+    pub notes: Option<String>,
+}
+
+impl Validate for AddTreatmentDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if let Some(date) = &self.date {
+            validation::past_date(&mut errors, "date", date);
+        }
+        validation::required(&mut errors, "treatment", &self.treatment);
+        validation::max_length(&mut errors, "treatment", &self.treatment, 200);
+        if let Some(notes) = &self.notes {
+            validation::max_length(&mut errors, "notes", notes, 2000);
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueListQuery {
+    pub open: Option<bool>, // true: only open, false: only resolved, missing: all
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AffectedPlantsQuery {
+    #[serde(rename = "type")]
+    pub issue_type: String,
+}
+
+impl Validate for AffectedPlantsQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validate_issue_type(&mut errors, &self.issue_type);
+        errors.into_result()
+    }
+}
@@ -1,2 +1,14 @@
 pub mod plant_dto;
-pub mod auth_dto;
\ No newline at end of file
+pub mod auth_dto;
+pub mod validation;
+pub mod export_dto;
+pub mod import_dto;
+pub mod calendar_dto;
+pub mod note_dto;
+pub mod issue_dto;
+pub mod alert_dto;
+pub mod sensor_dto;
+pub mod zone_dto;
+pub mod webhook_dto;
+pub mod garden_dto;
+pub mod share_dto;
\ No newline at end of file
@@ -0,0 +1,127 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::dtos::plant_dto::STATUS_TYPES;
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+
+pub const MAX_NOTE_TAGS: usize = 20;
+pub const DEFAULT_JOURNAL_LIMIT: i64 = 50;
+pub const MAX_JOURNAL_LIMIT: i64 = 200;
+
+fn validate_tags(errors: &mut ValidationErrors, tags: &[String]) {
+    if tags.len() > MAX_NOTE_TAGS {
+        errors.add("tags", format!("must contain at most {} tags", MAX_NOTE_TAGS));
+    }
+    for tag in tags {
+        validation::required(errors, "tags", tag);
+        validation::max_length(errors, "tags", tag, 30);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateNoteDTO {
+    pub date: Option<String>, // format: "2025-07-15", defaults to today
+    pub text: String, // e.g. "yellow leaves, reduced watering"
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub photo: Option<String>, // base64 or URL
+    pub care_action: Option<String>, // also marks the plant's status for the note's date
+}
+
+impl Validate for CreateNoteDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if let Some(date) = &self.date {
+            validation::date(&mut errors, "date", date);
+        }
+        validation::required(&mut errors, "text", &self.text);
+        validation::max_length(&mut errors, "text", &self.text, 5000);
+        validate_tags(&mut errors, &self.tags);
+        if let Some(care_action) = &self.care_action {
+            validation::one_of(&mut errors, "care_action", care_action, &STATUS_TYPES);
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateNoteDTO {
+    pub date: Option<String>,
+    pub text: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub photo: Option<String>,
+    pub care_action: Option<String>,
+}
+
+impl Validate for UpdateNoteDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if let Some(date) = &self.date {
+            validation::date(&mut errors, "date", date);
+        }
+        if let Some(text) = &self.text {
+            validation::required(&mut errors, "text", text);
+            validation::max_length(&mut errors, "text", text, 5000);
+        }
+        if let Some(tags) = &self.tags {
+            validate_tags(&mut errors, tags);
+        }
+        if let Some(care_action) = &self.care_action {
+            validation::one_of(&mut errors, "care_action", care_action, &STATUS_TYPES);
+        }
+
+        errors.into_result()
+    }
+}
+
+// GET /journal?q=yellow leaves&tag=pests&plant_id=...&from=2025-06-01&to=2025-06-30&limit=50&offset=0
+#[derive(Debug, Deserialize)]
+pub struct JournalQuery {
+    pub q: Option<String>, // full-text search on the note text
+    pub tag: Option<String>,
+    pub plant_id: Option<Uuid>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl JournalQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_JOURNAL_LIMIT).clamp(1, MAX_JOURNAL_LIMIT)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+
+    // Blank search terms are ignored rather than matching nothing
+    pub fn search(&self) -> Option<&str> {
+        self.q.as_deref().map(str::trim).filter(|q| !q.is_empty())
+    }
+
+    // Tags are stored lowercase
+    pub fn tag(&self) -> Option<String> {
+        self.tag.as_deref().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty())
+    }
+}
+
+impl Validate for JournalQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if let Some(from) = &self.from {
+            validation::date(&mut errors, "from", from);
+        }
+        if let Some(to) = &self.to {
+            validation::date(&mut errors, "to", to);
+        }
+        if let Some(q) = &self.q {
+            validation::max_length(&mut errors, "q", q, 200);
+        }
+
+        errors.into_result()
+    }
+}
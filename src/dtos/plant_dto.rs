@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+
+pub const STATUS_TYPES: [&str; 3] = ["watered", "fertilized", "harvested"];
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreatePlantDTO {
@@ -8,6 +10,27 @@ pub struct CreatePlantDTO {
     pub plant_type: String, // "Vegetable", "Fruit", "Herb", "Flower"
     pub image: Option<String>, // base64 or URL
     pub user_id: Uuid,
+    #[serde(default)]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub indoor: bool,
+}
+
+impl Validate for CreatePlantDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        validation::required(&mut errors, "name", &self.name);
+        validation::max_length(&mut errors, "name", &self.name, 100);
+        validation::required(&mut errors, "plant_type", &self.plant_type);
+        validation::max_length(&mut errors, "plant_type", &self.plant_type, 50);
+        if let Some(external_id) = &self.external_id {
+            validation::required(&mut errors, "external_id", external_id);
+            validation::max_length(&mut errors, "external_id", external_id, 100);
+        }
+
+        errors.into_result()
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -16,6 +39,29 @@ pub struct UpdatePlantDTO {
     pub plant_type: Option<String>,
     pub image: Option<String>,
     pub status: Option<serde_json::Value>,
+    pub indoor: Option<bool>,
+}
+
+impl Validate for UpdatePlantDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if let Some(name) = &self.name {
+            validation::required(&mut errors, "name", name);
+            validation::max_length(&mut errors, "name", name, 100);
+        }
+        if let Some(plant_type) = &self.plant_type {
+            validation::required(&mut errors, "plant_type", plant_type);
+            validation::max_length(&mut errors, "plant_type", plant_type, 50);
+        }
+        if let Some(status) = &self.status
+            && !status.is_object()
+        {
+            errors.add("status", "must be an object keyed by date");
+        }
+
+        errors.into_result()
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -24,3 +70,40 @@ pub struct UpdatePlantStatusDTO {
     pub status_type: String, // "watered", "fertilized", "harvested"
     pub value: bool,
 }
+
+impl Validate for UpdatePlantStatusDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        validation::date(&mut errors, "date", &self.date);
+        validation::one_of(&mut errors, "status_type", &self.status_type, &STATUS_TYPES);
+
+        errors.into_result()
+    }
+}
+
+pub const CARE_SOURCES: [&str; 3] = ["manual", "sensor", "zone"];
+const DEFAULT_CARE_EVENT_LIMIT: i64 = 50;
+const MAX_CARE_EVENT_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct CareEventQuery {
+    pub source: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl CareEventQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_CARE_EVENT_LIMIT).clamp(1, MAX_CARE_EVENT_LIMIT)
+    }
+}
+
+impl Validate for CareEventQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(source) = &self.source {
+            validation::one_of(&mut errors, "source", source, &CARE_SOURCES);
+        }
+        errors.into_result()
+    }
+}
@@ -0,0 +1,159 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+
+pub const MAX_INGEST_BATCH: usize = 500;
+pub const MAX_READING_BUCKETS: i64 = 2000;
+const DEFAULT_READINGS_RANGE_HOURS: i64 = 24;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDeviceDTO {
+    pub name: String,
+}
+
+impl Validate for CreateDeviceDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validation::required(&mut errors, "name", &self.name);
+        validation::max_length(&mut errors, "name", &self.name, 100);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct IngestReadingDTO {
+    pub plant_id: Uuid,
+    pub recorded_at: Option<DateTime<Utc>>, // time of receipt when the device has no clock
+    pub moisture_pct: Option<f64>,
+    pub temperature_c: Option<f64>,
+    pub light_lux: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IngestBatchDTO {
+    pub readings: Vec<IngestReadingDTO>,
+}
+
+impl Validate for IngestBatchDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if self.readings.is_empty() || self.readings.len() > MAX_INGEST_BATCH {
+            errors.add("readings", format!("must contain between 1 and {} readings", MAX_INGEST_BATCH));
+        }
+        // Devices with a drifting clock get a few minutes of slack
+        let latest = Utc::now() + Duration::minutes(5);
+        for (i, reading) in self.readings.iter().enumerate() {
+            if reading.moisture_pct.is_none() && reading.temperature_c.is_none() && reading.light_lux.is_none() {
+                errors.add("readings", format!("reading {}: needs at least one of moisture_pct, temperature_c, light_lux", i));
+            }
+            if let Some(moisture) = reading.moisture_pct && !(0.0..=100.0).contains(&moisture) {
+                errors.add("readings", format!("reading {}: moisture_pct must be between 0 and 100", i));
+            }
+            if let Some(temperature) = reading.temperature_c && !(-60.0..=80.0).contains(&temperature) {
+                errors.add("readings", format!("reading {}: temperature_c must be between -60 and 80", i));
+            }
+            if let Some(light) = reading.light_lux && !(light >= 0.0 && light.is_finite()) {
+                errors.add("readings", format!("reading {}: light_lux must not be negative", i));
+            }
+            if let Some(recorded_at) = reading.recorded_at && recorded_at > latest {
+                errors.add("readings", format!("reading {}: recorded_at must not be in the future", i));
+            }
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReadingsQuery {
+    pub from: Option<String>, // RFC 3339 timestamp or YYYY-MM-DD, defaults to 24 hours before `to`
+    pub to: Option<String>, // defaults to now
+    pub bucket: Option<String>, // e.g. 15m, 1h, 1d; defaults to 1h
+}
+
+// "2025-07-15T08:00:00Z" or "2025-07-15" (midnight UTC)
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok().map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc()))
+}
+
+// "<n>m", "<n>h" or "<n>d" in seconds, between one minute and a week
+fn parse_bucket(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let unit_at = value.len().checked_sub(1).filter(|i| value.is_char_boundary(*i))?;
+    let (amount, unit) = value.split_at(unit_at);
+    let amount: i64 = amount.parse().ok()?;
+    let seconds = amount.checked_mul(match unit {
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    })?;
+    (60..=7 * 86400).contains(&seconds).then_some(seconds)
+}
+
+impl ReadingsQuery {
+    // Only called after validate()
+    pub fn range(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let to = self.to.as_deref().and_then(parse_time).unwrap_or_else(Utc::now);
+        let from = self.from.as_deref()
+            .and_then(parse_time)
+            .unwrap_or_else(|| to - Duration::hours(DEFAULT_READINGS_RANGE_HOURS));
+        (from, to)
+    }
+
+    pub fn bucket_secs(&self) -> i64 {
+        self.bucket.as_deref().and_then(parse_bucket).unwrap_or(3600)
+    }
+}
+
+impl Validate for ReadingsQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        // "+" in a query string decodes to a space, so offsets have to be sent as %2B or as Z
+        for (field, value) in [("from", &self.from), ("to", &self.to)] {
+            if let Some(value) = value && parse_time(value).is_none() {
+                errors.add(field, "must be an RFC 3339 timestamp (e.g. 2025-07-15T08:00:00Z) or a date in YYYY-MM-DD format");
+            }
+        }
+        if let Some(bucket) = &self.bucket && parse_bucket(bucket).is_none() {
+            errors.add("bucket", "must be a number of minutes, hours or days like 15m, 1h or 1d, between 1m and 7d");
+        }
+        if !errors.is_empty() {
+            return errors.into_result();
+        }
+
+        let (from, to) = self.range();
+        if from >= to {
+            errors.add("from", "must be before to");
+        } else if (to - from).num_seconds() / self.bucket_secs() > MAX_READING_BUCKETS {
+            errors.add("bucket", format!("too small for this range, at most {} buckets are returned", MAX_READING_BUCKETS));
+        }
+        errors.into_result()
+    }
+}
+
+// Replaces the plant's thresholds; null removes one
+#[derive(Debug, Deserialize)]
+pub struct UpdateThresholdsDTO {
+    pub moisture_min_pct: Option<f64>,
+    pub watering_rise_pct: Option<f64>,
+}
+
+impl Validate for UpdateThresholdsDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(min) = self.moisture_min_pct && !(0.0..=100.0).contains(&min) {
+            errors.add("moisture_min_pct", "must be between 0 and 100");
+        }
+        if let Some(rise) = self.watering_rise_pct && !(rise > 0.0 && rise <= 100.0) {
+            errors.add("watering_rise_pct", "must be above 0 and at most 100");
+        }
+        errors.into_result()
+    }
+}
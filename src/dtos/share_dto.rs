@@ -0,0 +1,21 @@
+use serde::Deserialize;
+use crate::dtos::validation::{Validate, ValidationErrors};
+
+pub const MAX_SHARE_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize)]
+pub struct ShareQuery {
+    pub expires_in_days: Option<i64>, // no expiry when left out
+}
+
+impl Validate for ShareQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(days) = self.expires_in_days
+            && !(1..=MAX_SHARE_DAYS).contains(&days)
+        {
+            errors.add("expires_in_days", format!("must be between 1 and {}", MAX_SHARE_DAYS));
+        }
+        errors.into_result()
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Field name -> list of messages, serialized as a plain JSON object
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct ValidationErrors(BTreeMap<&'static str, Vec<String>>);
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &'static str, message: impl Into<String>) {
+        self.0.entry(field).or_default().push(message.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+}
+
+// Implemented by every request DTO, called by the handlers before the services
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+// ========== RULES ==========
+
+pub fn required(errors: &mut ValidationErrors, field: &'static str, value: &str) {
+    if value.trim().is_empty() {
+        errors.add(field, "is required");
+    }
+}
+
+pub fn max_length(errors: &mut ValidationErrors, field: &'static str, value: &str, max: usize) {
+    if value.chars().count() > max {
+        errors.add(field, format!("must be at most {} characters", max));
+    }
+}
+
+pub fn email(errors: &mut ValidationErrors, field: &'static str, value: &str) {
+    if !is_valid_email(value) {
+        errors.add(field, "must be a valid email address");
+    }
+}
+
+pub fn password_strength(errors: &mut ValidationErrors, field: &'static str, value: &str) {
+    if value.chars().count() < 8 {
+        errors.add(field, "must be at least 8 characters");
+    }
+    if !value.chars().any(|c| c.is_alphabetic()) || !value.chars().any(|c| c.is_ascii_digit()) {
+        errors.add(field, "must contain at least one letter and one number");
+    }
+}
+
+// Dates are exchanged as "YYYY-MM-DD" everywhere in the API
+pub fn date(errors: &mut ValidationErrors, field: &'static str, value: &str) -> Option<NaiveDate> {
+    match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => Some(date),
+        Err(_) => {
+            errors.add(field, "must be a date in YYYY-MM-DD format");
+            None
+        }
+    }
+}
+
+pub fn past_date(errors: &mut ValidationErrors, field: &'static str, value: &str) {
+    if let Some(date) = date(errors, field, value)
+        && date > Utc::now().date_naive()
+    {
+        errors.add(field, "must not be in the future");
+    }
+}
+
+pub fn one_of(errors: &mut ValidationErrors, field: &'static str, value: &str, allowed: &[&str]) {
+    if !allowed.contains(&value) {
+        errors.add(field, format!("must be one of: {}", allowed.join(", ")));
+    }
+}
+
+fn is_valid_email(value: &str) -> bool {
+    if value.len() > 254 || value.chars().any(char::is_whitespace) {
+        return false;
+    }
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use std::net::IpAddr;
+use reqwest::Url;
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+
+pub const WEBHOOK_EVENTS: [&str; 5] = ["plant.created", "plant.updated", "plant.deleted", "care.recorded", "harvest.recorded"];
+pub const DELIVERY_STATUSES: [&str; 3] = ["pending", "delivered", "failed"];
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+const MAX_DELIVERY_LIMIT: i64 = 500;
+
+// Anything but loopback, private (RFC 1918, unique local), link-local (where cloud metadata
+// services such as 169.254.169.254 live), shared, reserved and multicast addresses
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            !(v4.is_loopback() || v4.is_private() || v4.is_link_local() || v4.is_unspecified()
+                || v4.is_broadcast() || v4.is_documentation() || v4.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT
+                || (a == 192 && b == 0 && c == 0)
+                || a >= 240)
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback() || v6.is_unspecified() || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00 // unique local
+                || (first & 0xffc0) == 0xfe80) // link-local
+        }
+    }
+}
+
+// Webhooks are sent from inside our network, so they must not be pointed back into it. Host names
+// are checked again when they are resolved for each delivery, since DNS can change later.
+pub fn check_target(url: &Url) -> Result<(), &'static str> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("must be an http:// or https:// URL");
+    }
+    let Some(host) = url.host_str() else {
+        return Err("must be an http:// or https:// URL");
+    };
+    // IPv6 literals come bracketed, e.g. [::1]
+    let host = host.trim_start_matches('[').trim_end_matches(']').trim_end_matches('.').to_ascii_lowercase();
+    let public = match host.parse::<IpAddr>() {
+        Ok(ip) => is_public_ip(ip),
+        Err(_) => !(host == "localhost" || [".localhost", ".local", ".internal"].iter().any(|s| host.ends_with(s))),
+    };
+    if public { Ok(()) } else { Err("must point to a public host, not a loopback, private or link-local address") }
+}
+
+fn validate_url(errors: &mut ValidationErrors, url: &str) {
+    validation::required(errors, "url", url);
+    validation::max_length(errors, "url", url, 2000);
+    let url = url.trim();
+    if url.is_empty() {
+        return;
+    }
+    match Url::parse(url) {
+        Ok(url) => {
+            if let Err(message) = check_target(&url) {
+                errors.add("url", message);
+            }
+        }
+        Err(_) => errors.add("url", "must be an http:// or https:// URL"),
+    }
+}
+
+fn validate_events(errors: &mut ValidationErrors, events: &[String]) {
+    if events.is_empty() {
+        errors.add("events", format!("must contain at least one of {}", WEBHOOK_EVENTS.join(", ")));
+    }
+    for event in events {
+        if !WEBHOOK_EVENTS.contains(&event.as_str()) {
+            errors.add("events", format!("unknown event {:?}, expected one of {}", event, WEBHOOK_EVENTS.join(", ")));
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookDTO {
+    pub url: String,
+    pub events: Option<Vec<String>>, // defaults to every event
+}
+
+impl Validate for CreateWebhookDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validate_url(&mut errors, &self.url);
+        if let Some(events) = &self.events {
+            validate_events(&mut errors, events);
+        }
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookDTO {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>, // replaces the whole list
+    pub active: Option<bool>, // inactive webhooks get no new deliveries
+}
+
+impl Validate for UpdateWebhookDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(url) = &self.url {
+            validate_url(&mut errors, url);
+        }
+        if let Some(events) = &self.events {
+            validate_events(&mut errors, events);
+        }
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl DeliveriesQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT).clamp(1, MAX_DELIVERY_LIMIT)
+    }
+}
+
+impl Validate for DeliveriesQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(status) = &self.status {
+            validation::one_of(&mut errors, "status", status, &DELIVERY_STATUSES);
+        }
+        errors.into_result()
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::dtos::validation::{self, Validate, ValidationErrors};
+
+pub const MAX_RUN_MINUTES: i32 = 120;
+pub const DEFAULT_RUN_MINUTES: i32 = 10;
+const MAX_ZONE_PLANTS: usize = 100;
+const DEFAULT_RUN_LIMIT: i64 = 20;
+const MAX_RUN_LIMIT: i64 = 200;
+
+fn validate_minutes(errors: &mut ValidationErrors, field: &'static str, minutes: i32) {
+    if !(1..=MAX_RUN_MINUTES).contains(&minutes) {
+        errors.add(field, format!("must be between 1 and {}", MAX_RUN_MINUTES));
+    }
+}
+
+fn validate_plant_ids(errors: &mut ValidationErrors, plant_ids: &[Uuid]) {
+    if plant_ids.len() > MAX_ZONE_PLANTS {
+        errors.add("plant_ids", format!("must contain at most {} plants", MAX_ZONE_PLANTS));
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateZoneDTO {
+    pub name: String,
+    pub valve: String,
+    #[serde(default)]
+    pub plant_ids: Vec<Uuid>,
+    pub run_minutes: Option<i32>, // defaults to 10
+    #[serde(default)]
+    pub scheduled: bool,
+}
+
+impl Validate for CreateZoneDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        validation::required(&mut errors, "name", &self.name);
+        validation::max_length(&mut errors, "name", &self.name, 100);
+        validation::required(&mut errors, "valve", &self.valve);
+        validation::max_length(&mut errors, "valve", &self.valve, 200);
+        validate_plant_ids(&mut errors, &self.plant_ids);
+        if let Some(minutes) = self.run_minutes {
+            validate_minutes(&mut errors, "run_minutes", minutes);
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateZoneDTO {
+    pub name: Option<String>,
+    pub valve: Option<String>,
+    pub plant_ids: Option<Vec<Uuid>>, // replaces the whole list
+    pub run_minutes: Option<i32>,
+    pub scheduled: Option<bool>,
+}
+
+impl Validate for UpdateZoneDTO {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if let Some(name) = &self.name {
+            validation::required(&mut errors, "name", name);
+            validation::max_length(&mut errors, "name", name, 100);
+        }
+        if let Some(valve) = &self.valve {
+            validation::required(&mut errors, "valve", valve);
+            validation::max_length(&mut errors, "valve", valve, 200);
+        }
+        if let Some(plant_ids) = &self.plant_ids {
+            validate_plant_ids(&mut errors, plant_ids);
+        }
+        if let Some(minutes) = self.run_minutes {
+            validate_minutes(&mut errors, "run_minutes", minutes);
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunZoneQuery {
+    pub minutes: Option<i32>, // defaults to the zone's run_minutes
+}
+
+impl Validate for RunZoneQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(minutes) = self.minutes {
+            validate_minutes(&mut errors, "minutes", minutes);
+        }
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ZoneRunsQuery {
+    pub limit: Option<i64>,
+}
+
+impl ZoneRunsQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_RUN_LIMIT).clamp(1, MAX_RUN_LIMIT)
+    }
+}
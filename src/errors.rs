@@ -0,0 +1,34 @@
+use crate::dtos::validation::ValidationErrors;
+use std::fmt;
+
+// Errors that need a specific HTTP status; everything else stays a plain message
+#[derive(Debug)]
+pub enum ServiceError {
+    Conflict(String),
+    Unauthorized(String),
+    Forbidden(String),
+    TooManyRequests { retry_after: u64 },
+    // Input that only the service can check, e.g. against the database; answered like a DTO validation failure
+    Validation(ValidationErrors),
+    Other(String),
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceError::Conflict(msg) | ServiceError::Unauthorized(msg) | ServiceError::Forbidden(msg) | ServiceError::Other(msg) => {
+                write!(f, "{}", msg)
+            }
+            ServiceError::TooManyRequests { retry_after } => {
+                write!(f, "Too many failed login attempts, try again in {} seconds", retry_after)
+            }
+            ServiceError::Validation(_) => write!(f, "Validation failed"),
+        }
+    }
+}
+
+impl From<String> for ServiceError {
+    fn from(msg: String) -> Self {
+        ServiceError::Other(msg)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,90 @@
+use actix_web::{get, web, HttpResponse, Responder, HttpRequest};
+use crate::services::alert_service::AlertService;
+use crate::dtos::alert_dto::AlertListQuery;
+use crate::dtos::validation::Validate;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+
+// ========== POSTGRES ==========
+
+// Frost, heat and dry soil alerts for the user's plants, from today unless ?from= is given
+#[get("/pg/alerts")]
+pub async fn get_pg_alerts(
+    req: HttpRequest,
+    svc: web::Data<AlertService>,
+    query: web::Query<AlertListQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.get_all_pg(user_id, &query).await {
+        Ok(alerts) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} alerts found", alerts.len()),
+            data: Some(alerts),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// ========== SUPABASE ==========
+
+// Frost, heat and dry soil alerts for the user's plants, from today unless ?from= is given
+#[get("/sb/alerts")]
+pub async fn get_sb_alerts(
+    req: HttpRequest,
+    svc: web::Data<AlertService>,
+    query: web::Query<AlertListQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.get_all_sb(user_id, &query).await {
+        Ok(alerts) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} alerts found (Supabase)", alerts.len()),
+            data: Some(alerts),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
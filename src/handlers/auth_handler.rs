@@ -1,6 +1,11 @@
-use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+use actix_web::{get, post, delete, web, HttpRequest, HttpResponse, Responder};
 use crate::services::auth_service::AuthService;
-use crate::dtos::auth_dto::{RegisterDTO, LoginDTO,};
+use crate::services::plant_service::PlantService;
+use crate::services::account_export::{build_account_zip, AccountExportService};
+use crate::dtos::auth_dto::{RegisterDTO, LoginDTO, VerifyEmailQuery, DeleteAccountDTO};
+use crate::dtos::calendar_dto::CalendarFeedResponse;
+use crate::dtos::validation::Validate;
+use crate::errors::ServiceError;
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -12,16 +17,26 @@ struct ApiResponse<T> {
 
 // Helper function to extract token from Authorization header
 fn extract_token(req: &HttpRequest) -> Result<String, String> {
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if auth_str.starts_with("Bearer ") {
-                return Ok(auth_str[7..].to_string());
-            }
-        }
+    if let Some(auth_header) = req.headers().get("authorization")
+        && let Ok(auth_str) = auth_header.to_str()
+        && let Some(token) = auth_str.strip_prefix("Bearer ")
+    {
+        return Ok(token.to_string());
     }
     Err("Missing or invalid authorization header".to_string())
 }
 
+// The public feed lives under the same backend prefix as the account, e.g. "/pg"
+fn calendar_feed(svc: &AuthService, backend: &str, token: String) -> CalendarFeedResponse {
+    let url = format!("{}/{}/calendar/{}.ics", svc.app_base_url.trim_end_matches('/'), backend, token);
+    CalendarFeedResponse { token, url }
+}
+
+// Peer address of the connection; proxy headers are not trusted since they are client controlled
+fn client_ip(req: &HttpRequest) -> Option<String> {
+    req.peer_addr().map(|addr| addr.ip().to_string())
+}
+
 // ========== POSTGRES ==========
 
 #[post("/pg/auth/register")]
@@ -29,36 +44,71 @@ pub async fn register_pg(
     svc: web::Data<AuthService>,
     body: web::Json<RegisterDTO>
 ) -> impl Responder {
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
     match svc.register_pg(body.0).await {
         Ok(response) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "User registered successfully".to_string(),
             data: Some(response),
         }),
-        Err(err) => HttpResponse::BadRequest().json(ApiResponse::<()> {
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
             data: None,
         }),
+        Err(err) => HttpResponse::BadRequest().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
     }
 }
 
 #[post("/pg/auth/login")]
 pub async fn login_pg(
     svc: web::Data<AuthService>,
+    req: HttpRequest,
     body: web::Json<LoginDTO>
 ) -> impl Responder {
-    match svc.login_pg(&body.email, &body.password).await {
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let ip = client_ip(&req);
+    match svc.login_pg(&body.email, body.password.expose(), ip.as_deref()).await {
         Ok(response) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "Login successful".to_string(),
             data: Some(response),
         }),
-        Err(err) => HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        Err(err @ ServiceError::TooManyRequests { retry_after }) => HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.to_string()))
+            .json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err.to_string(),
+                data: None,
+            }),
+        Err(ServiceError::Unauthorized(err)) => HttpResponse::Unauthorized().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
             data: None,
         }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
     }
 }
 
@@ -99,43 +149,316 @@ pub async fn get_me_pg(
     }
 }
 
+#[get("/pg/auth/verify")]
+pub async fn verify_email_pg(
+    svc: web::Data<AuthService>,
+    query: web::Query<VerifyEmailQuery>
+) -> impl Responder {
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.verify_email_pg(&query.token).await {
+        Ok(user) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Email verified successfully".to_string(),
+            data: Some(user),
+        }),
+        Err(err) => HttpResponse::BadRequest().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[delete("/pg/auth/me")]
+pub async fn delete_me_pg(
+    svc: web::Data<AuthService>,
+    req: HttpRequest,
+    body: web::Json<DeleteAccountDTO>
+) -> impl Responder {
+    let token = match extract_token(&req) {
+        Ok(token) => token,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user_id = match svc.verify_token(&token) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.delete_account_pg(user_id, body.password.expose()).await {
+        Ok(response) if response.deleted => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Account deleted".to_string(),
+            data: Some(response),
+        }),
+        Ok(response) => HttpResponse::Accepted().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Account scheduled for deletion, log in again to cancel".to_string(),
+            data: Some(response),
+        }),
+        Err(err @ ServiceError::TooManyRequests { retry_after }) => HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.to_string()))
+            .json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err.to_string(),
+                data: None,
+            }),
+        Err(ServiceError::Unauthorized(err)) => HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/auth/me/export")]
+pub async fn export_me_pg(
+    svc: web::Data<AuthService>,
+    plant_svc: web::Data<PlantService>,
+    export_svc: web::Data<AccountExportService>,
+    req: HttpRequest
+) -> impl Responder {
+    let token = match extract_token(&req) {
+        Ok(token) => token,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user_id = match svc.verify_token(&token) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user = match svc.get_user_by_id_pg(user_id).await {
+        Ok(user) => user,
+        Err(err) => return HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let plants = match plant_svc.get_all_pg(user_id).await {
+        Ok(plants) => plants,
+        Err(err) => return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let data = match export_svc.collect_pg(user_id, &plants).await {
+        Ok(data) => data,
+        Err(err) => return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    // Decoding photos and compressing is CPU work, keep it off the async workers
+    match web::block(move || build_account_zip(&user, &plants, &data)).await {
+        Ok(Ok(zip)) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"gardenary-export-{}.zip\"", chrono::Utc::now().format("%Y-%m-%d")),
+            ))
+            .body(zip),
+        Ok(Err(err)) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
 // ========== SUPABASE ==========
 
+#[get("/pg/auth/me/calendar")]
+pub async fn get_calendar_feed_pg(
+    svc: web::Data<AuthService>,
+    req: HttpRequest
+) -> impl Responder {
+    let token = match extract_token(&req) {
+        Ok(token) => token,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user_id = match svc.verify_token(&token) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    match svc.calendar_token_pg(user_id, false).await {
+        Ok(feed_token) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Calendar feed retrieved successfully".to_string(),
+            data: Some(calendar_feed(&svc, "pg", feed_token)),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[post("/pg/auth/me/calendar/regenerate")]
+pub async fn regenerate_calendar_feed_pg(
+    svc: web::Data<AuthService>,
+    req: HttpRequest
+) -> impl Responder {
+    let token = match extract_token(&req) {
+        Ok(token) => token,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user_id = match svc.verify_token(&token) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    match svc.calendar_token_pg(user_id, true).await {
+        Ok(feed_token) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Calendar feed regenerated, the old URL no longer works".to_string(),
+            data: Some(calendar_feed(&svc, "pg", feed_token)),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
 #[post("/sb/auth/register")]
 pub async fn register_sb(
     svc: web::Data<AuthService>,
     body: web::Json<RegisterDTO>
 ) -> impl Responder {
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
     match svc.register_sb(body.0).await {
         Ok(response) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "User registered successfully (Supabase)".to_string(),
             data: Some(response),
         }),
-        Err(err) => HttpResponse::BadRequest().json(ApiResponse::<()> {
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
             data: None,
         }),
+        Err(err) => HttpResponse::BadRequest().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
     }
 }
 
 #[post("/sb/auth/login")]
 pub async fn login_sb(
     svc: web::Data<AuthService>,
+    req: HttpRequest,
     body: web::Json<LoginDTO>
 ) -> impl Responder {
-    match svc.login_sb(&body.email, &body.password).await {
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let ip = client_ip(&req);
+    match svc.login_sb(&body.email, body.password.expose(), ip.as_deref()).await {
         Ok(response) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "Login successful (Supabase)".to_string(),
             data: Some(response),
         }),
-        Err(err) => HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        Err(err @ ServiceError::TooManyRequests { retry_after }) => HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.to_string()))
+            .json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err.to_string(),
+                data: None,
+            }),
+        Err(ServiceError::Unauthorized(err)) => HttpResponse::Unauthorized().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
             data: None,
         }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
     }
 }
 
@@ -174,4 +497,242 @@ pub async fn get_me_sb(
             data: None,
         }),
     }
-}
\ No newline at end of file
+}
+
+#[get("/sb/auth/verify")]
+pub async fn verify_email_sb(
+    svc: web::Data<AuthService>,
+    query: web::Query<VerifyEmailQuery>
+) -> impl Responder {
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.verify_email_sb(&query.token).await {
+        Ok(user) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Email verified successfully (Supabase)".to_string(),
+            data: Some(user),
+        }),
+        Err(err) => HttpResponse::BadRequest().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[delete("/sb/auth/me")]
+pub async fn delete_me_sb(
+    svc: web::Data<AuthService>,
+    req: HttpRequest,
+    body: web::Json<DeleteAccountDTO>
+) -> impl Responder {
+    let token = match extract_token(&req) {
+        Ok(token) => token,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user_id = match svc.verify_token(&token) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.delete_account_sb(user_id, body.password.expose()).await {
+        Ok(response) if response.deleted => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Account deleted (Supabase)".to_string(),
+            data: Some(response),
+        }),
+        Ok(response) => HttpResponse::Accepted().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Account scheduled for deletion, log in again to cancel (Supabase)".to_string(),
+            data: Some(response),
+        }),
+        Err(err @ ServiceError::TooManyRequests { retry_after }) => HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.to_string()))
+            .json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err.to_string(),
+                data: None,
+            }),
+        Err(ServiceError::Unauthorized(err)) => HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/auth/me/export")]
+pub async fn export_me_sb(
+    svc: web::Data<AuthService>,
+    plant_svc: web::Data<PlantService>,
+    export_svc: web::Data<AccountExportService>,
+    req: HttpRequest
+) -> impl Responder {
+    let token = match extract_token(&req) {
+        Ok(token) => token,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user_id = match svc.verify_token(&token) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user = match svc.get_user_by_id_sb(user_id).await {
+        Ok(user) => user,
+        Err(err) => return HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let plants = match plant_svc.get_all_sb(user_id).await {
+        Ok(plants) => plants,
+        Err(err) => return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let data = match export_svc.collect_sb(user_id, &plants).await {
+        Ok(data) => data,
+        Err(err) => return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    // Decoding photos and compressing is CPU work, keep it off the async workers
+    match web::block(move || build_account_zip(&user, &plants, &data)).await {
+        Ok(Ok(zip)) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"gardenary-export-{}.zip\"", chrono::Utc::now().format("%Y-%m-%d")),
+            ))
+            .body(zip),
+        Ok(Err(err)) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/auth/me/calendar")]
+pub async fn get_calendar_feed_sb(
+    svc: web::Data<AuthService>,
+    req: HttpRequest
+) -> impl Responder {
+    let token = match extract_token(&req) {
+        Ok(token) => token,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user_id = match svc.verify_token(&token) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    match svc.calendar_token_sb(user_id, false).await {
+        Ok(feed_token) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Calendar feed retrieved successfully (Supabase)".to_string(),
+            data: Some(calendar_feed(&svc, "sb", feed_token)),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[post("/sb/auth/me/calendar/regenerate")]
+pub async fn regenerate_calendar_feed_sb(
+    svc: web::Data<AuthService>,
+    req: HttpRequest
+) -> impl Responder {
+    let token = match extract_token(&req) {
+        Ok(token) => token,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    let user_id = match svc.verify_token(&token) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    };
+
+    match svc.calendar_token_sb(user_id, true).await {
+        Ok(feed_token) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Calendar feed regenerated, the old URL no longer works (Supabase)".to_string(),
+            data: Some(calendar_feed(&svc, "sb", feed_token)),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
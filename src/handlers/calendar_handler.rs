@@ -0,0 +1,111 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use chrono::{Duration, Utc};
+use crate::services::auth_service::AuthService;
+use crate::services::plant_service::PlantService;
+use crate::services::care_schedule::upcoming_tasks;
+use crate::services::ical::{render_feed, FEED_HORIZON_DAYS};
+use crate::services::weather::WeatherService;
+use crate::dtos::calendar_dto::CalendarFeedQuery;
+use crate::models::plant::ApiResponse;
+
+// Public iCal feeds, authenticated by the secret token in the URL so calendar apps can subscribe
+
+#[get("/pg/calendar/{token}.ics")]
+pub async fn pg_calendar_feed(
+    auth_svc: web::Data<AuthService>,
+    plant_svc: web::Data<PlantService>,
+    weather: web::Data<WeatherService>,
+    path: web::Path<String>,
+    query: web::Query<CalendarFeedQuery>
+) -> impl Responder {
+    // The token is the only credential, unknown and revoked tokens look the same
+    let user = match auth_svc.user_by_calendar_token_pg(&path.into_inner()).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: "Calendar feed not found".to_string(),
+                data: None,
+            });
+        }
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let plants = match plant_svc.get_all_pg(user.id).await {
+        Ok(plants) => plants,
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let today = Utc::now().date_naive();
+    let mut tasks = upcoming_tasks(&plants, today, FEED_HORIZON_DAYS);
+    let forecast = weather.daily(user.city.as_deref(), today, today + Duration::days(FEED_HORIZON_DAYS)).await;
+    weather.mark_rain_skips(&mut tasks, &plants, &forecast, today);
+
+    HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header(("Content-Disposition", "inline; filename=\"gardenary.ics\""))
+        .insert_header(("Cache-Control", "private, max-age=900"))
+        .body(render_feed(&user, &plants, &tasks, query.todos))
+}
+
+#[get("/sb/calendar/{token}.ics")]
+pub async fn sb_calendar_feed(
+    auth_svc: web::Data<AuthService>,
+    plant_svc: web::Data<PlantService>,
+    weather: web::Data<WeatherService>,
+    path: web::Path<String>,
+    query: web::Query<CalendarFeedQuery>
+) -> impl Responder {
+    // The token is the only credential, unknown and revoked tokens look the same
+    let user = match auth_svc.user_by_calendar_token_sb(&path.into_inner()).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: "Calendar feed not found (Supabase)".to_string(),
+                data: None,
+            });
+        }
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let plants = match plant_svc.get_all_sb(user.id).await {
+        Ok(plants) => plants,
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let today = Utc::now().date_naive();
+    let mut tasks = upcoming_tasks(&plants, today, FEED_HORIZON_DAYS);
+    let forecast = weather.daily(user.city.as_deref(), today, today + Duration::days(FEED_HORIZON_DAYS)).await;
+    weather.mark_rain_skips(&mut tasks, &plants, &forecast, today);
+
+    HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header(("Content-Disposition", "inline; filename=\"gardenary.ics\""))
+        .insert_header(("Cache-Control", "private, max-age=900"))
+        .body(render_feed(&user, &plants, &tasks, query.todos))
+}
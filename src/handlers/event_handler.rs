@@ -0,0 +1,61 @@
+use actix_web::{get, web, HttpResponse, Responder, HttpRequest};
+use actix_web::web::Bytes;
+use futures_util::stream;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+use crate::services::event_bus::{EventBus, LiveEvent};
+
+// Proxies close connections that stay silent for too long
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+fn frame(event: &LiveEvent) -> Bytes {
+    let data = serde_json::to_string(event).unwrap_or_default();
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event.event, data))
+}
+
+// Server-Sent Events with changes to the user's plants and those of their gardens, for both backends,
+// whether made by hand, by a note, a sensor or a zone run; authenticated with the usual verified JWT:
+//   event: plant.updated
+//   data: {"event":"plant.updated","data":{...the plant...},"at":"..."}
+// A "resync" event means some changes were missed and the dashboard should reload
+#[get("/events")]
+pub async fn event_stream(
+    req: HttpRequest,
+    bus: web::Data<EventBus>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    // The first keep-alive tick fires right away, so clients see the stream open
+    let state = (bus.subscribe(), actix_web::rt::time::interval(KEEP_ALIVE));
+    let events = stream::unfold(state, move |(mut rx, mut keep_alive)| async move {
+        loop {
+            let chunk = tokio::select! {
+                _ = keep_alive.tick() => Bytes::from_static(b": keep-alive\n\n"),
+                received = rx.recv() => match received {
+                    Ok(event) if event.audience.contains(&user_id) => frame(&event),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) => Bytes::from_static(b"event: resync\ndata: {}\n\n"),
+                    Err(RecvError::Closed) => return None,
+                },
+            };
+            return Some((Ok::<_, actix_web::Error>(chunk), (rx, keep_alive)));
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no")) // nginx would otherwise hold events back
+        .streaming(events)
+}
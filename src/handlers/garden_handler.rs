@@ -0,0 +1,1448 @@
+use actix_web::{get, post, put, delete, web, HttpResponse, Responder, HttpRequest};
+use uuid::Uuid;
+use crate::services::garden_service::GardenService;
+use crate::dtos::garden_dto::{GardenDTO, InviteMemberDTO, UpdateMemberDTO};
+use crate::dtos::validation::Validate;
+use crate::errors::ServiceError;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+
+// ========== POSTGRES ==========
+
+// The creator becomes the garden's owner
+#[post("/pg/gardens")]
+pub async fn add_pg_garden(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    body: web::Json<GardenDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_pg(user_id, body.into_inner()).await {
+        Ok(garden) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Garden created successfully".to_string(),
+            data: Some(garden),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Every garden the user is a member of, with their role in it
+#[get("/pg/gardens")]
+pub async fn get_all_pg_gardens(
+    req: HttpRequest,
+    svc: web::Data<GardenService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_pg(user_id).await {
+        Ok(gardens) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} gardens found", gardens.len()),
+            data: Some(gardens),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/gardens/{id}")]
+pub async fn get_pg_garden_by_id(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_by_id_pg(id.into_inner(), user_id).await {
+        Ok(Some(garden)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Garden found".to_string(),
+            data: Some(garden),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/pg/gardens/{id}")]
+pub async fn update_pg_garden(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>,
+    body: web::Json<GardenDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.rename_pg(id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(garden)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Garden updated successfully".to_string(),
+            data: Some(garden),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// Owner only; the plants stay with their owners
+#[delete("/pg/gardens/{id}")]
+pub async fn delete_pg_garden(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.delete_pg(id.into_inner(), user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Garden deleted successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/gardens/{id}/members")]
+pub async fn get_pg_garden_members(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.members_pg(id.into_inner(), user_id).await {
+        Ok(Some(members)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} members found", members.len()),
+            data: Some(members),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Owner only; the owner's own role never changes
+#[put("/pg/gardens/{id}/members/{user_id}")]
+pub async fn update_pg_garden_member(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdateMemberDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let (garden_id, member_id) = path.into_inner();
+    match svc.set_role_pg(garden_id, user_id, member_id, body.into_inner()).await {
+        Ok(updated) if updated > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Member role updated successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Member not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// The owner removes members; with their own id a member leaves the garden
+#[delete("/pg/gardens/{id}/members/{user_id}")]
+pub async fn remove_pg_garden_member(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, member_id) = path.into_inner();
+    match svc.remove_member_pg(garden_id, user_id, member_id).await {
+        Ok(removed) if removed > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Member removed successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Member not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// Owner only; the invitation is also emailed to the address
+#[post("/pg/gardens/{id}/invitations")]
+pub async fn invite_pg_garden_member(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>,
+    body: web::Json<InviteMemberDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.invite_pg(id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(invitation)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Invitation sent successfully".to_string(),
+            data: Some(invitation),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/gardens/{id}/invitations")]
+pub async fn get_pg_garden_invitations(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.invitations_pg(id.into_inner(), user_id).await {
+        Ok(Some(invitations)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} invitations found", invitations.len()),
+            data: Some(invitations),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[delete("/pg/gardens/{id}/invitations/{invitation_id}")]
+pub async fn revoke_pg_garden_invitation(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, invitation_id) = path.into_inner();
+    match svc.revoke_invitation_pg(garden_id, user_id, invitation_id).await {
+        Ok(revoked) if revoked > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Invitation revoked successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Invitation not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// Pending invitations to the user's email address
+#[get("/pg/invitations")]
+pub async fn get_pg_invitations(
+    req: HttpRequest,
+    svc: web::Data<GardenService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.received_invitations_pg(user_id).await {
+        Ok(invitations) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} pending invitations found", invitations.len()),
+            data: Some(invitations),
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[post("/pg/invitations/{id}/accept")]
+pub async fn accept_pg_invitation(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.respond_pg(id.into_inner(), user_id, true).await {
+        Ok(Some(invitation)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Invitation accepted".to_string(),
+            data: Some(invitation),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Invitation not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[post("/pg/invitations/{id}/decline")]
+pub async fn decline_pg_invitation(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.respond_pg(id.into_inner(), user_id, false).await {
+        Ok(Some(invitation)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Invitation declined".to_string(),
+            data: Some(invitation),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Invitation not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/gardens/{id}/plants")]
+pub async fn get_pg_garden_plants(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.plants_pg(id.into_inner(), user_id).await {
+        Ok(Some(plants)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} plants found", plants.len()),
+            data: Some(plants),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Shares one of the user's own plants with the garden; a plant is in at most one garden
+#[post("/pg/gardens/{id}/plants/{plant_id}")]
+pub async fn add_pg_garden_plant(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, plant_id) = path.into_inner();
+    match svc.add_plant_pg(garden_id, user_id, plant_id).await {
+        Ok(Some(plant)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Plant added to the garden".to_string(),
+            data: Some(plant),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden or plant not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[delete("/pg/gardens/{id}/plants/{plant_id}")]
+pub async fn remove_pg_garden_plant(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, plant_id) = path.into_inner();
+    match svc.remove_plant_pg(garden_id, user_id, plant_id).await {
+        Ok(removed) if removed > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Plant removed from the garden".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found in the garden".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// ========== SUPABASE ==========
+
+// The creator becomes the garden's owner
+#[post("/sb/gardens")]
+pub async fn add_sb_garden(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    body: web::Json<GardenDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_sb(user_id, body.into_inner()).await {
+        Ok(garden) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Garden created successfully (Supabase)".to_string(),
+            data: Some(garden),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Every garden the user is a member of, with their role in it
+#[get("/sb/gardens")]
+pub async fn get_all_sb_gardens(
+    req: HttpRequest,
+    svc: web::Data<GardenService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_sb(user_id).await {
+        Ok(gardens) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} gardens found (Supabase)", gardens.len()),
+            data: Some(gardens),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/gardens/{id}")]
+pub async fn get_sb_garden_by_id(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_by_id_sb(id.into_inner(), user_id).await {
+        Ok(Some(garden)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Garden found (Supabase)".to_string(),
+            data: Some(garden),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/sb/gardens/{id}")]
+pub async fn update_sb_garden(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>,
+    body: web::Json<GardenDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.rename_sb(id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(garden)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Garden updated successfully (Supabase)".to_string(),
+            data: Some(garden),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// Owner only; the plants stay with their owners
+#[delete("/sb/gardens/{id}")]
+pub async fn delete_sb_garden(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.delete_sb(id.into_inner(), user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Garden deleted successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/gardens/{id}/members")]
+pub async fn get_sb_garden_members(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.members_sb(id.into_inner(), user_id).await {
+        Ok(Some(members)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} members found (Supabase)", members.len()),
+            data: Some(members),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Owner only; the owner's own role never changes
+#[put("/sb/gardens/{id}/members/{user_id}")]
+pub async fn update_sb_garden_member(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdateMemberDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let (garden_id, member_id) = path.into_inner();
+    match svc.set_role_sb(garden_id, user_id, member_id, body.into_inner()).await {
+        Ok(updated) if updated > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Member role updated successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Member not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// The owner removes members; with their own id a member leaves the garden
+#[delete("/sb/gardens/{id}/members/{user_id}")]
+pub async fn remove_sb_garden_member(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, member_id) = path.into_inner();
+    match svc.remove_member_sb(garden_id, user_id, member_id).await {
+        Ok(removed) if removed > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Member removed successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Member not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// Owner only; the invitation is also emailed to the address
+#[post("/sb/gardens/{id}/invitations")]
+pub async fn invite_sb_garden_member(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>,
+    body: web::Json<InviteMemberDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.invite_sb(id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(invitation)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Invitation sent successfully (Supabase)".to_string(),
+            data: Some(invitation),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/gardens/{id}/invitations")]
+pub async fn get_sb_garden_invitations(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.invitations_sb(id.into_inner(), user_id).await {
+        Ok(Some(invitations)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} invitations found (Supabase)", invitations.len()),
+            data: Some(invitations),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[delete("/sb/gardens/{id}/invitations/{invitation_id}")]
+pub async fn revoke_sb_garden_invitation(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, invitation_id) = path.into_inner();
+    match svc.revoke_invitation_sb(garden_id, user_id, invitation_id).await {
+        Ok(revoked) if revoked > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Invitation revoked successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Invitation not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// Pending invitations to the user's email address
+#[get("/sb/invitations")]
+pub async fn get_sb_invitations(
+    req: HttpRequest,
+    svc: web::Data<GardenService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.received_invitations_sb(user_id).await {
+        Ok(invitations) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} pending invitations found (Supabase)", invitations.len()),
+            data: Some(invitations),
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[post("/sb/invitations/{id}/accept")]
+pub async fn accept_sb_invitation(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.respond_sb(id.into_inner(), user_id, true).await {
+        Ok(Some(invitation)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Invitation accepted (Supabase)".to_string(),
+            data: Some(invitation),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Invitation not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[post("/sb/invitations/{id}/decline")]
+pub async fn decline_sb_invitation(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.respond_sb(id.into_inner(), user_id, false).await {
+        Ok(Some(invitation)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Invitation declined (Supabase)".to_string(),
+            data: Some(invitation),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Invitation not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/gardens/{id}/plants")]
+pub async fn get_sb_garden_plants(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.plants_sb(id.into_inner(), user_id).await {
+        Ok(Some(plants)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} plants found (Supabase)", plants.len()),
+            data: Some(plants),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Shares one of the user's own plants with the garden; a plant is in at most one garden
+#[post("/sb/gardens/{id}/plants/{plant_id}")]
+pub async fn add_sb_garden_plant(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, plant_id) = path.into_inner();
+    match svc.add_plant_sb(garden_id, user_id, plant_id).await {
+        Ok(Some(plant)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Plant added to the garden (Supabase)".to_string(),
+            data: Some(plant),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden or plant not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[delete("/sb/gardens/{id}/plants/{plant_id}")]
+pub async fn remove_sb_garden_plant(
+    req: HttpRequest,
+    svc: web::Data<GardenService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, plant_id) = path.into_inner();
+    match svc.remove_plant_sb(garden_id, user_id, plant_id).await {
+        Ok(removed) if removed > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Plant removed from the garden (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found in the garden (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
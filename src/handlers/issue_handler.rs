@@ -0,0 +1,588 @@
+use actix_web::{get, post, put, delete, web, HttpResponse, Responder, HttpRequest};
+use uuid::Uuid;
+use crate::services::issue_service::IssueService;
+use crate::dtos::issue_dto::{AddTreatmentDTO, AffectedPlantsQuery, CreateIssueDTO, IssueListQuery, UpdateIssueDTO};
+use crate::dtos::validation::Validate;
+use crate::errors::ServiceError;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+
+// ========== POSTGRES ==========
+
+#[post("/pg/plants/{id}/issues")]
+pub async fn add_pg_issue(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    plant_id: web::Path<Uuid>,
+    body: web::Json<CreateIssueDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_pg(plant_id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(issue)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Issue logged successfully".to_string(),
+            data: Some(issue),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/plants/{id}/issues")]
+pub async fn get_pg_issues(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    plant_id: web::Path<Uuid>,
+    query: web::Query<IssueListQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_pg(plant_id.into_inner(), user_id, query.open).await {
+        Ok(issues) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} issues found", issues.len()),
+            data: Some(issues),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/plants/{id}/issues/{issue_id}")]
+pub async fn get_pg_issue_by_id(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, issue_id) = path.into_inner();
+    match svc.get_by_id_pg(issue_id, plant_id, user_id).await {
+        Ok(Some(issue)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Issue found".to_string(),
+            data: Some(issue),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Issue not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/pg/plants/{id}/issues/{issue_id}")]
+pub async fn update_pg_issue(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdateIssueDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let (plant_id, issue_id) = path.into_inner();
+    match svc.update_pg(issue_id, plant_id, user_id, body.into_inner()).await {
+        Ok(Some(issue)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Issue updated successfully".to_string(),
+            data: Some(issue),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Issue not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[post("/pg/plants/{id}/issues/{issue_id}/treatments")]
+pub async fn add_pg_issue_treatment(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<AddTreatmentDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let (plant_id, issue_id) = path.into_inner();
+    match svc.add_treatment_pg(issue_id, plant_id, user_id, body.into_inner()).await {
+        Ok(Some(issue)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Treatment added successfully".to_string(),
+            data: Some(issue),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Issue not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[delete("/pg/plants/{id}/issues/{issue_id}")]
+pub async fn delete_pg_issue(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, issue_id) = path.into_inner();
+    match svc.delete_pg(issue_id, plant_id, user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Issue deleted successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Issue not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Plants currently affected by an issue type, e.g. /pg/issues/affected?type=aphids
+#[get("/pg/issues/affected")]
+pub async fn get_pg_affected_plants(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    query: web::Query<AffectedPlantsQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.affected_plants_pg(user_id, &query.issue_type).await {
+        Ok(plants) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} affected plants found", plants.len()),
+            data: Some(plants),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// ========== SUPABASE ==========
+
+#[post("/sb/plants/{id}/issues")]
+pub async fn add_sb_issue(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    plant_id: web::Path<Uuid>,
+    body: web::Json<CreateIssueDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_sb(plant_id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(issue)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Issue logged successfully (Supabase)".to_string(),
+            data: Some(issue),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/plants/{id}/issues")]
+pub async fn get_sb_issues(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    plant_id: web::Path<Uuid>,
+    query: web::Query<IssueListQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_sb(plant_id.into_inner(), user_id, query.open).await {
+        Ok(issues) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} issues found (Supabase)", issues.len()),
+            data: Some(issues),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/plants/{id}/issues/{issue_id}")]
+pub async fn get_sb_issue_by_id(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, issue_id) = path.into_inner();
+    match svc.get_by_id_sb(issue_id, plant_id, user_id).await {
+        Ok(Some(issue)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Issue found (Supabase)".to_string(),
+            data: Some(issue),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Issue not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/sb/plants/{id}/issues/{issue_id}")]
+pub async fn update_sb_issue(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdateIssueDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let (plant_id, issue_id) = path.into_inner();
+    match svc.update_sb(issue_id, plant_id, user_id, body.into_inner()).await {
+        Ok(Some(issue)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Issue updated successfully (Supabase)".to_string(),
+            data: Some(issue),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Issue not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[post("/sb/plants/{id}/issues/{issue_id}/treatments")]
+pub async fn add_sb_issue_treatment(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<AddTreatmentDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let (plant_id, issue_id) = path.into_inner();
+    match svc.add_treatment_sb(issue_id, plant_id, user_id, body.into_inner()).await {
+        Ok(Some(issue)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Treatment added successfully (Supabase)".to_string(),
+            data: Some(issue),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Issue not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[delete("/sb/plants/{id}/issues/{issue_id}")]
+pub async fn delete_sb_issue(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, issue_id) = path.into_inner();
+    match svc.delete_sb(issue_id, plant_id, user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Issue deleted successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Issue not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Plants currently affected by an issue type, e.g. /sb/issues/affected?type=aphids
+#[get("/sb/issues/affected")]
+pub async fn get_sb_affected_plants(
+    req: HttpRequest,
+    svc: web::Data<IssueService>,
+    query: web::Query<AffectedPlantsQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.affected_plants_sb(user_id, &query.issue_type).await {
+        Ok(plants) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} affected plants found (Supabase)", plants.len()),
+            data: Some(plants),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
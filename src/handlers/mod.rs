@@ -1,2 +1,12 @@
 pub mod plant_handler;
-pub mod auth_handler;
\ No newline at end of file
+pub mod auth_handler;
+pub mod calendar_handler;
+pub mod note_handler;
+pub mod issue_handler;
+pub mod alert_handler;
+pub mod sensor_handler;
+pub mod zone_handler;
+pub mod webhook_handler;
+pub mod event_handler;
+pub mod garden_handler;
+pub mod share_handler;
\ No newline at end of file
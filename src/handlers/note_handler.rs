@@ -0,0 +1,512 @@
+use actix_web::{get, post, put, delete, web, HttpResponse, Responder, HttpRequest};
+use uuid::Uuid;
+use crate::services::note_service::NoteService;
+use crate::dtos::note_dto::{CreateNoteDTO, JournalQuery, UpdateNoteDTO};
+use crate::dtos::validation::Validate;
+use crate::errors::ServiceError;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+
+// ========== POSTGRES ==========
+
+#[post("/pg/plants/{id}/notes")]
+pub async fn add_pg_note(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    plant_id: web::Path<Uuid>,
+    body: web::Json<CreateNoteDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_pg(plant_id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(note)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Note added successfully".to_string(),
+            data: Some(note),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/plants/{id}/notes")]
+pub async fn get_pg_notes(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    plant_id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_pg(plant_id.into_inner(), user_id).await {
+        Ok(notes) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} notes found", notes.len()),
+            data: Some(notes),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/plants/{id}/notes/{note_id}")]
+pub async fn get_pg_note_by_id(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, note_id) = path.into_inner();
+    match svc.get_by_id_pg(note_id, plant_id, user_id).await {
+        Ok(Some(note)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Note found".to_string(),
+            data: Some(note),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Note not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/pg/plants/{id}/notes/{note_id}")]
+pub async fn update_pg_note(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdateNoteDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let (plant_id, note_id) = path.into_inner();
+    match svc.update_pg(note_id, plant_id, user_id, body.into_inner()).await {
+        Ok(Some(note)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Note updated successfully".to_string(),
+            data: Some(note),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Note not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[delete("/pg/plants/{id}/notes/{note_id}")]
+pub async fn delete_pg_note(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, note_id) = path.into_inner();
+    match svc.delete_pg(note_id, plant_id, user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Note deleted successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Note not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/journal")]
+pub async fn get_pg_journal(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    query: web::Query<JournalQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.journal_pg(user_id, &query).await {
+        Ok(entries) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} journal entries found", entries.len()),
+            data: Some(entries),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// ========== SUPABASE ==========
+
+#[post("/sb/plants/{id}/notes")]
+pub async fn add_sb_note(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    plant_id: web::Path<Uuid>,
+    body: web::Json<CreateNoteDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_sb(plant_id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(note)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Note added successfully (Supabase)".to_string(),
+            data: Some(note),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/plants/{id}/notes")]
+pub async fn get_sb_notes(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    plant_id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_sb(plant_id.into_inner(), user_id).await {
+        Ok(notes) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} notes found (Supabase)", notes.len()),
+            data: Some(notes),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/plants/{id}/notes/{note_id}")]
+pub async fn get_sb_note_by_id(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, note_id) = path.into_inner();
+    match svc.get_by_id_sb(note_id, plant_id, user_id).await {
+        Ok(Some(note)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Note found (Supabase)".to_string(),
+            data: Some(note),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Note not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/sb/plants/{id}/notes/{note_id}")]
+pub async fn update_sb_note(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdateNoteDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    let (plant_id, note_id) = path.into_inner();
+    match svc.update_sb(note_id, plant_id, user_id, body.into_inner()).await {
+        Ok(Some(note)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Note updated successfully (Supabase)".to_string(),
+            data: Some(note),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Note not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[delete("/sb/plants/{id}/notes/{note_id}")]
+pub async fn delete_sb_note(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, note_id) = path.into_inner();
+    match svc.delete_sb(note_id, plant_id, user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Note deleted successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Note not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/journal")]
+pub async fn get_sb_journal(
+    req: HttpRequest,
+    svc: web::Data<NoteService>,
+    query: web::Query<JournalQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.journal_sb(user_id, &query).await {
+        Ok(entries) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} journal entries found (Supabase)", entries.len()),
+            data: Some(entries),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
@@ -1,60 +1,40 @@
 use actix_web::{get, post, put, patch, delete, web, HttpResponse, Responder, HttpRequest};
 use uuid::Uuid;
 use crate::services::plant_service::PlantService;
-use crate::dtos::plant_dto::{CreatePlantDTO, UpdatePlantDTO, UpdatePlantStatusDTO};
+use crate::services::auth_service::AuthService;
+use crate::dtos::plant_dto::{CareEventQuery, CreatePlantDTO, UpdatePlantDTO, UpdatePlantStatusDTO};
+use crate::dtos::validation::Validate;
+use crate::dtos::import_dto::{ExportQuery, ImportQuery, TransferFormat};
+use crate::services::plant_transfer::plants_to_csv;
 use crate::models::plant::{Plant, ApiResponse};
-use serde::Serialize;
+use crate::errors::ServiceError;
 
-// FIXED: Proper JWT token extraction and validation
-fn get_user_id_from_request(req: &HttpRequest) -> Result<Uuid, String> {
-    // Extract Authorization header
-    let auth_header = req.headers()
+// Explicit ?format= wins, otherwise a text/csv body is read as CSV and anything else as JSON
+fn import_format(req: &HttpRequest, query: &ImportQuery) -> TransferFormat {
+    if let Some(format) = query.format {
+        return format;
+    }
+    let content_type = req.headers().get("content-type").and_then(|h| h.to_str().ok()).unwrap_or("");
+    if content_type.starts_with("text/csv") {
+        TransferFormat::Csv
+    } else {
+        TransferFormat::Json
+    }
+}
+
+// The user id from a verified JWT in the Authorization header, as issued by login and register
+pub(crate) fn get_user_id_from_request(req: &HttpRequest) -> Result<Uuid, String> {
+    let token = req.headers()
         .get("Authorization")
         .ok_or_else(|| "Authorization header required".to_string())?
         .to_str()
-        .map_err(|_| "Invalid authorization header".to_string())?;
+        .map_err(|_| "Invalid authorization header".to_string())?
+        .strip_prefix("Bearer ")
+        .ok_or_else(|| "Invalid authorization format. Use: Bearer <token>".to_string())?;
 
-    // Check if it starts with "Bearer "
-    if !auth_header.starts_with("Bearer ") {
-        return Err("Invalid authorization format. Use: Bearer <token>".to_string());
-    }
-
-    let token = auth_header.strip_prefix("Bearer ").unwrap();
-    
-    // OPTION 1: For testing - extract user_id from a simple token format
-    // Assuming token format: "user_<uuid>" for testing
-    if token.starts_with("user_") {
-        let user_id_str = token.strip_prefix("user_").unwrap();
-        return Uuid::parse_str(user_id_str)
-            .map_err(|_| "Invalid user ID in token".to_string());
-    }
-    
-    // OPTION 2: Proper JWT validation (uncomment when ready)
-    /*
-    use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
-    use serde::{Deserialize, Serialize};
-    
-    #[derive(Debug, Serialize, Deserialize)]
-    struct Claims {
-        sub: String,  // user_id
-        exp: usize,   // expiration
-    }
-    
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .unwrap_or_else(|_| "your-secret-key".to_string());
-    
-    let decoding_key = DecodingKey::from_secret(jwt_secret.as_ref());
-    let validation = Validation::new(Algorithm::HS256);
-    
-    let token_data = decode::<Claims>(token, &decoding_key, &validation)
-        .map_err(|e| format!("Invalid JWT token: {}", e))?;
-    
-    Uuid::parse_str(&token_data.claims.sub)
-        .map_err(|_| "Invalid user ID in JWT".to_string())
-    */
-    
-    // OPTION 3: For immediate testing - return error with helpful message
-    Err("Invalid token format. For testing, use: Bearer user_<uuid>".to_string())
+    let auth_svc = req.app_data::<web::Data<AuthService>>()
+        .ok_or_else(|| "Authentication is not available".to_string())?;
+    auth_svc.verify_token(token).map_err(|_| "Invalid or expired token".to_string())
 }
 
 // ========== POSTGRES ==========
@@ -63,6 +43,7 @@ fn get_user_id_from_request(req: &HttpRequest) -> Result<Uuid, String> {
 pub async fn add_pg_plant(
     req: HttpRequest,
     svc: web::Data<PlantService>,
+    auth_svc: web::Data<AuthService>,
     body: web::Json<CreatePlantDTO>
 ) -> impl Responder {
     let user_id = match get_user_id_from_request(&req) {
@@ -76,6 +57,32 @@ pub async fn add_pg_plant(
         }
     };
 
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match auth_svc.can_add_plants_pg(user_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::Forbidden().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: "Please verify your email address before adding plants".to_string(),
+                data: None,
+            });
+        }
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    }
+
     let mut dto = body.into_inner();
     dto.user_id = user_id;
 
@@ -172,17 +179,30 @@ pub async fn update_pg_plant(
         }
     };
 
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
     match svc.update_pg(id.into_inner(), user_id, body.into_inner()).await {
         Ok(plant) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "Plant updated successfully".to_string(),
             data: Some(plant),
         }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
             data: None,
         }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
     }
 }
 
@@ -204,12 +224,66 @@ pub async fn update_pg_plant_status(
         }
     };
 
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
     match svc.update_status_pg(id.into_inner(), user_id, body.into_inner()).await {
         Ok(plant) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "Plant status updated successfully".to_string(),
             data: Some(plant),
         }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// Watering, fertilizing and harvesting as recorded by hand or detected by sensors
+#[get("/pg/plants/{id}/care-events")]
+pub async fn get_pg_care_events(
+    req: HttpRequest,
+    svc: web::Data<PlantService>,
+    id: web::Path<Uuid>,
+    query: web::Query<CareEventQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.care_events_pg(id.into_inner(), user_id, &query).await {
+        Ok(events) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} care events found", events.len()),
+            data: Some(events),
+        }),
         Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
@@ -246,11 +320,16 @@ pub async fn delete_pg_plant(
             message: "Plant not found".to_string(),
             data: None,
         }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
             data: None,
         }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
     }
 }
 
@@ -258,6 +337,7 @@ pub async fn delete_pg_plant(
 pub async fn get_pg_dashboard_stats(
     req: HttpRequest,
     svc: web::Data<PlantService>,
+    auth_svc: web::Data<AuthService>,
     query: web::Query<std::collections::HashMap<String, String>>
 ) -> impl Responder {
     let user_id = match get_user_id_from_request(&req) {
@@ -273,7 +353,10 @@ pub async fn get_pg_dashboard_stats(
 
     let date = query.get("date").map(|s| s.as_str()).unwrap_or("2025-07-15");
     
-    match svc.get_dashboard_stats_pg(user_id, date).await {
+    // Weather advice needs the user's city; without one the stats are returned without it
+    let city = auth_svc.get_user_by_id_pg(user_id).await.ok().and_then(|user| user.city);
+
+    match svc.get_dashboard_stats_pg(user_id, date, city.as_deref()).await {
         Ok(stats) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "Dashboard stats retrieved successfully".to_string(),
@@ -287,12 +370,107 @@ pub async fn get_pg_dashboard_stats(
     }
 }
 
+#[get("/pg/plants/export")]
+pub async fn export_pg_plants(
+    req: HttpRequest,
+    svc: web::Data<PlantService>,
+    query: web::Query<ExportQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let plants = match svc.get_all_pg(user_id).await {
+        Ok(plants) => plants,
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    // A plain array/file rather than ApiResponse so the download can be imported again as-is
+    let date = chrono::Utc::now().format("%Y-%m-%d");
+    match query.format {
+        TransferFormat::Json => HttpResponse::Ok()
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"plants-{}.json\"", date)))
+            .json(plants),
+        TransferFormat::Csv => match plants_to_csv(&plants) {
+            Ok(csv) => HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header(("Content-Disposition", format!("attachment; filename=\"plants-{}.csv\"", date)))
+                .body(csv),
+            Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            }),
+        },
+    }
+}
+
+#[post("/pg/plants/import")]
+pub async fn import_pg_plants(
+    req: HttpRequest,
+    svc: web::Data<PlantService>,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let format = import_format(&req, &query);
+    match svc.import_pg(user_id, &body, format, query.dry_run).await {
+        Ok(report) if report.failed == 0 => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: if report.dry_run {
+                format!("Dry run: {} plants would be created, {} updated", report.created, report.updated)
+            } else {
+                format!("{} plants created, {} updated", report.created, report.updated)
+            },
+            data: Some(report),
+        }),
+        Ok(report) => HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: if report.committed {
+                format!("{} rows failed, the other rows were saved", report.failed)
+            } else {
+                format!("{} rows failed, nothing was imported", report.failed)
+            },
+            data: Some(report),
+        }),
+        Err(err) => HttpResponse::BadRequest().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
 // ========== SUPABASE ==========
 
 #[post("/sb/plants")]
 pub async fn add_sb_plant(
     req: HttpRequest,
     svc: web::Data<PlantService>,
+    auth_svc: web::Data<AuthService>,
     body: web::Json<CreatePlantDTO>
 ) -> impl Responder {
     let user_id = match get_user_id_from_request(&req) {
@@ -306,6 +484,32 @@ pub async fn add_sb_plant(
         }
     };
 
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match auth_svc.can_add_plants_sb(user_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::Forbidden().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: "Please verify your email address before adding plants".to_string(),
+                data: None,
+            });
+        }
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    }
+
     let mut dto = body.into_inner();
     dto.user_id = user_id;
 
@@ -402,17 +606,30 @@ pub async fn update_sb_plant(
         }
     };
 
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
     match svc.update_sb(id.into_inner(), user_id, body.into_inner()).await {
         Ok(plant) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "Plant updated successfully (Supabase)".to_string(),
             data: Some(plant),
         }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
             data: None,
         }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
     }
 }
 
@@ -434,12 +651,66 @@ pub async fn update_sb_plant_status(
         }
     };
 
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
     match svc.update_status_sb(id.into_inner(), user_id, body.into_inner()).await {
         Ok(plant) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "Plant status updated successfully (Supabase)".to_string(),
             data: Some(plant),
         }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+// Watering, fertilizing and harvesting as recorded by hand or detected by sensors
+#[get("/sb/plants/{id}/care-events")]
+pub async fn get_sb_care_events(
+    req: HttpRequest,
+    svc: web::Data<PlantService>,
+    id: web::Path<Uuid>,
+    query: web::Query<CareEventQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.care_events_sb(id.into_inner(), user_id, &query).await {
+        Ok(events) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} care events found (Supabase)", events.len()),
+            data: Some(events),
+        }),
         Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
@@ -466,16 +737,26 @@ pub async fn delete_sb_plant(
     };
 
     match svc.delete_sb(id.into_inner(), user_id).await {
-        Ok(_) => HttpResponse::Ok().json(ApiResponse::<()> {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
             status: "success".to_string(),
             message: "Plant deleted successfully (Supabase)".to_string(),
             data: None,
         }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Forbidden(err)) => HttpResponse::Forbidden().json(ApiResponse::<()> {
             status: "error".to_string(),
             message: err,
             data: None,
         }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
     }
 }
 
@@ -483,6 +764,7 @@ pub async fn delete_sb_plant(
 pub async fn get_sb_dashboard_stats(
     req: HttpRequest,
     svc: web::Data<PlantService>,
+    auth_svc: web::Data<AuthService>,
     query: web::Query<std::collections::HashMap<String, String>>
 ) -> impl Responder {
     let user_id = match get_user_id_from_request(&req) {
@@ -498,7 +780,10 @@ pub async fn get_sb_dashboard_stats(
 
     let date = query.get("date").map(|s| s.as_str()).unwrap_or("2025-07-15");
     
-    match svc.get_dashboard_stats_sb(user_id, date).await {
+    // Weather advice needs the user's city; without one the stats are returned without it
+    let city = auth_svc.get_user_by_id_sb(user_id).await.ok().and_then(|user| user.city);
+
+    match svc.get_dashboard_stats_sb(user_id, date, city.as_deref()).await {
         Ok(stats) => HttpResponse::Ok().json(ApiResponse {
             status: "success".to_string(),
             message: "Dashboard stats retrieved successfully (Supabase)".to_string(),
@@ -512,6 +797,100 @@ pub async fn get_sb_dashboard_stats(
     }
 }
 
+#[get("/sb/plants/export")]
+pub async fn export_sb_plants(
+    req: HttpRequest,
+    svc: web::Data<PlantService>,
+    query: web::Query<ExportQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let plants = match svc.get_all_sb(user_id).await {
+        Ok(plants) => plants,
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    // A plain array/file rather than ApiResponse so the download can be imported again as-is
+    let date = chrono::Utc::now().format("%Y-%m-%d");
+    match query.format {
+        TransferFormat::Json => HttpResponse::Ok()
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"plants-{}.json\"", date)))
+            .json(plants),
+        TransferFormat::Csv => match plants_to_csv(&plants) {
+            Ok(csv) => HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header(("Content-Disposition", format!("attachment; filename=\"plants-{}.csv\"", date)))
+                .body(csv),
+            Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            }),
+        },
+    }
+}
+
+#[post("/sb/plants/import")]
+pub async fn import_sb_plants(
+    req: HttpRequest,
+    svc: web::Data<PlantService>,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let format = import_format(&req, &query);
+    match svc.import_sb(user_id, &body, format, query.dry_run).await {
+        Ok(report) if report.failed == 0 => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: if report.dry_run {
+                format!("Dry run: {} plants would be created, {} updated (Supabase)", report.created, report.updated)
+            } else {
+                format!("{} plants created, {} updated (Supabase)", report.created, report.updated)
+            },
+            data: Some(report),
+        }),
+        Ok(report) => HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: if report.committed {
+                format!("{} rows failed, the other rows were saved (Supabase)", report.failed)
+            } else {
+                format!("{} rows failed, nothing was imported (Supabase)", report.failed)
+            },
+            data: Some(report),
+        }),
+        Err(err) => HttpResponse::BadRequest().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
 // ========== TESTING ENDPOINTS (No Auth Required) ==========
 
 #[post("/test/sb/plants")]
@@ -519,6 +898,14 @@ pub async fn add_sb_plant_test(
     svc: web::Data<PlantService>,
     body: web::Json<CreatePlantDTO>
 ) -> impl Responder {
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
     // For testing purposes - no auth required
     match svc.add_sb(body.into_inner()).await {
         Ok(plant) => HttpResponse::Ok().json(ApiResponse {
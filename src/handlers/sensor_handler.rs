@@ -0,0 +1,591 @@
+use actix_web::{get, post, put, delete, web, HttpResponse, Responder, HttpRequest};
+use uuid::Uuid;
+use crate::services::sensor_service::SensorService;
+use crate::dtos::sensor_dto::{CreateDeviceDTO, IngestBatchDTO, ReadingsQuery, UpdateThresholdsDTO};
+use crate::dtos::validation::Validate;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+
+fn device_key(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get("x-api-key")
+        .and_then(|h| h.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+// ========== POSTGRES ==========
+
+#[post("/pg/devices")]
+pub async fn register_pg_device(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    body: web::Json<CreateDeviceDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.register_device_pg(user_id, body.into_inner()).await {
+        Ok(registered) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Device registered, store the API key now - it is not shown again".to_string(),
+            data: Some(registered),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/devices")]
+pub async fn get_pg_devices(
+    req: HttpRequest,
+    svc: web::Data<SensorService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_devices_pg(user_id).await {
+        Ok(devices) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} devices found", devices.len()),
+            data: Some(devices),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[delete("/pg/devices/{id}")]
+pub async fn delete_pg_device(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.delete_device_pg(id.into_inner(), user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Device deleted successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Device not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Called by the devices themselves, authenticated with the X-Api-Key header instead of a user token
+#[post("/pg/ingest/readings")]
+pub async fn ingest_pg_readings(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    body: web::Json<IngestBatchDTO>
+) -> impl Responder {
+    let Some(api_key) = device_key(&req) else {
+        return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Missing X-Api-Key header".to_string(),
+            data: None,
+        });
+    };
+    let device = match svc.authenticate_pg(api_key).await {
+        Ok(Some(device)) => device,
+        Ok(None) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: "Invalid API key".to_string(),
+                data: None,
+            });
+        }
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.ingest_pg(&device, body.into_inner()).await {
+        Ok(report) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} of {} readings stored", report.stored, report.received),
+            data: Some(report),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Downsampled readings, e.g. /pg/plants/{id}/readings?from=2025-07-14&to=2025-07-15&bucket=1h
+#[get("/pg/plants/{id}/readings")]
+pub async fn get_pg_plant_readings(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    plant_id: web::Path<Uuid>,
+    query: web::Query<ReadingsQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.readings_pg(plant_id.into_inner(), user_id, &query).await {
+        Ok(Some(buckets)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} buckets found", buckets.len()),
+            data: Some(buckets),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/plants/{id}/thresholds")]
+pub async fn get_pg_thresholds(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    plant_id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.thresholds_pg(plant_id.into_inner(), user_id).await {
+        Ok(Some(thresholds)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Thresholds retrieved successfully".to_string(),
+            data: Some(thresholds),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/pg/plants/{id}/thresholds")]
+pub async fn update_pg_thresholds(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    plant_id: web::Path<Uuid>,
+    body: web::Json<UpdateThresholdsDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.set_thresholds_pg(plant_id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(thresholds)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Thresholds updated successfully".to_string(),
+            data: Some(thresholds),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// ========== SUPABASE ==========
+
+#[post("/sb/devices")]
+pub async fn register_sb_device(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    body: web::Json<CreateDeviceDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.register_device_sb(user_id, body.into_inner()).await {
+        Ok(registered) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Device registered, store the API key now - it is not shown again (Supabase)".to_string(),
+            data: Some(registered),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/devices")]
+pub async fn get_sb_devices(
+    req: HttpRequest,
+    svc: web::Data<SensorService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_devices_sb(user_id).await {
+        Ok(devices) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} devices found (Supabase)", devices.len()),
+            data: Some(devices),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[delete("/sb/devices/{id}")]
+pub async fn delete_sb_device(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.delete_device_sb(id.into_inner(), user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Device deleted successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Device not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Called by the devices themselves, authenticated with the X-Api-Key header instead of a user token
+#[post("/sb/ingest/readings")]
+pub async fn ingest_sb_readings(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    body: web::Json<IngestBatchDTO>
+) -> impl Responder {
+    let Some(api_key) = device_key(&req) else {
+        return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Missing X-Api-Key header".to_string(),
+            data: None,
+        });
+    };
+    let device = match svc.authenticate_sb(api_key).await {
+        Ok(Some(device)) => device,
+        Ok(None) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: "Invalid API key".to_string(),
+                data: None,
+            });
+        }
+        Err(err) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.ingest_sb(&device, body.into_inner()).await {
+        Ok(report) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} of {} readings stored (Supabase)", report.stored, report.received),
+            data: Some(report),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Downsampled readings, e.g. /sb/plants/{id}/readings?from=2025-07-14&to=2025-07-15&bucket=1h
+#[get("/sb/plants/{id}/readings")]
+pub async fn get_sb_plant_readings(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    plant_id: web::Path<Uuid>,
+    query: web::Query<ReadingsQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.readings_sb(plant_id.into_inner(), user_id, &query).await {
+        Ok(Some(buckets)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} buckets found (Supabase)", buckets.len()),
+            data: Some(buckets),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/plants/{id}/thresholds")]
+pub async fn get_sb_thresholds(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    plant_id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.thresholds_sb(plant_id.into_inner(), user_id).await {
+        Ok(Some(thresholds)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Thresholds retrieved successfully (Supabase)".to_string(),
+            data: Some(thresholds),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/sb/plants/{id}/thresholds")]
+pub async fn update_sb_thresholds(
+    req: HttpRequest,
+    svc: web::Data<SensorService>,
+    plant_id: web::Path<Uuid>,
+    body: web::Json<UpdateThresholdsDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.set_thresholds_sb(plant_id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(thresholds)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Thresholds updated successfully (Supabase)".to_string(),
+            data: Some(thresholds),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
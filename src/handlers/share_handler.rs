@@ -0,0 +1,579 @@
+use actix_web::{get, post, delete, web, HttpResponse, Responder, HttpRequest};
+use uuid::Uuid;
+use crate::services::share_service::ShareService;
+use crate::dtos::share_dto::ShareQuery;
+use crate::dtos::validation::Validate;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+
+// ========== POSTGRES ==========
+
+// Read-only public link to the plant; ?expires_in_days= limits how long it works
+#[post("/pg/plants/{id}/share")]
+pub async fn share_pg_plant(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    id: web::Path<Uuid>,
+    query: web::Query<ShareQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_pg(id.into_inner(), user_id, &query).await {
+        Ok(Some(link)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Share link created".to_string(),
+            data: Some(link),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/plants/{id}/share")]
+pub async fn get_pg_plant_shares(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_pg(id.into_inner(), user_id).await {
+        Ok(links) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} share links found", links.len()),
+            data: Some(links),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// The link stops working right away
+#[delete("/pg/plants/{id}/share/{share_id}")]
+pub async fn revoke_pg_plant_share(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, share_id) = path.into_inner();
+    match svc.revoke_pg(share_id, plant_id, user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Share link revoked".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Share link not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Public, the token is the only credential; unknown, revoked and expired tokens look the same
+#[get("/pg/shared/{token}")]
+pub async fn get_pg_shared_plant(
+    svc: web::Data<ShareService>,
+    path: web::Path<String>
+) -> impl Responder {
+    match svc.shared_plant_pg(&path.into_inner()).await {
+        Ok(Some(plant)) => HttpResponse::Ok()
+            .insert_header(("Cache-Control", "no-cache")) // a revoked link should not live on in caches
+            .json(ApiResponse {
+                status: "success".to_string(),
+                message: "Shared plant found".to_string(),
+                data: Some(plant),
+            }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Shared plant not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Read-only public link to the garden and all its plants; only the owner can create one; ?expires_in_days= limits how long it works
+#[post("/pg/gardens/{id}/share")]
+pub async fn share_pg_garden(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    id: web::Path<Uuid>,
+    query: web::Query<ShareQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_garden_pg(id.into_inner(), user_id, &query).await {
+        Ok(Some(link)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Share link created".to_string(),
+            data: Some(link),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/gardens/{id}/share")]
+pub async fn get_pg_garden_shares(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_garden_pg(id.into_inner(), user_id).await {
+        Ok(links) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} share links found", links.len()),
+            data: Some(links),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// The link stops working right away
+#[delete("/pg/gardens/{id}/share/{share_id}")]
+pub async fn revoke_pg_garden_share(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, share_id) = path.into_inner();
+    match svc.revoke_garden_pg(share_id, garden_id, user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Share link revoked".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Share link not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Public, the token is the only credential; unknown, revoked and expired tokens look the same
+#[get("/pg/shared/gardens/{token}")]
+pub async fn get_pg_shared_garden(
+    svc: web::Data<ShareService>,
+    path: web::Path<String>
+) -> impl Responder {
+    match svc.shared_garden_pg(&path.into_inner()).await {
+        Ok(Some(garden)) => HttpResponse::Ok()
+            .insert_header(("Cache-Control", "no-cache")) // a revoked link should not live on in caches
+            .json(ApiResponse {
+                status: "success".to_string(),
+                message: "Shared garden found".to_string(),
+                data: Some(garden),
+            }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Shared garden not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// ========== SUPABASE ==========
+
+// Read-only public link to the plant; ?expires_in_days= limits how long it works
+#[post("/sb/plants/{id}/share")]
+pub async fn share_sb_plant(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    id: web::Path<Uuid>,
+    query: web::Query<ShareQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_sb(id.into_inner(), user_id, &query).await {
+        Ok(Some(link)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Share link created (Supabase)".to_string(),
+            data: Some(link),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Plant not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/plants/{id}/share")]
+pub async fn get_sb_plant_shares(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_sb(id.into_inner(), user_id).await {
+        Ok(links) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} share links found (Supabase)", links.len()),
+            data: Some(links),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// The link stops working right away
+#[delete("/sb/plants/{id}/share/{share_id}")]
+pub async fn revoke_sb_plant_share(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (plant_id, share_id) = path.into_inner();
+    match svc.revoke_sb(share_id, plant_id, user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Share link revoked (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Share link not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Public, the token is the only credential; unknown, revoked and expired tokens look the same
+#[get("/sb/shared/{token}")]
+pub async fn get_sb_shared_plant(
+    svc: web::Data<ShareService>,
+    path: web::Path<String>
+) -> impl Responder {
+    match svc.shared_plant_sb(&path.into_inner()).await {
+        Ok(Some(plant)) => HttpResponse::Ok()
+            .insert_header(("Cache-Control", "no-cache")) // a revoked link should not live on in caches
+            .json(ApiResponse {
+                status: "success".to_string(),
+                message: "Shared plant found (Supabase)".to_string(),
+                data: Some(plant),
+            }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Shared plant not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Read-only public link to the garden and all its plants; only the owner can create one; ?expires_in_days= limits how long it works
+#[post("/sb/gardens/{id}/share")]
+pub async fn share_sb_garden(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    id: web::Path<Uuid>,
+    query: web::Query<ShareQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_garden_sb(id.into_inner(), user_id, &query).await {
+        Ok(Some(link)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Share link created (Supabase)".to_string(),
+            data: Some(link),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/gardens/{id}/share")]
+pub async fn get_sb_garden_shares(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_garden_sb(id.into_inner(), user_id).await {
+        Ok(links) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} share links found (Supabase)", links.len()),
+            data: Some(links),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// The link stops working right away
+#[delete("/sb/gardens/{id}/share/{share_id}")]
+pub async fn revoke_sb_garden_share(
+    req: HttpRequest,
+    svc: web::Data<ShareService>,
+    path: web::Path<(Uuid, Uuid)>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    let (garden_id, share_id) = path.into_inner();
+    match svc.revoke_garden_sb(share_id, garden_id, user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Share link revoked (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Share link not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Public, the token is the only credential; unknown, revoked and expired tokens look the same
+#[get("/sb/shared/gardens/{token}")]
+pub async fn get_sb_shared_garden(
+    svc: web::Data<ShareService>,
+    path: web::Path<String>
+) -> impl Responder {
+    match svc.shared_garden_sb(&path.into_inner()).await {
+        Ok(Some(garden)) => HttpResponse::Ok()
+            .insert_header(("Cache-Control", "no-cache")) // a revoked link should not live on in caches
+            .json(ApiResponse {
+                status: "success".to_string(),
+                message: "Shared garden found (Supabase)".to_string(),
+                data: Some(garden),
+            }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Shared garden not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
@@ -0,0 +1,475 @@
+use actix_web::{get, post, put, delete, web, HttpResponse, Responder, HttpRequest};
+use uuid::Uuid;
+use crate::services::webhook_service::WebhookService;
+use crate::dtos::webhook_dto::{CreateWebhookDTO, DeliveriesQuery, UpdateWebhookDTO};
+use crate::dtos::validation::Validate;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+
+// ========== POSTGRES ==========
+
+#[post("/pg/webhooks")]
+pub async fn add_pg_webhook(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    body: web::Json<CreateWebhookDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_pg(user_id, body.into_inner()).await {
+        Ok(webhook) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Webhook created, store the secret now - it is not shown again".to_string(),
+            data: Some(webhook),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/webhooks")]
+pub async fn get_all_pg_webhooks(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_pg(user_id).await {
+        Ok(webhooks) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} webhooks found", webhooks.len()),
+            data: Some(webhooks),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/webhooks/{id}")]
+pub async fn get_pg_webhook_by_id(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_by_id_pg(id.into_inner(), user_id).await {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Webhook found".to_string(),
+            data: Some(webhook),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Webhook not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/pg/webhooks/{id}")]
+pub async fn update_pg_webhook(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    id: web::Path<Uuid>,
+    body: web::Json<UpdateWebhookDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.update_pg(id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Webhook updated successfully".to_string(),
+            data: Some(webhook),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Webhook not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[delete("/pg/webhooks/{id}")]
+pub async fn delete_pg_webhook(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.delete_pg(id.into_inner(), user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Webhook deleted successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Webhook not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Delivery log, newest first; ?status=pending|delivered|failed&limit=
+#[get("/pg/webhooks/{id}/deliveries")]
+pub async fn get_pg_webhook_deliveries(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    id: web::Path<Uuid>,
+    query: web::Query<DeliveriesQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.deliveries_pg(id.into_inner(), user_id, &query).await {
+        Ok(Some(deliveries)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} deliveries found", deliveries.len()),
+            data: Some(deliveries),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Webhook not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// ========== SUPABASE ==========
+
+#[post("/sb/webhooks")]
+pub async fn add_sb_webhook(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    body: web::Json<CreateWebhookDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_sb(user_id, body.into_inner()).await {
+        Ok(webhook) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Webhook created, store the secret now - it is not shown again (Supabase)".to_string(),
+            data: Some(webhook),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/webhooks")]
+pub async fn get_all_sb_webhooks(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_sb(user_id).await {
+        Ok(webhooks) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} webhooks found (Supabase)", webhooks.len()),
+            data: Some(webhooks),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/webhooks/{id}")]
+pub async fn get_sb_webhook_by_id(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_by_id_sb(id.into_inner(), user_id).await {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Webhook found (Supabase)".to_string(),
+            data: Some(webhook),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Webhook not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/sb/webhooks/{id}")]
+pub async fn update_sb_webhook(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    id: web::Path<Uuid>,
+    body: web::Json<UpdateWebhookDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.update_sb(id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Webhook updated successfully (Supabase)".to_string(),
+            data: Some(webhook),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Webhook not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[delete("/sb/webhooks/{id}")]
+pub async fn delete_sb_webhook(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.delete_sb(id.into_inner(), user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Webhook deleted successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Webhook not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Delivery log, newest first; ?status=pending|delivered|failed&limit=
+#[get("/sb/webhooks/{id}/deliveries")]
+pub async fn get_sb_webhook_deliveries(
+    req: HttpRequest,
+    svc: web::Data<WebhookService>,
+    id: web::Path<Uuid>,
+    query: web::Query<DeliveriesQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.deliveries_sb(id.into_inner(), user_id, &query).await {
+        Ok(Some(deliveries)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} deliveries found (Supabase)", deliveries.len()),
+            data: Some(deliveries),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Webhook not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
@@ -0,0 +1,580 @@
+use actix_web::{get, post, put, delete, web, HttpResponse, Responder, HttpRequest};
+use uuid::Uuid;
+use crate::services::zone_service::ZoneService;
+use crate::dtos::zone_dto::{CreateZoneDTO, RunZoneQuery, UpdateZoneDTO, ZoneRunsQuery};
+use crate::dtos::validation::Validate;
+use crate::errors::ServiceError;
+use crate::handlers::plant_handler::get_user_id_from_request;
+use crate::models::plant::ApiResponse;
+
+// ========== POSTGRES ==========
+
+#[post("/pg/zones")]
+pub async fn add_pg_zone(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    body: web::Json<CreateZoneDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_pg(user_id, body.into_inner()).await {
+        Ok(zone) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Zone created successfully".to_string(),
+            data: Some(zone),
+        }),
+        Err(ServiceError::Validation(errors)) => HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/zones")]
+pub async fn get_all_pg_zones(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_pg(user_id).await {
+        Ok(zones) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} zones found", zones.len()),
+            data: Some(zones),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/zones/{id}")]
+pub async fn get_pg_zone_by_id(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_by_id_pg(id.into_inner(), user_id).await {
+        Ok(Some(zone)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Zone found".to_string(),
+            data: Some(zone),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/pg/zones/{id}")]
+pub async fn update_pg_zone(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>,
+    body: web::Json<UpdateZoneDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.update_pg(id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(zone)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Zone updated successfully".to_string(),
+            data: Some(zone),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Validation(errors)) => HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[delete("/pg/zones/{id}")]
+pub async fn delete_pg_zone(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.delete_pg(id.into_inner(), user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Zone deleted successfully".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Opens the zone's valve for ?minutes= (default: the zone's run_minutes); answers right away with the running run
+#[post("/pg/zones/{id}/run")]
+pub async fn run_pg_zone(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>,
+    query: web::Query<RunZoneQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.into_inner().run_pg(id.into_inner(), user_id, query.minutes, "manual").await {
+        Ok(Some(run)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Zone run started".to_string(),
+            data: Some(run),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/pg/zones/{id}/runs")]
+pub async fn get_pg_zone_runs(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>,
+    query: web::Query<ZoneRunsQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_runs_pg(id.into_inner(), user_id, query.limit()).await {
+        Ok(Some(runs)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} runs found", runs.len()),
+            data: Some(runs),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// ========== SUPABASE ==========
+
+#[post("/sb/zones")]
+pub async fn add_sb_zone(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    body: web::Json<CreateZoneDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.create_sb(user_id, body.into_inner()).await {
+        Ok(zone) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Zone created successfully (Supabase)".to_string(),
+            data: Some(zone),
+        }),
+        Err(ServiceError::Validation(errors)) => HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/zones")]
+pub async fn get_all_sb_zones(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_all_sb(user_id).await {
+        Ok(zones) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} zones found (Supabase)", zones.len()),
+            data: Some(zones),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/zones/{id}")]
+pub async fn get_sb_zone_by_id(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_by_id_sb(id.into_inner(), user_id).await {
+        Ok(Some(zone)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Zone found (Supabase)".to_string(),
+            data: Some(zone),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+#[put("/sb/zones/{id}")]
+pub async fn update_sb_zone(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>,
+    body: web::Json<UpdateZoneDTO>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = body.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.update_sb(id.into_inner(), user_id, body.into_inner()).await {
+        Ok(Some(zone)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Zone updated successfully (Supabase)".to_string(),
+            data: Some(zone),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Validation(errors)) => HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[delete("/sb/zones/{id}")]
+pub async fn delete_sb_zone(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.delete_sb(id.into_inner(), user_id).await {
+        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
+            status: "success".to_string(),
+            message: "Zone deleted successfully (Supabase)".to_string(),
+            data: None,
+        }),
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
+
+// Opens the zone's valve for ?minutes= (default: the zone's run_minutes); answers right away with the running run
+#[post("/sb/zones/{id}/run")]
+pub async fn run_sb_zone(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>,
+    query: web::Query<RunZoneQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    if let Err(errors) = query.validate() {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse {
+            status: "error".to_string(),
+            message: "Validation failed".to_string(),
+            data: Some(errors),
+        });
+    }
+
+    match svc.into_inner().run_sb(id.into_inner(), user_id, query.minutes, "manual").await {
+        Ok(Some(run)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: "Zone run started (Supabase)".to_string(),
+            data: Some(run),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(ServiceError::Conflict(err)) => HttpResponse::Conflict().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err.to_string(),
+            data: None,
+        }),
+    }
+}
+
+#[get("/sb/zones/{id}/runs")]
+pub async fn get_sb_zone_runs(
+    req: HttpRequest,
+    svc: web::Data<ZoneService>,
+    id: web::Path<Uuid>,
+    query: web::Query<ZoneRunsQuery>
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Ok(id) => id,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: err,
+                data: None,
+            });
+        }
+    };
+
+    match svc.get_runs_sb(id.into_inner(), user_id, query.limit()).await {
+        Ok(Some(runs)) => HttpResponse::Ok().json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("{} runs found (Supabase)", runs.len()),
+            data: Some(runs),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: "Zone not found (Supabase)".to_string(),
+            data: None,
+        }),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            status: "error".to_string(),
+            message: err,
+            data: None,
+        }),
+    }
+}
@@ -0,0 +1,14 @@
+// Shared by the HTTP server (main.rs) and the gardenary-admin tool
+pub mod config;
+pub mod migrations;
+pub mod secret;
+pub mod errors;
+pub mod dtos;
+pub mod models;
+pub mod repositories;
+pub mod services;
+pub mod handlers;
+pub mod middleware;
+
+#[cfg(test)]
+mod test_support;
@@ -1,74 +1,375 @@
-mod config;
-mod dtos;
-mod models;
-mod repositories;
-mod services;
-mod handlers;
-
-use actix_web::{App, HttpServer, web, middleware::Logger};
+use actix_web::{App, HttpServer, web, middleware::{from_fn, Logger}};
 use actix_cors::Cors;
-use services::plant_service::PlantService;
-use services::auth_service::AuthService;
-use repositories::plant_postgres::PlantPostgresRepo;
-use repositories::plant_supabase::PlantSupabaseRepo;
-use repositories::auth_postgres::AuthPostgresRepo;
-use repositories::auth_supabase::AuthSupabaseRepo;
-use handlers::plant_handler::*;
-use handlers::auth_handler::*;
+use employee::{config, migrations};
+use employee::services::plant_service::PlantService;
+use employee::services::auth_service::AuthService;
+use employee::services::note_service::NoteService;
+use employee::services::issue_service::IssueService;
+use employee::services::mailer::{Mailer, LogMailer, FileMailer};
+use employee::services::alert_service::AlertService;
+use employee::services::sensor_service::SensorService;
+use employee::services::mqtt_bridge::MqttBridge;
+use employee::services::zone_service::ZoneService;
+use employee::services::webhook_service::WebhookService;
+use employee::services::event_bus::EventBus;
+use employee::services::garden_service::GardenService;
+use employee::services::share_service::ShareService;
+use employee::services::account_export::AccountExportService;
+use employee::services::valve_driver::{ValveDriver, SimulatedValveDriver};
+use employee::services::notifier::{EmailChannel, Notifier};
+use employee::services::weather::WeatherService;
+use employee::services::login_throttle::{AttemptStore, InMemoryAttemptStore, LoginThrottle};
+use employee::repositories::plant_postgres::PlantPostgresRepo;
+use employee::repositories::plant_supabase::PlantSupabaseRepo;
+use employee::repositories::auth_postgres::AuthPostgresRepo;
+use employee::repositories::auth_supabase::AuthSupabaseRepo;
+use employee::repositories::note_postgres::NotePostgresRepo;
+use employee::repositories::note_supabase::NoteSupabaseRepo;
+use employee::repositories::issue_postgres::IssuePostgresRepo;
+use employee::repositories::issue_supabase::IssueSupabaseRepo;
+use employee::repositories::care_event_postgres::CareEventPostgresRepo;
+use employee::repositories::care_event_supabase::CareEventSupabaseRepo;
+use employee::repositories::sensor_postgres::SensorPostgresRepo;
+use employee::repositories::sensor_supabase::SensorSupabaseRepo;
+use employee::repositories::zone_postgres::ZonePostgresRepo;
+use employee::repositories::zone_supabase::ZoneSupabaseRepo;
+use employee::repositories::webhook_postgres::WebhookPostgresRepo;
+use employee::repositories::webhook_supabase::WebhookSupabaseRepo;
+use employee::repositories::garden_postgres::GardenPostgresRepo;
+use employee::repositories::garden_supabase::GardenSupabaseRepo;
+use employee::repositories::share_postgres::SharePostgresRepo;
+use employee::repositories::share_supabase::ShareSupabaseRepo;
+use employee::repositories::login_attempt_postgres::LoginAttemptPostgresRepo;
+use employee::repositories::supabase_client::SupabaseClient;
+use employee::handlers::plant_handler::*;
+use employee::handlers::auth_handler::*;
+use employee::handlers::calendar_handler::*;
+use employee::handlers::note_handler::*;
+use employee::handlers::issue_handler::*;
+use employee::handlers::alert_handler::*;
+use employee::handlers::sensor_handler::*;
+use employee::handlers::zone_handler::*;
+use employee::handlers::webhook_handler::*;
+use employee::handlers::event_handler::*;
+use employee::handlers::garden_handler::*;
+use employee::handlers::share_handler::*;
+use employee::middleware::rate_limit::{rate_limit, RateLimiter};
+use employee::config::{Backend, MailerKind, ThrottleStoreKind, ValveDriverKind};
+use std::sync::Arc;
+
+// Imports may carry base64 photos, the default 256 KiB is too small for that
+const IMPORT_BODY_LIMIT: usize = 10 * 1024 * 1024;
+// JSON bodies, big enough for a full sensor batch
+const JSON_BODY_LIMIT: usize = 1024 * 1024;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
-    env_logger::init();
-
-    // Validasi env variable
-    let supabase_url = std::env::var("SUPABASE_URL")
-        .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "SUPABASE_URL not set"))?;
-    let supabase_key = std::env::var("SUPABASE_KEY")
-        .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "SUPABASE_KEY not set"))?;
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "JWT_SECRET not set"))?;
-
-    // Log config untuk debug
-    println!("Supabase URL: {}", supabase_url);
-    println!("Supabase KEY: {}", &supabase_key[..6]); // hanya 6 karakter pertama
-    println!("JWT Secret: {}", &jwt_secret[..6]); // hanya 6 karakter pertama
-
-    let pg_pool = config::get_pg_pool();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    // Load and validate the whole configuration before anything starts
+    let config = match config::AppConfig::load() {
+        Ok(config) => config,
+        Err(err) => {
+            log::error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    log::info!("Supabase URL: {}", config.supabase.url);
+    log::info!("Primary backend: {:?}", config.backend);
+
+    let mailer: Arc<dyn Mailer> = match config.mail.mailer {
+        MailerKind::File => Arc::new(FileMailer { dir: config.mail.dir.clone().into() }),
+        MailerKind::Log => Arc::new(LogMailer),
+    };
+
+    let pg_pool = config::get_pg_pool(&config.database).map_err(std::io::Error::other)?;
+
+    // `employee migrate ...` applies or reverts migrations and exits
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        if let Err(err) = migrations::run_command(&pg_pool, &args[1..]).await {
+            log::error!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let schema_check = if config.database.auto_migrate {
+        migrations::run_pending(&pg_pool).await.map(|_| ())
+    } else {
+        migrations::ensure_up_to_date(&pg_pool).await
+    };
+    if let Err(err) = schema_check {
+        // Only fatal when Postgres is the primary backend, the /pg routes are optional otherwise
+        if config.backend == Backend::Postgres {
+            log::error!("{}", err);
+            std::process::exit(1);
+        }
+        log::warn!("Postgres schema check failed: {}", err);
+    }
+    let supabase = SupabaseClient::new(&config.supabase.url, config.supabase.key.clone())
+        .map_err(std::io::Error::other)?;
     
+    // Weather - optional, without a provider nothing is skipped because of rain
+    let weather = Arc::new(WeatherService::from_config(&config.weather).map_err(std::io::Error::other)?);
+
+    // Outbound webhooks - plant changes are queued by PlantService and sent, with retries, by a background worker
+    let webhook_svc = web::Data::new(WebhookService::new(
+        WebhookPostgresRepo { pool: pg_pool.clone() },
+        WebhookSupabaseRepo { client: supabase.clone() },
+        &config.webhooks,
+    ).map_err(std::io::Error::other)?);
+    {
+        let webhook_svc = webhook_svc.clone();
+        let every = std::time::Duration::from_secs(config.webhooks.poll_interval_seconds);
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = webhook_svc.wake.notified() => {}
+                }
+                match webhook_svc.deliver_due_pg().await {
+                    Ok(delivered) if delivered > 0 => log::info!("Delivered {} webhooks (Postgres)", delivered),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Delivering webhooks (Postgres) failed: {}", e),
+                }
+                match webhook_svc.deliver_due_sb().await {
+                    Ok(delivered) if delivered > 0 => log::info!("Delivered {} webhooks (Supabase)", delivered),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Delivering webhooks (Supabase) failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Live updates - PlantService publishes its writes, GET /events streams them to dashboards
+    let event_bus = web::Data::new(EventBus::default());
+
     // Plant services
-    let plant_pg_repo = PlantPostgresRepo { pool: pg_pool.clone() };
-    let plant_sb_repo = PlantSupabaseRepo {
-        project_url: supabase_url.clone(),
-        api_key: supabase_key.clone(),
-    };
-    let plant_svc = web::Data::new(PlantService { 
-        pg_repo: plant_pg_repo, 
-        sb_repo: plant_sb_repo 
+    let plant_svc = web::Data::new(PlantService::new(
+        &pg_pool,
+        &supabase,
+        weather.clone(),
+        webhook_svc.clone().into_inner(),
+        event_bus.clone().into_inner(),
+    ));
+
+    // Plant notes / journal
+    let note_svc = web::Data::new(NoteService {
+        pg_repo: NotePostgresRepo { pool: pg_pool.clone() },
+        sb_repo: NoteSupabaseRepo { client: supabase.clone() },
+        garden_pg_repo: GardenPostgresRepo { pool: pg_pool.clone() },
+        garden_sb_repo: GardenSupabaseRepo { client: supabase.clone() },
+        plants: plant_svc.clone().into_inner(),
     });
 
-    // Auth services
-    let auth_pg_repo = AuthPostgresRepo { pool: pg_pool };
-    let auth_sb_repo = AuthSupabaseRepo {
-        project_url: supabase_url,
-        api_key: supabase_key,
+    // Pest and disease issues
+    let issue_svc = web::Data::new(IssueService {
+        pg_repo: IssuePostgresRepo { pool: pg_pool.clone() },
+        sb_repo: IssueSupabaseRepo { client: supabase.clone() },
+    });
+
+    // Shared gardens - members, invitations and which plants are shared; PlantService checks access through them
+    let garden_svc = web::Data::new(GardenService {
+        pg_repo: GardenPostgresRepo { pool: pg_pool.clone() },
+        sb_repo: GardenSupabaseRepo { client: supabase.clone() },
+        plant_pg_repo: PlantPostgresRepo { pool: pg_pool.clone() },
+        plant_sb_repo: PlantSupabaseRepo { client: supabase.clone() },
+        auth_pg_repo: AuthPostgresRepo { pool: pg_pool.clone() },
+        auth_sb_repo: AuthSupabaseRepo { client: supabase.clone() },
+        mailer: mailer.clone(),
+        app_base_url: config.auth.app_base_url.clone(),
+    });
+
+    // Public share links - a read-only view of one plant or garden for anyone with the token
+    let share_svc = web::Data::new(ShareService {
+        pg_repo: SharePostgresRepo { pool: pg_pool.clone() },
+        sb_repo: ShareSupabaseRepo { client: supabase.clone() },
+        plant_pg_repo: PlantPostgresRepo { pool: pg_pool.clone() },
+        plant_sb_repo: PlantSupabaseRepo { client: supabase.clone() },
+        care_pg_repo: CareEventPostgresRepo { pool: pg_pool.clone() },
+        care_sb_repo: CareEventSupabaseRepo { client: supabase.clone() },
+        garden_pg_repo: GardenPostgresRepo { pool: pg_pool.clone() },
+        garden_sb_repo: GardenSupabaseRepo { client: supabase.clone() },
+        app_base_url: config.auth.app_base_url.clone(),
+    });
+
+    // Account export - everything the user owns beside the plants, for the /auth/me/export routes
+    let export_svc = web::Data::new(AccountExportService {
+        note_pg_repo: NotePostgresRepo { pool: pg_pool.clone() },
+        note_sb_repo: NoteSupabaseRepo { client: supabase.clone() },
+        issue_pg_repo: IssuePostgresRepo { pool: pg_pool.clone() },
+        issue_sb_repo: IssueSupabaseRepo { client: supabase.clone() },
+        care_pg_repo: CareEventPostgresRepo { pool: pg_pool.clone() },
+        care_sb_repo: CareEventSupabaseRepo { client: supabase.clone() },
+        sensor_pg_repo: SensorPostgresRepo { pool: pg_pool.clone() },
+        sensor_sb_repo: SensorSupabaseRepo { client: supabase.clone() },
+        zone_pg_repo: ZonePostgresRepo { pool: pg_pool.clone() },
+        zone_sb_repo: ZoneSupabaseRepo { client: supabase.clone() },
+        webhook_pg_repo: WebhookPostgresRepo { pool: pg_pool.clone() },
+        webhook_sb_repo: WebhookSupabaseRepo { client: supabase.clone() },
+        garden_pg_repo: GardenPostgresRepo { pool: pg_pool.clone() },
+        garden_sb_repo: GardenSupabaseRepo { client: supabase.clone() },
+        share_pg_repo: SharePostgresRepo { pool: pg_pool.clone() },
+        share_sb_repo: ShareSupabaseRepo { client: supabase.clone() },
+    });
+
+    // Login throttling - counters in memory unless they have to be shared between instances
+    let attempt_store: Arc<dyn AttemptStore> = match config.auth.login_throttle_store {
+        ThrottleStoreKind::Postgres => Arc::new(LoginAttemptPostgresRepo { pool: pg_pool.clone() }),
+        ThrottleStoreKind::Memory => Arc::new(InMemoryAttemptStore::default()),
     };
+
+    // Auth services
+    let auth_pg_repo = AuthPostgresRepo { pool: pg_pool.clone() };
+    let auth_sb_repo = AuthSupabaseRepo { client: supabase.clone() };
     let auth_svc = web::Data::new(AuthService {
         pg_repo: auth_pg_repo,
         sb_repo: auth_sb_repo,
-        jwt_secret,
+        jwt_secret: config.auth.jwt_secret.clone(),
+        jwt_ttl_hours: config.auth.jwt_ttl_hours,
+        mailer: mailer.clone(),
+        app_base_url: config.auth.app_base_url.clone(),
+        require_email_verification: config.auth.require_email_verification,
+        login_throttle: LoginThrottle::new(attempt_store),
+        account_deletion_grace_days: config.auth.account_deletion_grace_days,
     });
 
-    println!("🚀 Plant Management Server starting on http://127.0.0.1:8081");
+    // Accounts whose deletion grace period is over are removed by a background job
+    if config.auth.account_deletion_grace_days > 0 {
+        let auth_svc = auth_svc.clone();
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                if let Err(e) = auth_svc.purge_deleted_accounts_pg().await {
+                    log::warn!("Purging deleted accounts (Postgres) failed: {}", e);
+                }
+                if let Err(e) = auth_svc.purge_deleted_accounts_sb().await {
+                    log::warn!("Purging deleted accounts (Supabase) failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Frost and heat alerts - the forecast is checked periodically and new alerts go out to every channel
+    let notifier = Arc::new(Notifier { channels: vec![Arc::new(EmailChannel { mailer: mailer.clone() })] });
+    let alert_svc = web::Data::new(AlertService::new(&pg_pool, &supabase, weather.clone(), notifier.clone(), &config.weather));
+    if weather.enabled() && config.weather.alert_interval_minutes > 0 {
+        let alert_svc = alert_svc.clone();
+        let every = std::time::Duration::from_secs(config.weather.alert_interval_minutes * 60);
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                interval.tick().await;
+                match alert_svc.check_pg().await {
+                    Ok(created) if created > 0 => log::info!("Raised {} weather alerts (Postgres)", created),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Checking weather alerts (Postgres) failed: {}", e),
+                }
+                match alert_svc.check_sb().await {
+                    Ok(created) if created > 0 => log::info!("Raised {} weather alerts (Supabase)", created),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Checking weather alerts (Supabase) failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Sensor devices and their readings; moisture readings can record waterings and raise dry alerts
+    let sensor_svc = web::Data::new(SensorService::new(
+        &pg_pool,
+        &supabase,
+        alert_svc.clone().into_inner(),
+        plant_svc.clone().into_inner(),
+    ));
 
-    HttpServer::new(move || {
+    // MQTT bridge - optional, readings from MQTT controllers and "water now" commands for their valves
+    if let Some(host) = &config.mqtt.host {
+        let bridge = MqttBridge::start(&config.mqtt, host, config.backend, sensor_svc.clone().into_inner(), weather.clone());
+        log::info!("MQTT bridge using {}:{} (topics under {}/)", host, config.mqtt.port, config.mqtt.topic_prefix);
+        if config.mqtt.command_interval_minutes > 0 {
+            let every = std::time::Duration::from_secs(config.mqtt.command_interval_minutes * 60);
+            actix_web::rt::spawn(async move {
+                let mut interval = actix_web::rt::time::interval(every);
+                loop {
+                    interval.tick().await;
+                    match bridge.publish_due().await {
+                        Ok(sent) if sent > 0 => log::info!("Sent {} water now commands over MQTT", sent),
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Sending water now commands failed: {}", e),
+                    }
+                }
+            });
+        }
+    }
+
+    // Watering zones - a valve per zone, run on demand or on the care schedule
+    let valve_driver: Arc<dyn ValveDriver> = match config.valves.driver {
+        ValveDriverKind::Simulated => Arc::new(SimulatedValveDriver {
+            seconds_per_minute: config.valves.simulated_seconds_per_minute,
+        }),
+    };
+    log::info!("Valve driver: {}", valve_driver.name());
+    let zone_svc = web::Data::new(ZoneService {
+        pg_repo: ZonePostgresRepo { pool: pg_pool.clone() },
+        sb_repo: ZoneSupabaseRepo { client: supabase.clone() },
+        plant_pg_repo: PlantPostgresRepo { pool: pg_pool.clone() },
+        plant_sb_repo: PlantSupabaseRepo { client: supabase.clone() },
+        plants: plant_svc.clone().into_inner(),
+        driver: valve_driver,
+        weather: weather.clone(),
+    });
+    {
+        let zone_svc = zone_svc.clone();
+        actix_web::rt::spawn(async move {
+            match zone_svc.fail_interrupted_pg().await {
+                Ok(failed) if failed > 0 => log::warn!("Marked {} interrupted zone runs as failed (Postgres)", failed),
+                Ok(_) => {}
+                Err(e) => log::warn!("Failing interrupted zone runs (Postgres) failed: {}", e),
+            }
+            match zone_svc.fail_interrupted_sb().await {
+                Ok(failed) if failed > 0 => log::warn!("Marked {} interrupted zone runs as failed (Supabase)", failed),
+                Ok(_) => {}
+                Err(e) => log::warn!("Failing interrupted zone runs (Supabase) failed: {}", e),
+            }
+        });
+    }
+    if config.valves.schedule_interval_minutes > 0 {
+        let zone_svc = zone_svc.clone().into_inner();
+        let every = std::time::Duration::from_secs(config.valves.schedule_interval_minutes * 60);
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                interval.tick().await;
+                match zone_svc.run_due_pg().await {
+                    Ok(started) if started > 0 => log::info!("Started {} scheduled zone runs (Postgres)", started),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Starting scheduled zone runs (Postgres) failed: {}", e),
+                }
+                match zone_svc.run_due_sb().await {
+                    Ok(started) if started > 0 => log::info!("Started {} scheduled zone runs (Supabase)", started),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Starting scheduled zone runs (Supabase) failed: {}", e),
+                }
+            }
+        });
+    }
+
+    let rate_limiter = web::Data::new(RateLimiter::new(config.rate_limits));
+
+    let server_config = config.server.clone();
+    log::info!("Plant Management Server starting on http://{}:{}", server_config.host, server_config.port);
+
+    let server = HttpServer::new(move || {
         // Konfigurasi CORS
-        let cors = Cors::default()
-            .allowed_origin("http://localhost:3001")  // Frontend URL
-            .allowed_origin("http://localhost:3000")  // Frontend URL
-            .allowed_origin("http://127.0.0.1:3000")  // Alternative localhost
-            .allowed_origin("http://localhost:5173")  // Vite default port
-            .allowed_origin("http://127.0.0.1:5173")  // Alternative Vite port
+        let cors = server_config.allowed_origins.iter().fold(Cors::default(), |cors, origin| {
+            cors.allowed_origin(origin)
+        });
+        let cors = cors
             .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
             .allowed_headers(vec![
                 "accept",
@@ -77,39 +378,207 @@ async fn main() -> std::io::Result<()> {
                 "user-agent",
                 "x-requested-with"
             ])
+            .expose_headers(vec![
+                "ratelimit-limit",
+                "ratelimit-remaining",
+                "ratelimit-reset",
+                "retry-after"
+            ])
             .max_age(3600);
 
         App::new()
+            .wrap(from_fn(rate_limit))  // Inside CORS so 429 responses still carry CORS headers
             .wrap(cors)  // Tambahkan CORS middleware
             .wrap(Logger::default())  // Logger untuk debugging
             .app_data(plant_svc.clone())
             .app_data(auth_svc.clone())
+            .app_data(note_svc.clone())
+            .app_data(issue_svc.clone())
+            .app_data(web::Data::from(weather.clone()))
+            .app_data(alert_svc.clone())
+            .app_data(sensor_svc.clone())
+            .app_data(zone_svc.clone())
+            .app_data(webhook_svc.clone())
+            .app_data(event_bus.clone())
+            .app_data(garden_svc.clone())
+            .app_data(share_svc.clone())
+            .app_data(export_svc.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(web::PayloadConfig::new(IMPORT_BODY_LIMIT))  // raw bodies, i.e. plant imports
+            .app_data(web::JsonConfig::default().limit(JSON_BODY_LIMIT))
             // Plant endpoints - Postgres
             .service(add_pg_plant)
             .service(get_all_pg_plants)
+            .service(export_pg_plants)  // before /pg/plants/{id} so "export" is not taken as an id
+            .service(import_pg_plants)
             .service(get_pg_plant_by_id)
             .service(update_pg_plant)
             .service(update_pg_plant_status)
             .service(delete_pg_plant)
             .service(get_pg_dashboard_stats)
+            .service(add_pg_note)
+            .service(get_pg_notes)
+            .service(get_pg_note_by_id)
+            .service(update_pg_note)
+            .service(delete_pg_note)
+            .service(get_pg_journal)
+            .service(add_pg_issue)
+            .service(get_pg_issues)
+            .service(get_pg_issue_by_id)
+            .service(update_pg_issue)
+            .service(add_pg_issue_treatment)
+            .service(delete_pg_issue)
+            .service(get_pg_affected_plants)
+            .service(get_pg_alerts)
+            .service(register_pg_device)
+            .service(get_pg_devices)
+            .service(delete_pg_device)
+            .service(ingest_pg_readings)
+            .service(get_pg_plant_readings)
+            .service(get_pg_thresholds)
+            .service(update_pg_thresholds)
+            .service(get_pg_care_events)
+            .service(add_pg_zone)
+            .service(get_all_pg_zones)
+            .service(get_pg_zone_by_id)
+            .service(update_pg_zone)
+            .service(delete_pg_zone)
+            .service(run_pg_zone)
+            .service(get_pg_zone_runs)
+            .service(add_pg_webhook)
+            .service(get_all_pg_webhooks)
+            .service(get_pg_webhook_by_id)
+            .service(update_pg_webhook)
+            .service(delete_pg_webhook)
+            .service(get_pg_webhook_deliveries)
+            .service(add_pg_garden)
+            .service(get_all_pg_gardens)
+            .service(get_pg_garden_by_id)
+            .service(update_pg_garden)
+            .service(delete_pg_garden)
+            .service(get_pg_garden_members)
+            .service(update_pg_garden_member)
+            .service(remove_pg_garden_member)
+            .service(invite_pg_garden_member)
+            .service(get_pg_garden_invitations)
+            .service(revoke_pg_garden_invitation)
+            .service(get_pg_invitations)
+            .service(accept_pg_invitation)
+            .service(decline_pg_invitation)
+            .service(get_pg_garden_plants)
+            .service(add_pg_garden_plant)
+            .service(remove_pg_garden_plant)
+            .service(share_pg_plant)
+            .service(get_pg_plant_shares)
+            .service(revoke_pg_plant_share)
+            .service(share_pg_garden)
+            .service(get_pg_garden_shares)
+            .service(revoke_pg_garden_share)
             // Plant endpoints - Supabase
             .service(add_sb_plant)
             .service(get_all_sb_plants)
+            .service(export_sb_plants)
+            .service(import_sb_plants)
             .service(get_sb_plant_by_id)
             .service(update_sb_plant)
             .service(update_sb_plant_status)
             .service(delete_sb_plant)
             .service(get_sb_dashboard_stats)
+            .service(add_sb_note)
+            .service(get_sb_notes)
+            .service(get_sb_note_by_id)
+            .service(update_sb_note)
+            .service(delete_sb_note)
+            .service(get_sb_journal)
+            .service(add_sb_issue)
+            .service(get_sb_issues)
+            .service(get_sb_issue_by_id)
+            .service(update_sb_issue)
+            .service(add_sb_issue_treatment)
+            .service(delete_sb_issue)
+            .service(get_sb_affected_plants)
+            .service(get_sb_alerts)
+            .service(register_sb_device)
+            .service(get_sb_devices)
+            .service(delete_sb_device)
+            .service(ingest_sb_readings)
+            .service(get_sb_plant_readings)
+            .service(get_sb_thresholds)
+            .service(update_sb_thresholds)
+            .service(get_sb_care_events)
+            .service(add_sb_zone)
+            .service(get_all_sb_zones)
+            .service(get_sb_zone_by_id)
+            .service(update_sb_zone)
+            .service(delete_sb_zone)
+            .service(run_sb_zone)
+            .service(get_sb_zone_runs)
+            .service(add_sb_webhook)
+            .service(get_all_sb_webhooks)
+            .service(get_sb_webhook_by_id)
+            .service(update_sb_webhook)
+            .service(delete_sb_webhook)
+            .service(get_sb_webhook_deliveries)
+            .service(add_sb_garden)
+            .service(get_all_sb_gardens)
+            .service(get_sb_garden_by_id)
+            .service(update_sb_garden)
+            .service(delete_sb_garden)
+            .service(get_sb_garden_members)
+            .service(update_sb_garden_member)
+            .service(remove_sb_garden_member)
+            .service(invite_sb_garden_member)
+            .service(get_sb_garden_invitations)
+            .service(revoke_sb_garden_invitation)
+            .service(get_sb_invitations)
+            .service(accept_sb_invitation)
+            .service(decline_sb_invitation)
+            .service(get_sb_garden_plants)
+            .service(add_sb_garden_plant)
+            .service(remove_sb_garden_plant)
+            .service(share_sb_plant)
+            .service(get_sb_plant_shares)
+            .service(revoke_sb_plant_share)
+            .service(share_sb_garden)
+            .service(get_sb_garden_shares)
+            .service(revoke_sb_garden_share)
             // Auth endpoints - Postgres
             .service(register_pg)
             .service(login_pg)
             .service(get_me_pg)
+            .service(verify_email_pg)
+            .service(delete_me_pg)
+            .service(export_me_pg)
+            .service(get_calendar_feed_pg)
+            .service(regenerate_calendar_feed_pg)
             // Auth endpoints - Supabase
             .service(register_sb)
             .service(login_sb)
             .service(get_me_sb)
-    })
-    .bind(("127.0.0.1", 8081))?
-    .run()
-    .await
+            .service(verify_email_sb)
+            .service(delete_me_sb)
+            .service(export_me_sb)
+            .service(get_calendar_feed_sb)
+            .service(regenerate_calendar_feed_sb)
+            // Calendar feeds, public and authenticated by the token in the URL
+            .service(pg_calendar_feed)
+            .service(sb_calendar_feed)
+            // Shared plant views, public in the same way
+            .service(get_pg_shared_plant)
+            .service(get_sb_shared_plant)
+            .service(get_pg_shared_garden)
+            .service(get_sb_shared_garden)
+            // Live updates for either backend
+            .service(event_stream)
+    });
+
+    let server = match config.server.workers {
+        Some(workers) => server.workers(workers),
+        None => server,
+    };
+
+    server
+        .bind((config.server.host.as_str(), config.server.port))?
+        .run()
+        .await
 }
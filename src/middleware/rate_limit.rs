@@ -0,0 +1,182 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpResponse};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use crate::models::plant::ApiResponse;
+use crate::services::auth_service::AuthService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteClass {
+    Auth,
+    Read,
+    Write,
+}
+
+impl RouteClass {
+    fn of(req: &ServiceRequest) -> Self {
+        if req.path().contains("/auth/") {
+            RouteClass::Auth
+        } else if matches!(*req.method(), Method::GET | Method::HEAD) {
+            RouteClass::Read
+        } else {
+            RouteClass::Write
+        }
+    }
+}
+
+// A bucket holds `capacity` requests and refills continuously at capacity / period
+#[derive(Debug, Clone, Copy)]
+pub struct BucketConfig {
+    pub capacity: u32,
+    pub period_secs: u64,
+}
+
+impl BucketConfig {
+    fn refill_per_sec(&self) -> f64 {
+        self.capacity as f64 / self.period_secs.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimits {
+    pub auth: BucketConfig,
+    pub read: BucketConfig,
+    pub write: BucketConfig,
+}
+
+impl RateLimits {
+    fn for_class(&self, class: RouteClass) -> BucketConfig {
+        match class {
+            RouteClass::Auth => self.auth,
+            RouteClass::Read => self.read,
+            RouteClass::Write => self.write,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_secs: u64,       // until the bucket is full again
+    pub retry_after_secs: u64, // until the next request is allowed
+}
+
+// Above this many tracked clients, buckets that have refilled completely are dropped
+const MAX_TRACKED_BUCKETS: usize = 50_000;
+
+pub struct RateLimiter {
+    pub limits: RateLimits,
+    buckets: Mutex<HashMap<(RouteClass, String), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        RateLimiter {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn check(&self, class: RouteClass, client_key: &str) -> Decision {
+        let config = self.limits.for_class(class);
+        let capacity = config.capacity as f64;
+        let refill = config.refill_per_sec();
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() > MAX_TRACKED_BUCKETS {
+            buckets.retain(|(class, _), bucket| {
+                let refill = self.limits.for_class(*class).refill_per_sec();
+                let capacity = self.limits.for_class(*class).capacity as f64;
+                bucket.tokens + now.duration_since(bucket.updated_at).as_secs_f64() * refill < capacity
+            });
+        }
+
+        let bucket = buckets.entry((class, client_key.to_string())).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill).min(capacity);
+        bucket.updated_at = now;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        Decision {
+            allowed,
+            limit: config.capacity,
+            remaining: bucket.tokens.floor() as u32,
+            reset_secs: ((capacity - bucket.tokens) / refill).ceil() as u64,
+            retry_after_secs: if allowed { 0 } else { ((1.0 - bucket.tokens) / refill).ceil().max(1.0) as u64 },
+        }
+    }
+}
+
+// User id from a verified token, otherwise the peer IP; a made-up token gets no bucket of its own
+fn client_key(req: &ServiceRequest) -> String {
+    if let Some(auth_svc) = req.app_data::<web::Data<AuthService>>()
+        && let Some(token) = req.headers()
+            .get("authorization")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+        && let Ok(user_id) = auth_svc.verify_token(token)
+    {
+        return format!("user:{}", user_id);
+    }
+    let ip = req.peer_addr().map(|addr| addr.ip().to_string()).unwrap_or_else(|| "unknown".to_string());
+    format!("ip:{}", ip)
+}
+
+fn insert_rate_limit_headers<B>(res: &mut ServiceResponse<B>, decision: &Decision) {
+    let headers = res.headers_mut();
+    for (name, value) in [
+        ("ratelimit-limit", decision.limit as u64),
+        ("ratelimit-remaining", decision.remaining as u64),
+        ("ratelimit-reset", decision.reset_secs),
+    ] {
+        headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+    }
+}
+
+pub async fn rate_limit(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let limiter = match req.app_data::<web::Data<RateLimiter>>() {
+        Some(limiter) => limiter.clone(),
+        None => return next.call(req).await.map(|res| res.map_into_left_body()),
+    };
+
+    let decision = limiter.check(RouteClass::of(&req), &client_key(&req));
+
+    if !decision.allowed {
+        let response = HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", decision.retry_after_secs.to_string()))
+            .json(ApiResponse::<()> {
+                status: "error".to_string(),
+                message: format!("Rate limit exceeded, try again in {} seconds", decision.retry_after_secs),
+                data: None,
+            });
+        let mut res = req.into_response(response).map_into_right_body();
+        insert_rate_limit_headers(&mut res, &decision);
+        return Ok(res);
+    }
+
+    let mut res = next.call(req).await?.map_into_left_body();
+    insert_rate_limit_headers(&mut res, &decision);
+    Ok(res)
+}
@@ -0,0 +1,176 @@
+use deadpool_postgres::Pool;
+use std::collections::BTreeSet;
+
+// Schema changes for the Postgres backend, embedded so the binary always carries the schema it expects
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+macro_rules! migration {
+    ($version:expr, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!("../migrations/", $name, ".up.sql")),
+            down: include_str!(concat!("../migrations/", $name, ".down.sql")),
+        }
+    };
+}
+
+// Keep ordered by version; never edit a migration that has been released, add a new one instead
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_base_schema"),
+    migration!(2, "0002_users_email_lower_unique"),
+    migration!(3, "0003_login_attempts"),
+    migration!(4, "0004_account_deletion"),
+    migration!(5, "0005_plants_external_id"),
+    migration!(6, "0006_calendar_tokens"),
+    migration!(7, "0007_plant_notes"),
+    migration!(8, "0008_plant_issues"),
+    migration!(9, "0009_plants_indoor"),
+    migration!(10, "0010_weather_alerts"),
+    migration!(11, "0011_sensors"),
+    migration!(12, "0012_sensor_care"),
+    migration!(13, "0013_zones"),
+    migration!(14, "0014_webhooks"),
+    migration!(15, "0015_gardens"),
+    migration!(16, "0016_plant_shares"),
+    migration!(17, "0017_garden_shares"),
+];
+
+// Serializes migration runs from several instances starting at once
+const MIGRATION_LOCK_ID: i64 = 0x6761_7264_656e;
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS schema_migrations (
+    version BIGINT PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+)";
+
+pub async fn applied_versions(pool: &Pool) -> Result<BTreeSet<i64>, String> {
+    let client = pool.get().await.map_err(|e| e.to_string())?;
+    client.batch_execute(CREATE_TABLE).await.map_err(|e| e.to_string())?;
+
+    let rows = client.query("SELECT version FROM schema_migrations", &[])
+        .await.map_err(|e| e.to_string())?;
+    Ok(rows.iter().map(|row| row.get::<_, i64>("version")).collect())
+}
+
+pub async fn pending(pool: &Pool) -> Result<Vec<&'static Migration>, String> {
+    let applied = applied_versions(pool).await?;
+    Ok(MIGRATIONS.iter().filter(|m| !applied.contains(&m.version)).collect())
+}
+
+// Applies every pending migration, each one in its own transaction
+pub async fn run_pending(pool: &Pool) -> Result<Vec<&'static Migration>, String> {
+    let mut client = pool.get().await.map_err(|e| e.to_string())?;
+    client.batch_execute(CREATE_TABLE).await.map_err(|e| e.to_string())?;
+
+    let mut done = Vec::new();
+    for migration in MIGRATIONS {
+        let tx = client.transaction().await.map_err(|e| e.to_string())?;
+        tx.execute("SELECT pg_advisory_xact_lock($1)", &[&MIGRATION_LOCK_ID])
+            .await.map_err(|e| e.to_string())?;
+
+        // Checked under the lock, another instance may have just applied it
+        let exists = tx.query_opt("SELECT 1 FROM schema_migrations WHERE version = $1", &[&migration.version])
+            .await.map_err(|e| e.to_string())?;
+        if exists.is_some() {
+            continue;
+        }
+
+        log::info!("Applying migration {}", migration.name);
+        tx.batch_execute(migration.up).await
+            .map_err(|e| format!("migration {} failed: {}", migration.name, e))?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name) VALUES ($1, $2)",
+            &[&migration.version, &migration.name]
+        ).await.map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        done.push(migration);
+    }
+    Ok(done)
+}
+
+// Reverts the latest `steps` applied migrations, newest first
+pub async fn rollback(pool: &Pool, steps: usize) -> Result<Vec<&'static Migration>, String> {
+    let mut client = pool.get().await.map_err(|e| e.to_string())?;
+    client.batch_execute(CREATE_TABLE).await.map_err(|e| e.to_string())?;
+
+    let mut done = Vec::new();
+    for _ in 0..steps {
+        let tx = client.transaction().await.map_err(|e| e.to_string())?;
+        tx.execute("SELECT pg_advisory_xact_lock($1)", &[&MIGRATION_LOCK_ID])
+            .await.map_err(|e| e.to_string())?;
+
+        let Some(row) = tx.query_opt("SELECT version FROM schema_migrations ORDER BY version DESC LIMIT 1", &[])
+            .await.map_err(|e| e.to_string())?
+        else {
+            break;
+        };
+        let version: i64 = row.get("version");
+        let migration = MIGRATIONS.iter().find(|m| m.version == version)
+            .ok_or_else(|| format!("database is at version {} which this binary does not know", version))?;
+
+        log::info!("Reverting migration {}", migration.name);
+        tx.batch_execute(migration.down).await
+            .map_err(|e| format!("reverting {} failed: {}", migration.name, e))?;
+        tx.execute("DELETE FROM schema_migrations WHERE version = $1", &[&version])
+            .await.map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        done.push(migration);
+    }
+    Ok(done)
+}
+
+// Startup check: refuse to serve against a schema older than the code
+pub async fn ensure_up_to_date(pool: &Pool) -> Result<(), String> {
+    let pending = pending(pool).await?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let names = pending.iter().map(|m| m.name).collect::<Vec<_>>().join(", ");
+    Err(format!(
+        "database schema is behind, pending migrations: {} (run `employee migrate` or set PG_AUTO_MIGRATE=true)",
+        names
+    ))
+}
+
+// `employee migrate [up | down [steps] | status]`
+pub async fn run_command(pool: &Pool, args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        None | Some("up") => {
+            let applied = run_pending(pool).await?;
+            if applied.is_empty() {
+                println!("Database is up to date");
+            }
+            for m in applied {
+                println!("Applied {}", m.name);
+            }
+        }
+        Some("down") => {
+            let steps = match args.get(1) {
+                Some(n) => n.parse().map_err(|_| format!("invalid number of steps: {}", n))?,
+                None => 1,
+            };
+            for m in rollback(pool, steps).await? {
+                println!("Reverted {}", m.name);
+            }
+        }
+        Some("status") => {
+            let applied = applied_versions(pool).await?;
+            for m in MIGRATIONS {
+                let state = if applied.contains(&m.version) { "applied" } else { "pending" };
+                println!("{:<8} {}", state, m.name);
+            }
+            for version in applied.iter().filter(|v| !MIGRATIONS.iter().any(|m| m.version == **v)) {
+                println!("{:<8} version {} (unknown to this binary)", "applied", version);
+            }
+        }
+        Some(other) => return Err(format!("unknown migrate command {:?}, expected up, down or status", other)),
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CareEvent {
+    pub id: Uuid,
+    pub plant_id: Uuid,
+    pub user_id: Uuid,
+    pub action: String, // "watered", "fertilized", "harvested"
+    pub source: String, // "manual", "sensor" or "zone"
+    pub occurred_at: DateTime<Utc>,
+    pub details: serde_json::Value, // e.g. the moisture rise a sensor event was detected from
+    pub created_at: DateTime<Utc>,
+}
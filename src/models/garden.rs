@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+pub const OWNER: &str = "owner";
+pub const EDITOR: &str = "editor";
+pub const VIEWER: &str = "viewer";
+
+// Editors may change plants and record care, viewers only look
+pub fn can_edit(role: &str) -> bool {
+    role == OWNER || role == EDITOR
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Garden {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// A garden as one of its members sees it
+#[derive(Debug, Serialize, Clone)]
+pub struct MemberGarden {
+    #[serde(flatten)]
+    pub garden: Garden,
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GardenMember {
+    pub garden_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String, // "owner", "editor" or "viewer"
+    pub name: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GardenInvitation {
+    pub id: Uuid,
+    pub garden_id: Uuid,
+    pub email: String,
+    pub role: String, // "editor" or "viewer"
+    pub invited_by: Uuid,
+    pub status: String, // "pending", "accepted", "declined" or "revoked"
+    pub created_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+// A pending invitation as the invited user sees it
+#[derive(Debug, Serialize, Clone)]
+pub struct ReceivedInvitation {
+    #[serde(flatten)]
+    pub invitation: GardenInvitation,
+    pub garden_name: String,
+}
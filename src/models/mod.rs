@@ -1,2 +1,11 @@
 pub mod plant;
-pub mod user;
\ No newline at end of file
+pub mod user;
+pub mod plant_note;
+pub mod plant_issue;
+pub mod plant_alert;
+pub mod sensor;
+pub mod care_event;
+pub mod zone;
+pub mod webhook;
+pub mod garden;
+pub mod plant_share;
\ No newline at end of file
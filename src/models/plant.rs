@@ -12,27 +12,20 @@ pub struct Plant {
     pub age: i32,
     pub user_id: Uuid,
     pub status: serde_json::Value, // JSON object for daily status
+    pub external_id: Option<String>, // id in the system the plant was imported from
+    #[serde(default)]
+    pub indoor: bool, // indoor plants are left out of weather based advice
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct PlantStatus {
     pub watered: bool,
     pub fertilized: bool,
     pub harvested: bool,
 }
 
-impl Default for PlantStatus {
-    fn default() -> Self {
-        Self {
-            watered: false,
-            fertilized: false,
-            harvested: false,
-        }
-    }
-}
-
 #[derive(Serialize)]
 pub struct ApiResponse<T> {
     pub status: String,      
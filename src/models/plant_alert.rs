@@ -0,0 +1,35 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlantAlert {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub plant_id: Uuid,
+    pub plant_name: String, // as it was named when the alert was raised
+    pub kind: String, // "frost", "heat" or "dry"
+    pub date: NaiveDate, // the day the forecast or reading is for
+    pub value: f64, // °C for frost (forecast minimum) and heat (maximum), soil moisture % for dry
+    pub threshold: f64, // in the same unit as value
+    pub notified_at: Option<DateTime<Utc>>, // None until delivered to at least one channel
+    pub created_at: DateTime<Utc>,
+}
+
+// An outdoor plant of a user with a city, i.e. something the weather alert job can check
+#[derive(Debug, Clone)]
+pub struct WatchedPlant {
+    pub plant_id: Uuid,
+    pub plant_name: String,
+    pub plant_type: String,
+    pub user_id: Uuid,
+    pub city: String,
+}
+
+// Where alerts for a user are sent
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRecipient {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+}
@@ -0,0 +1,63 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Treatment {
+    pub date: NaiveDate,
+    pub treatment: String, // e.g. "neem oil spray"
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlantIssue {
+    pub id: Uuid,
+    pub plant_id: Uuid,
+    pub user_id: Uuid,
+    pub issue_type: String, // lowercase, e.g. "aphids", "powdery mildew"
+    pub severity: String, // "low", "medium", "high"
+    pub description: Option<String>,
+    pub opened_on: NaiveDate,
+    pub resolved_on: Option<NaiveDate>, // None while the issue is open
+    pub resolution: Option<String>,
+    pub treatments: Vec<Treatment>, // oldest first
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// A plant with an open issue of the requested type
+#[derive(Debug, Serialize, Clone)]
+pub struct AffectedPlant {
+    pub plant_id: Uuid,
+    pub plant_name: String,
+    pub plant_type: String,
+    pub issue_id: Uuid,
+    pub severity: String,
+    pub opened_on: NaiveDate,
+}
+
+// Open issue counts for the dashboard
+#[derive(Debug, Serialize, Default)]
+pub struct OpenIssueSummary {
+    pub total: usize,
+    pub plants_affected: usize,
+    pub by_severity: BTreeMap<String, usize>,
+    pub by_type: BTreeMap<String, usize>,
+}
+
+impl OpenIssueSummary {
+    // (issue_type, severity, plant_id) of each open issue
+    pub fn from_open(issues: impl IntoIterator<Item = (String, String, Uuid)>) -> Self {
+        let mut summary = OpenIssueSummary::default();
+        let mut plants = HashSet::new();
+        for (issue_type, severity, plant_id) in issues {
+            summary.total += 1;
+            plants.insert(plant_id);
+            *summary.by_severity.entry(severity).or_default() += 1;
+            *summary.by_type.entry(issue_type).or_default() += 1;
+        }
+        summary.plants_affected = plants.len();
+        summary
+    }
+}
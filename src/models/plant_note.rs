@@ -0,0 +1,25 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlantNote {
+    pub id: Uuid,
+    pub plant_id: Uuid,
+    pub user_id: Uuid,
+    pub date: NaiveDate,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub photo: Option<String>, // base64 or URL, same as Plant.image
+    pub care_action: Option<String>, // "watered", "fertilized", "harvested"
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// A note in the cross-plant journal, with the plant it belongs to
+#[derive(Debug, Serialize, Clone)]
+pub struct JournalEntry {
+    #[serde(flatten)]
+    pub note: PlantNote,
+    pub plant_name: String,
+}
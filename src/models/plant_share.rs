@@ -0,0 +1,79 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlantShare {
+    pub id: Uuid,
+    pub plant_id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub expires_at: Option<DateTime<Utc>>, // None: valid until revoked
+    pub created_at: DateTime<Utc>,
+}
+
+// A share as its owner sees it, with the public URL to hand out
+#[derive(Debug, Serialize, Clone)]
+pub struct ShareLink {
+    #[serde(flatten)]
+    pub share: PlantShare,
+    pub url: String,
+}
+
+// A link to a whole garden; only its owner creates and revokes them
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GardenShare {
+    pub id: Uuid,
+    pub garden_id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub expires_at: Option<DateTime<Utc>>, // None: valid until revoked
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GardenShareLink {
+    #[serde(flatten)]
+    pub share: GardenShare,
+    pub url: String,
+}
+
+// What anyone with the link sees. Built field by field so nothing that identifies the owner
+// (user_id, email, notes, journal photos) can slip in when Plant grows new fields.
+#[derive(Debug, Serialize, Clone)]
+pub struct SharedPlant {
+    pub name: String,
+    pub plant_type: String,
+    pub age: i32,
+    pub planted_date: NaiveDate,
+    pub indoor: bool,
+    pub image: Option<String>,
+    pub care: CareSummary,
+    pub shared_until: Option<DateTime<Utc>>,
+}
+
+// A shared garden: its name and plants, nothing about its members
+#[derive(Debug, Serialize, Clone)]
+pub struct SharedGarden {
+    pub name: String,
+    pub plants: Vec<SharedPlant>,
+    pub shared_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CareSummary {
+    pub since: NaiveDate,
+    pub watered: usize,
+    pub fertilized: usize,
+    pub harvested: usize,
+    pub last_watered: Option<DateTime<Utc>>,
+    pub last_fertilized: Option<DateTime<Utc>>,
+    pub last_harvested: Option<DateTime<Utc>>,
+    pub recent: Vec<SharedCareEvent>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SharedCareEvent {
+    pub action: String,
+    pub occurred_at: DateTime<Utc>,
+}
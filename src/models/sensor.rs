@@ -0,0 +1,68 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SensorDevice {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub key_prefix: String, // first characters of the API key, to tell keys apart
+    pub last_seen_at: Option<DateTime<Utc>>, // last accepted ingest
+    pub created_at: DateTime<Utc>,
+}
+
+// Returned once when a device is registered; the key cannot be shown again
+#[derive(Debug, Serialize, Clone)]
+pub struct RegisteredDevice {
+    #[serde(flatten)]
+    pub device: SensorDevice,
+    pub api_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SensorReading {
+    pub device_id: Uuid,
+    pub plant_id: Uuid,
+    pub recorded_at: DateTime<Utc>,
+    pub moisture_pct: Option<f64>, // soil moisture, 0-100
+    pub temperature_c: Option<f64>,
+    pub light_lux: Option<f64>,
+}
+
+// Min / average / max of one metric within a bucket; None when no reading had the metric
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct MetricSummary {
+    pub min: Option<f64>,
+    pub avg: Option<f64>,
+    pub max: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReadingBucket {
+    pub start: DateTime<Utc>,
+    pub count: i64,
+    pub moisture_pct: MetricSummary,
+    pub temperature_c: MetricSummary,
+    pub light_lux: MetricSummary,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct IngestReport {
+    pub received: usize,
+    pub stored: usize,
+    pub duplicates: usize, // already stored by an earlier batch
+    pub unknown_plants: Vec<Uuid>, // not found or not owned by the device's user, their readings are dropped
+    pub watering_events: usize, // detected from a sharp moisture rise
+    pub dry_alerts: usize,
+}
+
+// Per-plant limits for moisture readings
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlantThresholds {
+    pub plant_id: Uuid,
+    pub user_id: Uuid,
+    pub moisture_min_pct: Option<f64>, // a reading below this raises a "dry" alert, None: no alert
+    pub watering_rise_pct: Option<f64>, // a rise of at least this much counts as watering, None: the default
+    pub updated_at: DateTime<Utc>,
+}
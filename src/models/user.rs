@@ -1,17 +1,22 @@
 use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use uuid::Uuid;
+use crate::secret::Secret;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct User {
     pub id: Uuid,
     pub name: String,
     pub email: String,
-    pub password: String,
+    pub password: Secret<String>, // bcrypt hash
     pub city: Option<String>,
     pub birth_date: Option<String>,
     pub created_at: Option<String>,
     pub avatar: Option<String>,
     pub bio: Option<String>,
+    pub email_verified_at: Option<String>,
+    pub deletion_scheduled_at: Option<String>, // set while a deletion request is in its grace period
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,6 +29,8 @@ pub struct SafeUser {
     pub created_at: Option<String>,
     pub avatar: Option<String>,
     pub bio: Option<String>,
+    pub email_verified_at: Option<String>,
+    pub deletion_scheduled_at: Option<String>, // set while a deletion request is in its grace period
 }
 
 impl From<User> for SafeUser {
@@ -37,6 +44,37 @@ impl From<User> for SafeUser {
             created_at: user.created_at,
             avatar: user.avatar,
             bio: user.bio,
+            email_verified_at: user.email_verified_at,
+            deletion_scheduled_at: user.deletion_scheduled_at,
         }
     }
-}
\ No newline at end of file
+}
+
+// Rows removed together with an account, by table
+#[derive(Debug, Default, Clone)]
+pub struct DeletedRows(pub BTreeMap<&'static str, u64>);
+
+impl DeletedRows {
+    pub fn add(&mut self, table: &'static str, rows: u64) {
+        *self.0.entry(table).or_default() += rows;
+    }
+
+    pub fn plants(&self) -> u64 {
+        self.0.get("plants").copied().unwrap_or(0)
+    }
+}
+
+// "3 plants, 12 care_events", tables without rows are left out
+impl fmt::Display for DeletedRows {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tables: Vec<String> = self.0.iter()
+            .filter(|(_, rows)| **rows > 0)
+            .map(|(table, rows)| format!("{} {}", rows, table))
+            .collect();
+        if tables.is_empty() {
+            write!(f, "no other rows")
+        } else {
+            write!(f, "{}", tables.join(", "))
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Zone {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub valve: String, // address of the valve or relay for the driver, e.g. a relay channel
+    pub plant_ids: Vec<Uuid>,
+    pub run_minutes: i32, // default length of a run
+    pub scheduled: bool, // run automatically when a plant in the zone is due for watering
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ZoneRun {
+    pub id: Uuid,
+    pub zone_id: Uuid,
+    pub user_id: Uuid,
+    pub minutes: i32,
+    pub triggered_by: String, // "manual" or "schedule"
+    pub status: String, // "running", "completed" or "failed"
+    pub error: Option<String>, // why a run failed
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
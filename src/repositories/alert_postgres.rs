@@ -0,0 +1,105 @@
+use crate::models::plant_alert::{AlertRecipient, PlantAlert, WatchedPlant};
+use chrono::NaiveDate;
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+
+pub struct AlertPostgresRepo {
+    pub pool: Pool,
+}
+
+const COLUMNS: &str = "id, user_id, plant_id, plant_name, kind, date, value, threshold, notified_at, created_at";
+
+fn from_row(row: &Row) -> PlantAlert {
+    PlantAlert {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        plant_id: row.get("plant_id"),
+        plant_name: row.get("plant_name"),
+        kind: row.get("kind"),
+        date: row.get("date"),
+        value: row.get("value"),
+        threshold: row.get("threshold"),
+        notified_at: row.get("notified_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+impl AlertPostgresRepo {
+    // Outdoor plants of users who set a city and are not waiting for their account to be deleted
+    pub async fn watched_plants(&self) -> Result<Vec<WatchedPlant>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT p.id AS plant_id, p.name AS plant_name, p.plant_type, u.id AS user_id, u.city
+             FROM plants p JOIN users u ON u.id = p.user_id
+             WHERE NOT p.indoor AND btrim(coalesce(u.city, '')) <> '' AND u.deletion_scheduled_at IS NULL"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| WatchedPlant {
+            plant_id: row.get("plant_id"),
+            plant_name: row.get("plant_name"),
+            plant_type: row.get("plant_type"),
+            user_id: row.get("user_id"),
+            city: row.get("city"),
+        }).collect())
+    }
+
+    // Alerts already raised for the same plant, kind and day are skipped; only new ones are returned
+    pub async fn insert_new(&self, alerts: &[PlantAlert]) -> Result<Vec<PlantAlert>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO plant_alerts ({})
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             ON CONFLICT (plant_id, kind, date) DO NOTHING
+             RETURNING {}", COLUMNS, COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let mut created = Vec::new();
+        for alert in alerts {
+            let row = client.query_opt(&stmt, &[
+                &alert.id, &alert.user_id, &alert.plant_id, &alert.plant_name, &alert.kind, &alert.date,
+                &alert.value, &alert.threshold, &alert.notified_at, &alert.created_at
+            ]).await.map_err(|e| e.to_string())?;
+            created.extend(row.as_ref().map(from_row));
+        }
+        Ok(created)
+    }
+
+    // Users not waiting for their account to be deleted
+    pub async fn recipients(&self, user_ids: &[Uuid]) -> Result<Vec<AlertRecipient>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT id, name, email FROM users WHERE id = ANY($1) AND deletion_scheduled_at IS NULL"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_ids]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| AlertRecipient {
+            id: row.get("id"),
+            name: row.get("name"),
+            email: row.get("email"),
+        }).collect())
+    }
+
+    pub async fn mark_notified(&self, ids: &[Uuid]) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "UPDATE plant_alerts SET notified_at = NOW() WHERE id = ANY($1)"
+        ).await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&ids]).await.map_err(|e| e.to_string())
+    }
+
+    // Soonest first
+    pub async fn get_all_by_user(&self, user_id: Uuid, from: NaiveDate, kind: Option<&str>) -> Result<Vec<PlantAlert>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM plant_alerts
+             WHERE user_id = $1 AND date >= $2 AND ($3::text IS NULL OR kind = $3)
+             ORDER BY date, kind, plant_name", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_id, &from, &kind]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+}
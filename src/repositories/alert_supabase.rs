@@ -0,0 +1,103 @@
+use crate::models::plant_alert::{AlertRecipient, PlantAlert, WatchedPlant};
+use crate::repositories::supabase_client::{Order, Prefer, SupabaseClient};
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+use serde::Deserialize;
+use serde_json::json;
+
+pub struct AlertSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "plant_alerts";
+
+#[derive(Deserialize)]
+struct Owner {
+    id: Uuid,
+    city: Option<String>,
+    deletion_scheduled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct WatchedRow {
+    id: Uuid,
+    name: String,
+    plant_type: String,
+    users: Owner,
+}
+
+impl AlertSupabaseRepo {
+    // Outdoor plants of users who set a city and are not waiting for their account to be deleted
+    pub async fn watched_plants(&self) -> Result<Vec<WatchedPlant>, String> {
+        let rows: Vec<WatchedRow> = self.client.from("plants")
+            .select("id,name,plant_type,users!inner(id,city,deletion_scheduled_at)")
+            .eq("indoor", false)
+            .not_null("users.city")
+            .get()
+            .await?;
+
+        Ok(rows.into_iter()
+            .filter(|row| row.users.deletion_scheduled_at.is_none())
+            .filter_map(|row| {
+                let city = row.users.city.filter(|c| !c.trim().is_empty())?;
+                Some(WatchedPlant {
+                    plant_id: row.id,
+                    plant_name: row.name,
+                    plant_type: row.plant_type,
+                    user_id: row.users.id,
+                    city,
+                })
+            })
+            .collect())
+    }
+
+    // Alerts already raised for the same plant, kind and day are skipped; only new ones are returned
+    pub async fn insert_new(&self, alerts: &[PlantAlert]) -> Result<Vec<PlantAlert>, String> {
+        if alerts.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(self.client.from(TABLE)
+            .on_conflict("plant_id,kind,date")
+            .prefer(Prefer::IgnoreDuplicates)
+            .insert(alerts)
+            .await?)
+    }
+
+    // Users not waiting for their account to be deleted
+    pub async fn recipients(&self, user_ids: &[Uuid]) -> Result<Vec<AlertRecipient>, String> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(self.client.from("users")
+            .select("id,name,email")
+            .in_list("id", user_ids)
+            .null("deletion_scheduled_at")
+            .get()
+            .await?)
+    }
+
+    pub async fn mark_notified(&self, ids: &[Uuid]) -> Result<u64, String> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let updated: Vec<PlantAlert> = self.client.from(TABLE)
+            .in_list("id", ids)
+            .update(&json!({ "notified_at": Utc::now() }))
+            .await?;
+        Ok(updated.len() as u64)
+    }
+
+    // Soonest first
+    pub async fn get_all_by_user(&self, user_id: Uuid, from: NaiveDate, kind: Option<&str>) -> Result<Vec<PlantAlert>, String> {
+        let mut query = self.client.from(TABLE)
+            .eq("user_id", user_id)
+            .gte("date", from)
+            .order("date", Order::Asc)
+            .order("kind", Order::Asc)
+            .order("plant_name", Order::Asc);
+        if let Some(kind) = kind {
+            query = query.eq("kind", kind);
+        }
+        Ok(query.get().await?)
+    }
+}
@@ -1,51 +1,76 @@
-use crate::models::user::{User, SafeUser};
+use crate::models::user::{DeletedRows, User, SafeUser};
 use crate::dtos::auth_dto::RegisterDTO;
 use deadpool_postgres::Pool;
 use uuid::Uuid;
 use tokio_postgres::Row;
-use tokio_postgres::types::ToSql;
+use tokio_postgres::error::SqlState;
+use crate::errors::ServiceError;
+use crate::secret::Secret;
 use bcrypt::{hash, verify, DEFAULT_COST};
+use chrono::{DateTime, Utc};
 
 pub struct AuthPostgresRepo {
     pub pool: Pool,
 }
 
+// Rows that go with an account as (table, condition on the user id $1), children before their parents
+const OWNED_ROWS: [(&str, &str); 17] = [
+    ("plant_notes", "user_id = $1 OR plant_id IN (SELECT id FROM plants WHERE user_id = $1)"),
+    ("plant_issues", "user_id = $1 OR plant_id IN (SELECT id FROM plants WHERE user_id = $1)"),
+    ("plant_alerts", "user_id = $1 OR plant_id IN (SELECT id FROM plants WHERE user_id = $1)"),
+    ("care_events", "user_id = $1 OR plant_id IN (SELECT id FROM plants WHERE user_id = $1)"),
+    ("plant_thresholds", "user_id = $1 OR plant_id IN (SELECT id FROM plants WHERE user_id = $1)"),
+    ("sensor_readings", "plant_id IN (SELECT id FROM plants WHERE user_id = $1)
+        OR device_id IN (SELECT id FROM sensor_devices WHERE user_id = $1)"),
+    ("sensor_devices", "user_id = $1"),
+    ("zone_runs", "user_id = $1 OR zone_id IN (SELECT id FROM zones WHERE user_id = $1)"),
+    ("zones", "user_id = $1"),
+    ("webhook_deliveries", "user_id = $1 OR webhook_id IN (SELECT id FROM webhooks WHERE user_id = $1)"),
+    ("webhooks", "user_id = $1"),
+    ("plant_shares", "user_id = $1 OR plant_id IN (SELECT id FROM plants WHERE user_id = $1)"),
+    ("garden_shares", "user_id = $1 OR garden_id IN (SELECT id FROM gardens WHERE owner_id = $1)"),
+    ("garden_plants", "added_by = $1 OR plant_id IN (SELECT id FROM plants WHERE user_id = $1)
+        OR garden_id IN (SELECT id FROM gardens WHERE owner_id = $1)"),
+    ("garden_invitations", "invited_by = $1 OR garden_id IN (SELECT id FROM gardens WHERE owner_id = $1)"),
+    ("garden_members", "user_id = $1 OR garden_id IN (SELECT id FROM gardens WHERE owner_id = $1)"),
+    ("gardens", "owner_id = $1"),
+];
+
 fn user_from_row(row: Row) -> User {
     User {
         id: row.get::<_, Uuid>("id"),
         name: row.get("name"),
         email: row.get("email"),
-        password: row.get("password"),
+        password: Secret::new(row.get("password")),
         city: row.get("city"),
         birth_date: row.get("birth_date"),
         created_at: row.get("created_at"),
+        avatar: row.get("avatar"),
+        bio: row.get("bio"),
+        email_verified_at: row.get("email_verified_at"),
+        deletion_scheduled_at: row.get("deletion_scheduled_at"),
     }
 }
 
 impl AuthPostgresRepo {
-    pub async fn register(&self, dto: RegisterDTO) -> Result<SafeUser, String> {
+    pub async fn register(&self, dto: RegisterDTO) -> Result<SafeUser, ServiceError> {
         // Validate password confirmation
-        if dto.password != dto.confirm_password {
-            return Err("Password and confirm password do not match".to_string());
+        if dto.password.expose() != dto.confirm_password.expose() {
+            return Err(ServiceError::Other("Password and confirm password do not match".to_string()));
         }
 
         let client = self.pool.get().await.map_err(|e| e.to_string())?;
         
-        // Check if email already exists
-        let check_stmt = client.prepare("SELECT id FROM users WHERE email = $1").await.map_err(|e| e.to_string())?;
-        if client.query_opt(&check_stmt, &[&dto.email]).await.map_err(|e| e.to_string())?.is_some() {
-            return Err("Email already exists".to_string());
-        }
-        
         let id = Uuid::new_v4();
-        let hashed_password = hash(&dto.password, DEFAULT_COST).map_err(|e| e.to_string())?;
+        let hashed_password = hash(dto.password.expose(), DEFAULT_COST).map_err(|e| e.to_string())?;
         
         let stmt = client.prepare(
             "INSERT INTO users (id, name, email, password, city, birth_date, created_at) 
              VALUES ($1, $2, $3, $4, $5, $6, NOW()) 
-             RETURNING id, name, email, password, city, birth_date, created_at::text"
+             RETURNING id, name, email, password, city, birth_date, created_at::text, avatar, bio, email_verified_at::text, deletion_scheduled_at::text"
         ).await.map_err(|e| e.to_string())?;
         
+        // Uniqueness is enforced by the users_email_lower_key index, not by a prior SELECT
         let row = client.query_one(&stmt, &[
             &id,
             &dto.name,
@@ -53,32 +78,38 @@ impl AuthPostgresRepo {
             &hashed_password,
             &dto.city,
             &dto.birth_date
-        ]).await.map_err(|e| e.to_string())?;
+        ]).await.map_err(|e| {
+            if e.code() == Some(&SqlState::UNIQUE_VIOLATION) {
+                ServiceError::Conflict("Email already exists".to_string())
+            } else {
+                ServiceError::Other(e.to_string())
+            }
+        })?;
         
         let user = user_from_row(row);
         Ok(SafeUser::from(user))
     }
 
-    pub async fn login(&self, email: &str, password: &str) -> Result<SafeUser, String> {
+    pub async fn login(&self, email: &str, password: &str) -> Result<SafeUser, ServiceError> {
         let client = self.pool.get().await.map_err(|e| e.to_string())?;
         
         let stmt = client.prepare(
-            "SELECT id, name, email, password, city, birth_date, created_at::text 
-             FROM users WHERE email = $1"
+            "SELECT id, name, email, password, city, birth_date, created_at::text, avatar, bio, email_verified_at::text, deletion_scheduled_at::text 
+             FROM users WHERE lower(email) = $1"
         ).await.map_err(|e| e.to_string())?;
         
         let row = client.query_opt(&stmt, &[&email])
             .await
             .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Invalid email or password".to_string())?;
+            .ok_or_else(|| ServiceError::Unauthorized("Invalid email or password".to_string()))?;
             
         let user = user_from_row(row);
         
         // Verify password
-        if verify(password, &user.password).map_err(|e| e.to_string())? {
+        if verify(password, user.password.expose()).map_err(|e| e.to_string())? {
             Ok(SafeUser::from(user))
         } else {
-            Err("Invalid email or password".to_string())
+            Err(ServiceError::Unauthorized("Invalid email or password".to_string()))
         }
     }
 
@@ -86,7 +117,7 @@ impl AuthPostgresRepo {
         let client = self.pool.get().await.map_err(|e| e.to_string())?;
         
         let stmt = client.prepare(
-            "SELECT id, name, email, password, city, birth_date, created_at::text 
+            "SELECT id, name, email, password, city, birth_date, created_at::text, avatar, bio, email_verified_at::text, deletion_scheduled_at::text 
              FROM users WHERE id = $1"
         ).await.map_err(|e| e.to_string())?;
         
@@ -98,4 +129,156 @@ impl AuthPostgresRepo {
         let user = user_from_row(row);
         Ok(SafeUser::from(user))
     }
-}
\ No newline at end of file
+
+    pub async fn mark_email_verified(&self, id: Uuid) -> Result<SafeUser, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        // Keep the original timestamp if the link is opened more than once
+        let stmt = client.prepare(
+            "UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW())
+             WHERE id = $1
+             RETURNING id, name, email, password, city, birth_date, created_at::text, avatar, bio, email_verified_at::text, deletion_scheduled_at::text"
+        ).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id])
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "User not found".to_string())?;
+
+        let user = user_from_row(row);
+        Ok(SafeUser::from(user))
+    }
+
+    pub async fn get_user_by_email(&self, email: &str) -> Result<SafeUser, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let stmt = client.prepare(
+            "SELECT id, name, email, password, city, birth_date, created_at::text, avatar, bio, email_verified_at::text, deletion_scheduled_at::text 
+             FROM users WHERE lower(email) = $1"
+        ).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&email])
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "User not found".to_string())?;
+
+        Ok(SafeUser::from(user_from_row(row)))
+    }
+
+    // Users whose name or email contains `search` (case-insensitive), oldest first
+    pub async fn list_users(&self, search: Option<&str>, limit: i64, offset: i64) -> Result<Vec<SafeUser>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let pattern = search.map(|s| format!("%{}%", s.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+
+        let stmt = client.prepare(
+            "SELECT id, name, email, password, city, birth_date, created_at::text, avatar, bio, email_verified_at::text, deletion_scheduled_at::text 
+             FROM users
+             WHERE $1::text IS NULL OR name ILIKE $1 OR email ILIKE $1
+             ORDER BY created_at, id
+             LIMIT $2 OFFSET $3"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&pattern, &limit, &offset]).await.map_err(|e| e.to_string())?;
+        Ok(rows.into_iter().map(|row| SafeUser::from(user_from_row(row))).collect())
+    }
+
+    pub async fn update_password(&self, id: Uuid, password: &str) -> Result<(), String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let hashed_password = hash(password, DEFAULT_COST).map_err(|e| e.to_string())?;
+
+        let stmt = client.prepare("UPDATE users SET password = $2 WHERE id = $1")
+            .await.map_err(|e| e.to_string())?;
+        let updated = client.execute(&stmt, &[&id, &hashed_password]).await.map_err(|e| e.to_string())?;
+        if updated == 0 {
+            return Err("User not found".to_string());
+        }
+        Ok(())
+    }
+
+    // Removes the user and their plants in one transaction, returns the number of plants deleted
+    // The foreign keys cascade from users and plants anyway; deleting the rows one table at a time, children
+    // first, is what lets us count them
+    pub async fn delete_user(&self, id: Uuid) -> Result<DeletedRows, String> {
+        let mut client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let tx = client.transaction().await.map_err(|e| e.to_string())?;
+
+        let mut deleted = DeletedRows::default();
+        for (table, condition) in OWNED_ROWS {
+            let rows = tx.execute(&format!("DELETE FROM {} WHERE {}", table, condition), &[&id])
+                .await.map_err(|e| e.to_string())?;
+            deleted.add(table, rows);
+        }
+        let plants = tx.execute("DELETE FROM plants WHERE user_id = $1", &[&id])
+            .await.map_err(|e| e.to_string())?;
+        deleted.add("plants", plants);
+        let users = tx.execute("DELETE FROM users WHERE id = $1", &[&id])
+            .await.map_err(|e| e.to_string())?;
+        if users == 0 {
+            return Err("User not found".to_string());
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(deleted)
+    }
+
+    // Some(date) starts the grace period, None cancels a pending deletion
+    pub async fn schedule_deletion(&self, id: Uuid, at: Option<DateTime<Utc>>) -> Result<SafeUser, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let stmt = client.prepare(
+            "UPDATE users SET deletion_scheduled_at = $2
+             WHERE id = $1
+             RETURNING id, name, email, password, city, birth_date, created_at::text, avatar, bio, email_verified_at::text, deletion_scheduled_at::text"
+        ).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id, &at])
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "User not found".to_string())?;
+
+        Ok(SafeUser::from(user_from_row(row)))
+    }
+
+    pub async fn users_due_for_deletion(&self) -> Result<Vec<Uuid>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("SELECT id FROM users WHERE deletion_scheduled_at <= NOW()")
+            .await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+
+    pub async fn calendar_token(&self, id: Uuid) -> Result<Option<String>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("SELECT calendar_token FROM users WHERE id = $1")
+            .await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id])
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "User not found".to_string())?;
+        Ok(row.get("calendar_token"))
+    }
+
+    // Replaces the token, so any feed URL built from the old one stops working
+    pub async fn set_calendar_token(&self, id: Uuid, token: &str) -> Result<(), String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("UPDATE users SET calendar_token = $2 WHERE id = $1")
+            .await.map_err(|e| e.to_string())?;
+
+        let updated = client.execute(&stmt, &[&id, &token]).await.map_err(|e| e.to_string())?;
+        if updated == 0 {
+            return Err("User not found".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn user_id_by_calendar_token(&self, token: &str) -> Result<Option<Uuid>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("SELECT id FROM users WHERE calendar_token = $1")
+            .await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&token]).await.map_err(|e| e.to_string())?;
+        Ok(row.map(|row| row.get("id")))
+    }
+}
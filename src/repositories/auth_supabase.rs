@@ -1,62 +1,52 @@
-use crate::models::user::{User, SafeUser};
+use crate::models::user::{DeletedRows, User, SafeUser};
 use crate::dtos::auth_dto::RegisterDTO;
-use reqwest::Client;
+use crate::errors::ServiceError;
+use crate::repositories::supabase_client::{Order, SupabaseClient};
 use uuid::Uuid;
 use serde_json::json;
 use bcrypt::{hash, verify, DEFAULT_COST};
+use chrono::{DateTime, Utc};
 
 pub struct AuthSupabaseRepo {
-    pub project_url: String,
-    pub api_key: String,
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "users";
+
+// ILIKE pattern matching `email` literally; `*` is a wildcard to PostgREST and cannot be escaped,
+// so find_by_email compares the rows it gets back again
+fn email_pattern(email: &str) -> String {
+    email.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
 }
 
 impl AuthSupabaseRepo {
-    fn base_url(&self) -> String {
-        let mut url = self.project_url.clone();
-        if !url.ends_with("/rest/v1") {
-            url = format!("{}/rest/v1", url.trim_end_matches('/'));
-        }
-        format!("{}/users", url)
+    // Rows stored before emails were lowercased may still have capitals
+    async fn find_by_email(&self, email: &str) -> Result<Vec<User>, String> {
+        let email = email.to_lowercase();
+        let users: Vec<User> = self.client.from(TABLE)
+            .ilike("email", &email_pattern(&email))
+            .get()
+            .await?;
+        Ok(users.into_iter().filter(|u| u.email.to_lowercase() == email).collect())
     }
 
-    pub async fn register(&self, dto: RegisterDTO) -> Result<SafeUser, String> {
+
+    pub async fn register(&self, dto: RegisterDTO) -> Result<SafeUser, ServiceError> {
         // Validate password confirmation
-        if dto.password != dto.confirm_password {
-            return Err("Password and confirm password do not match".to_string());
+        if dto.password.expose() != dto.confirm_password.expose() {
+            return Err(ServiceError::Other("Password and confirm password do not match".to_string()));
         }
 
-        println!("=== REGISTER DEBUG START ===");
-        println!("Base URL: {}", self.base_url());
-        println!("DTO: {:?}", dto);
-        
-        let client = Client::new();
-        
-        // Check if email already exists
-        let check_url = format!("{}?email=eq.{}", self.base_url(), dto.email);
-        println!("Check URL: {}", check_url);
-        
-        let check_res = client
-            .get(&check_url)
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to check existing email: {}", e))?;
-        
-        println!("Check response status: {}", check_res.status());
-        let check_text = check_res.text().await
-            .map_err(|e| format!("Failed to get check response text: {}", e))?;
-        println!("Check response body: {}", check_text);
-            
-        let existing_users: Vec<User> = serde_json::from_str(&check_text)
-            .map_err(|e| format!("Failed to parse existing users response: {} | Response was: {}", e, check_text))?;
-        
-        if !existing_users.is_empty() {
-            return Err("Email already exists".to_string());
+        log::debug!("Registering user {} via Supabase", dto.email);
+
+        // The users_email_lower_key index only exists once supabase/users_email_lower_unique.sql was run,
+        // so check first; the index then closes the race between two registrations
+        if !self.find_by_email(&dto.email).await?.is_empty() {
+            return Err(ServiceError::Conflict("Email already exists".to_string()));
         }
-        
+
         let id = Uuid::new_v4();
-        let hashed_password = hash(&dto.password, DEFAULT_COST)
+        let hashed_password = hash(dto.password.expose(), DEFAULT_COST)
             .map_err(|e| format!("Failed to hash password: {}", e))?;
         
         let payload = json!({
@@ -68,90 +58,254 @@ impl AuthSupabaseRepo {
             "birth_date": dto.birth_date
         });
         
-        let res = client
-            .post(&self.base_url())
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .header("Content-Type", "application/json")
-            .header("Prefer", "return=representation")
-            .json(&payload)
-            .send()
+        // PostgREST answers 409 when the users_email_lower_key index is violated
+        let users: Vec<User> = self.client.from(TABLE)
+            .insert(&payload)
             .await
-            .map_err(|e| format!("Failed to send request to Supabase: {}", e))?;
+            .map_err(|e| {
+                if e.is_conflict() {
+                    ServiceError::Conflict("Email already exists".to_string())
+                } else {
+                    ServiceError::from(e)
+                }
+            })?;
 
-        let status = res.status();
-        let text = res.text().await
-            .map_err(|e| format!("Failed to get response text: {}", e))?;
-        
-        if !status.is_success() {
-            return Err(format!("Supabase error ({}): {}", status, text));
-        }
-        
-        // Debug: Print the actual response
-        println!("Supabase response: {}", text);
-        
-        // Try to parse as array first (standard Supabase response)
-        if let Ok(users) = serde_json::from_str::<Vec<User>>(&text) {
-            if let Some(user) = users.into_iter().next() {
-                return Ok(SafeUser::from(user));
-            } else {
-                return Err("No user returned in array response".to_string());
-            }
-        }
-        
-        // If array parsing fails, try parsing as single object
-        if let Ok(user) = serde_json::from_str::<User>(&text) {
-            return Ok(SafeUser::from(user));
-        }
-        
-        // If both fail, return detailed error
-        Err(format!("Failed to parse Supabase response. Response was: {}", text))
+        users.into_iter().next()
+            .map(SafeUser::from)
+            .ok_or_else(|| ServiceError::Other("No user returned in array response".to_string()))
     }
 
-    pub async fn login(&self, email: &str, password: &str) -> Result<SafeUser, String> {
-        let client = Client::new();
-        let url = format!("{}?email=eq.{}", self.base_url(), email);
-        
-        let res = client
-            .get(&url)
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to send login request: {}", e))?;
-            
-        let users: Vec<User> = res.json().await
-            .map_err(|e| format!("Failed to parse login response: {}", e))?;
-        
+    pub async fn login(&self, email: &str, password: &str) -> Result<SafeUser, ServiceError> {
+        let users = self.find_by_email(email).await?;
+
         let user = users.into_iter().next()
-            .ok_or_else(|| "Invalid email or password".to_string())?;
+            .ok_or_else(|| ServiceError::Unauthorized("Invalid email or password".to_string()))?;
         
         // Verify password
-        if verify(password, &user.password).map_err(|e| format!("Password verification error: {}", e))? {
+        if verify(password, user.password.expose()).map_err(|e| format!("Password verification error: {}", e))? {
             Ok(SafeUser::from(user))
         } else {
-            Err("Invalid email or password".to_string())
+            Err(ServiceError::Unauthorized("Invalid email or password".to_string()))
         }
     }
 
     pub async fn get_user_by_id(&self, id: Uuid) -> Result<SafeUser, String> {
-        let client = Client::new();
-        let url = format!("{}?id=eq.{}", self.base_url(), id);
-        
-        let res = client
-            .get(&url)
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to get user by id: {}", e))?;
-            
-        let users: Vec<User> = res.json().await
-            .map_err(|e| format!("Failed to parse get_user response: {}", e))?;
+        let users: Vec<User> = self.client.from(TABLE)
+            .eq("id", id)
+            .get()
+            .await?;
         
         let user = users.into_iter().next()
             .ok_or_else(|| "User not found".to_string())?;
         
         Ok(SafeUser::from(user))
     }
-}
\ No newline at end of file
+
+    pub async fn mark_email_verified(&self, id: Uuid) -> Result<SafeUser, String> {
+        // Only touch rows that are still unverified so the original timestamp is kept
+        let users: Vec<User> = self.client.from(TABLE)
+            .eq("id", id)
+            .null("email_verified_at")
+            .update(&json!({ "email_verified_at": Utc::now().to_rfc3339() }))
+            .await?;
+
+        match users.into_iter().next() {
+            Some(user) => Ok(SafeUser::from(user)),
+            // Nothing updated: either already verified or the user does not exist
+            None => self.get_user_by_id(id).await,
+        }
+    }
+
+    pub async fn get_user_by_email(&self, email: &str) -> Result<SafeUser, String> {
+        self.find_by_email(email).await?.into_iter().next()
+            .map(SafeUser::from)
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    // Users whose name or email contains `search` (case-insensitive), oldest first
+    pub async fn list_users(&self, search: Option<&str>, limit: i64, offset: i64) -> Result<Vec<SafeUser>, String> {
+        let mut query = self.client.from(TABLE)
+            .order("created_at", Order::Asc)
+            .order("id", Order::Asc)
+            .range(offset.max(0) as u64, (offset.max(0) + limit.max(1) - 1) as u64);
+        if let Some(search) = search {
+            let pattern = format!("*{}*", search);
+            query = query.or(&[("name", "ilike", &pattern), ("email", "ilike", &pattern)]);
+        }
+
+        let users: Vec<User> = query.get().await?;
+        Ok(users.into_iter().map(SafeUser::from).collect())
+    }
+
+    pub async fn update_password(&self, id: Uuid, password: &str) -> Result<(), String> {
+        let hashed_password = hash(password, DEFAULT_COST)
+            .map_err(|e| format!("Failed to hash password: {}", e))?;
+
+        let users: Vec<serde_json::Value> = self.client.from(TABLE)
+            .eq("id", id)
+            .select("id")
+            .update(&json!({ "password": hashed_password }))
+            .await?;
+        if users.is_empty() {
+            return Err("User not found".to_string());
+        }
+        Ok(())
+    }
+
+    // PostgREST has no multi-request transactions: rows pointing at the user, their plants, gardens,
+    // devices, zones and webhooks go first and the user last, so a failure never leaves orphans
+    pub async fn delete_user(&self, id: Uuid) -> Result<DeletedRows, String> {
+        let user = [id];
+        let plants = self.ids("plants", "user_id", id).await?;
+        let gardens = self.ids("gardens", "owner_id", id).await?;
+        let devices = self.ids("sensor_devices", "user_id", id).await?;
+        let zones = self.ids("zones", "user_id", id).await?;
+        let webhooks = self.ids("webhooks", "user_id", id).await?;
+
+        let owned: [(&str, &str, &[Uuid]); 27] = [
+            ("plant_notes", "plant_id", &plants),
+            ("plant_notes", "user_id", &user),
+            ("plant_issues", "plant_id", &plants),
+            ("plant_issues", "user_id", &user),
+            ("plant_alerts", "plant_id", &plants),
+            ("plant_alerts", "user_id", &user),
+            ("care_events", "plant_id", &plants),
+            ("care_events", "user_id", &user),
+            ("plant_thresholds", "plant_id", &plants),
+            ("sensor_readings", "plant_id", &plants),
+            ("sensor_readings", "device_id", &devices),
+            ("sensor_devices", "user_id", &user),
+            ("zone_runs", "zone_id", &zones),
+            ("zones", "user_id", &user),
+            ("webhook_deliveries", "webhook_id", &webhooks),
+            ("webhooks", "user_id", &user),
+            ("plant_shares", "plant_id", &plants),
+            ("plant_shares", "user_id", &user),
+            ("garden_shares", "garden_id", &gardens),
+            ("garden_plants", "plant_id", &plants),
+            ("garden_plants", "garden_id", &gardens),
+            ("garden_plants", "added_by", &user),
+            ("garden_invitations", "garden_id", &gardens),
+            ("garden_invitations", "invited_by", &user),
+            ("garden_members", "garden_id", &gardens),
+            ("garden_members", "user_id", &user),
+            ("gardens", "owner_id", &user),
+        ];
+        let mut deleted = DeletedRows::default();
+        for (table, column, values) in owned {
+            deleted.add(table, self.delete_in(table, column, values).await?);
+        }
+
+        let plants: Vec<serde_json::Value> = self.client.from("plants")
+            .eq("user_id", id)
+            .select("id")
+            .delete()
+            .await?;
+        let users: Vec<serde_json::Value> = self.client.from(TABLE)
+            .eq("id", id)
+            .select("id")
+            .delete()
+            .await?;
+        if users.is_empty() {
+            return Err("User not found".to_string());
+        }
+        deleted.add("plants", plants.len() as u64);
+        Ok(deleted)
+    }
+
+    async fn ids(&self, table: &str, column: &str, user_id: Uuid) -> Result<Vec<Uuid>, String> {
+        #[derive(serde::Deserialize)]
+        struct Row {
+            id: Uuid,
+        }
+
+        let rows: Vec<Row> = self.client.from(table)
+            .select("id")
+            .eq(column, user_id)
+            .get()
+            .await?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    // Only the filtered column comes back, some of these tables have no id; returns how many rows went
+    async fn delete_in(&self, table: &str, column: &str, values: &[Uuid]) -> Result<u64, String> {
+        if values.is_empty() {
+            return Ok(0);
+        }
+        let rows: Vec<serde_json::Value> = self.client.from(table)
+            .in_list(column, values)
+            .select(column)
+            .delete()
+            .await?;
+        Ok(rows.len() as u64)
+    }
+
+    // Some(date) starts the grace period, None cancels a pending deletion
+    pub async fn schedule_deletion(&self, id: Uuid, at: Option<DateTime<Utc>>) -> Result<SafeUser, String> {
+        let users: Vec<User> = self.client.from(TABLE)
+            .eq("id", id)
+            .update(&json!({ "deletion_scheduled_at": at.map(|at| at.to_rfc3339()) }))
+            .await?;
+
+        users.into_iter().next()
+            .map(SafeUser::from)
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    pub async fn users_due_for_deletion(&self) -> Result<Vec<Uuid>, String> {
+        #[derive(serde::Deserialize)]
+        struct Row {
+            id: Uuid,
+        }
+
+        let rows: Vec<Row> = self.client.from(TABLE)
+            .select("id")
+            .lte("deletion_scheduled_at", Utc::now().to_rfc3339())
+            .get()
+            .await?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    pub async fn calendar_token(&self, id: Uuid) -> Result<Option<String>, String> {
+        #[derive(serde::Deserialize)]
+        struct Row {
+            calendar_token: Option<String>,
+        }
+
+        let rows: Vec<Row> = self.client.from(TABLE)
+            .select("calendar_token")
+            .eq("id", id)
+            .get()
+            .await?;
+        rows.into_iter().next()
+            .map(|row| row.calendar_token)
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    // Replaces the token, so any feed URL built from the old one stops working
+    pub async fn set_calendar_token(&self, id: Uuid, token: &str) -> Result<(), String> {
+        let users: Vec<serde_json::Value> = self.client.from(TABLE)
+            .eq("id", id)
+            .select("id")
+            .update(&json!({ "calendar_token": token }))
+            .await?;
+        if users.is_empty() {
+            return Err("User not found".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn user_id_by_calendar_token(&self, token: &str) -> Result<Option<Uuid>, String> {
+        #[derive(serde::Deserialize)]
+        struct Row {
+            id: Uuid,
+        }
+
+        let rows: Vec<Row> = self.client.from(TABLE)
+            .select("id")
+            .eq("calendar_token", token)
+            .get()
+            .await?;
+        Ok(rows.into_iter().next().map(|row| row.id))
+    }
+}
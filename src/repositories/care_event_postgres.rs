@@ -0,0 +1,64 @@
+use crate::models::care_event::CareEvent;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+
+pub struct CareEventPostgresRepo {
+    pub pool: Pool,
+}
+
+const COLUMNS: &str = "id, plant_id, user_id, action, source, occurred_at, details, created_at";
+
+fn from_row(row: &Row) -> CareEvent {
+    CareEvent {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        user_id: row.get("user_id"),
+        action: row.get("action"),
+        source: row.get("source"),
+        occurred_at: row.get("occurred_at"),
+        details: row.get("details"),
+        created_at: row.get("created_at"),
+    }
+}
+
+impl CareEventPostgresRepo {
+    pub async fn create(&self, event: &CareEvent) -> Result<CareEvent, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO care_events ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING {}", COLUMNS, COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_one(&stmt, &[
+            &event.id, &event.plant_id, &event.user_id, &event.action, &event.source,
+            &event.occurred_at, &event.details, &event.created_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(from_row(&row))
+    }
+
+    // Newest first; every member who can see the plant sees all of its care, whoever recorded it
+    pub async fn get_all_by_plant(&self, plant_id: Uuid, source: Option<&str>, limit: i64) -> Result<Vec<CareEvent>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM care_events
+             WHERE plant_id = $1 AND ($2::text IS NULL OR source = $2)
+             ORDER BY occurred_at DESC LIMIT $3", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id, &source, &limit]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    pub async fn get_in_range(&self, plant_id: Uuid, action: &str, source: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<CareEvent>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM care_events
+             WHERE plant_id = $1 AND action = $2 AND source = $3 AND occurred_at BETWEEN $4 AND $5
+             ORDER BY occurred_at", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id, &action, &source, &from, &to]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::models::care_event::CareEvent;
+use crate::repositories::supabase_client::{Order, SupabaseClient};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub struct CareEventSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "care_events";
+
+impl CareEventSupabaseRepo {
+    pub async fn create(&self, event: &CareEvent) -> Result<CareEvent, String> {
+        let mut arr: Vec<CareEvent> = self.client.from(TABLE).insert(event).await?;
+        arr.pop().ok_or_else(|| "Failed to record care event".to_string())
+    }
+
+    // Newest first; every member who can see the plant sees all of its care, whoever recorded it
+    pub async fn get_all_by_plant(&self, plant_id: Uuid, source: Option<&str>, limit: i64) -> Result<Vec<CareEvent>, String> {
+        let mut query = self.client.from(TABLE)
+            .eq("plant_id", plant_id)
+            .order("occurred_at", Order::Desc)
+            .limit(limit as u64);
+        if let Some(source) = source {
+            query = query.eq("source", source);
+        }
+        Ok(query.get().await?)
+    }
+
+    pub async fn get_in_range(&self, plant_id: Uuid, action: &str, source: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<CareEvent>, String> {
+        Ok(self.client.from(TABLE)
+            .eq("plant_id", plant_id)
+            .eq("action", action)
+            .eq("source", source)
+            .gte("occurred_at", from.to_rfc3339())
+            .lte("occurred_at", to.to_rfc3339())
+            .order("occurred_at", Order::Asc)
+            .get()
+            .await?)
+    }
+}
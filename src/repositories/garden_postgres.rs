@@ -0,0 +1,306 @@
+use crate::models::garden::{Garden, GardenInvitation, GardenMember, MemberGarden, ReceivedInvitation, OWNER};
+use chrono::Utc;
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+
+pub struct GardenPostgresRepo {
+    pub pool: Pool,
+}
+
+const COLUMNS: &str = "id, owner_id, name, created_at, updated_at";
+const INVITATION_COLUMNS: &str = "id, garden_id, email, role, invited_by, status, created_at, responded_at";
+
+fn from_row(row: &Row) -> Garden {
+    Garden {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        name: row.get("name"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn invitation_from_row(row: &Row) -> GardenInvitation {
+    GardenInvitation {
+        id: row.get("id"),
+        garden_id: row.get("garden_id"),
+        email: row.get("email"),
+        role: row.get("role"),
+        invited_by: row.get("invited_by"),
+        status: row.get("status"),
+        created_at: row.get("created_at"),
+        responded_at: row.get("responded_at"),
+    }
+}
+
+impl GardenPostgresRepo {
+    // The creator joins as the owner
+    pub async fn create(&self, garden: &Garden) -> Result<Garden, String> {
+        let mut client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let tx = client.transaction().await.map_err(|e| e.to_string())?;
+
+        let row = tx.query_one(
+            &format!("INSERT INTO gardens ({}) VALUES ($1, $2, $3, $4, $5) RETURNING {}", COLUMNS, COLUMNS),
+            &[&garden.id, &garden.owner_id, &garden.name, &garden.created_at, &garden.updated_at]
+        ).await.map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO garden_members (garden_id, user_id, role, created_at) VALUES ($1, $2, $3, $4)",
+            &[&garden.id, &garden.owner_id, &OWNER, &garden.created_at]
+        ).await.map_err(|e| e.to_string())?;
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(from_row(&row))
+    }
+
+    pub async fn member_gardens(&self, user_id: Uuid) -> Result<Vec<MemberGarden>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT g.*, m.role FROM gardens g
+             JOIN garden_members m ON m.garden_id = g.id
+             WHERE m.user_id = $1 ORDER BY g.name, g.created_at"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| MemberGarden { garden: from_row(row), role: row.get("role") }).collect())
+    }
+
+    // The user's role in the garden, None when they are not a member
+    pub async fn role(&self, garden_id: Uuid, user_id: Uuid) -> Result<Option<String>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("SELECT role FROM garden_members WHERE garden_id = $1 AND user_id = $2")
+            .await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&garden_id, &user_id]).await.map_err(|e| e.to_string())?;
+        Ok(row.map(|row| row.get("role")))
+    }
+
+    // Not scoped to a user, the caller checks the role first
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Option<Garden>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!("SELECT {} FROM gardens WHERE id = $1", COLUMNS))
+            .await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    pub async fn rename(&self, id: Uuid, name: &str) -> Result<Option<Garden>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "UPDATE gardens SET name = $2, updated_at = $3 WHERE id = $1 RETURNING {}", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id, &name, &Utc::now()]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    // Members, invitations and the plant list go with it; the plants themselves stay with their owners
+    pub async fn delete(&self, id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM gardens WHERE id = $1").await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id]).await.map_err(|e| e.to_string())
+    }
+
+    // Owner first, then by name
+    pub async fn members(&self, garden_id: Uuid) -> Result<Vec<GardenMember>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT m.garden_id, m.user_id, m.role, u.name, u.email, m.created_at
+             FROM garden_members m JOIN users u ON u.id = m.user_id
+             WHERE m.garden_id = $1 ORDER BY m.role = 'owner' DESC, u.name"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&garden_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| GardenMember {
+            garden_id: row.get("garden_id"),
+            user_id: row.get("user_id"),
+            role: row.get("role"),
+            name: row.get("name"),
+            email: row.get("email"),
+            created_at: row.get("created_at"),
+        }).collect())
+    }
+
+    pub async fn member_ids(&self, garden_id: Uuid) -> Result<Vec<Uuid>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("SELECT user_id FROM garden_members WHERE garden_id = $1")
+            .await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&garden_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| row.get("user_id")).collect())
+    }
+
+    // The owner's role never changes
+    pub async fn set_role(&self, garden_id: Uuid, user_id: Uuid, role: &str) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "UPDATE garden_members SET role = $3 WHERE garden_id = $1 AND user_id = $2 AND role <> 'owner'"
+        ).await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&garden_id, &user_id, &role]).await.map_err(|e| e.to_string())
+    }
+
+    // The owner cannot leave; a member who does takes their plants out of the garden
+    pub async fn remove_member(&self, garden_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let mut client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let tx = client.transaction().await.map_err(|e| e.to_string())?;
+
+        let removed = tx.execute(
+            "DELETE FROM garden_members WHERE garden_id = $1 AND user_id = $2 AND role <> 'owner'",
+            &[&garden_id, &user_id]
+        ).await.map_err(|e| e.to_string())?;
+        if removed > 0 {
+            tx.execute(
+                "DELETE FROM garden_plants gp USING plants p
+                 WHERE gp.plant_id = p.id AND gp.garden_id = $1 AND p.user_id = $2",
+                &[&garden_id, &user_id]
+            ).await.map_err(|e| e.to_string())?;
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(removed)
+    }
+
+    // None when the address already has a pending invitation to the garden
+    pub async fn create_invitation(&self, invitation: &GardenInvitation) -> Result<Option<GardenInvitation>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO garden_invitations ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (garden_id, email) WHERE status = 'pending' DO NOTHING
+             RETURNING {}", INVITATION_COLUMNS, INVITATION_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &invitation.id, &invitation.garden_id, &invitation.email, &invitation.role, &invitation.invited_by,
+            &invitation.status, &invitation.created_at, &invitation.responded_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(invitation_from_row))
+    }
+
+    // Newest first
+    pub async fn invitations(&self, garden_id: Uuid) -> Result<Vec<GardenInvitation>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM garden_invitations WHERE garden_id = $1 ORDER BY created_at DESC", INVITATION_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&garden_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(invitation_from_row).collect())
+    }
+
+    pub async fn pending_invitations(&self, email: &str) -> Result<Vec<ReceivedInvitation>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT i.*, g.name AS garden_name FROM garden_invitations i
+             JOIN gardens g ON g.id = i.garden_id
+             WHERE i.email = $1 AND i.status = 'pending' ORDER BY i.created_at DESC"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&email]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| ReceivedInvitation {
+            invitation: invitation_from_row(row),
+            garden_name: row.get("garden_name"),
+        }).collect())
+    }
+
+    // Accepting also adds the user to the garden. None when there is no pending invitation for the address.
+    pub async fn respond(&self, id: Uuid, email: &str, user_id: Uuid, accept: bool) -> Result<Option<GardenInvitation>, String> {
+        let mut client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let tx = client.transaction().await.map_err(|e| e.to_string())?;
+        let status = if accept { "accepted" } else { "declined" };
+
+        let row = tx.query_opt(
+            &format!(
+                "UPDATE garden_invitations SET status = $3, responded_at = NOW()
+                 WHERE id = $1 AND email = $2 AND status = 'pending'
+                 RETURNING {}", INVITATION_COLUMNS
+            ),
+            &[&id, &email, &status]
+        ).await.map_err(|e| e.to_string())?;
+        let Some(invitation) = row.as_ref().map(invitation_from_row) else {
+            return Ok(None);
+        };
+        if accept {
+            tx.execute(
+                "INSERT INTO garden_members (garden_id, user_id, role, created_at) VALUES ($1, $2, $3, NOW())
+                 ON CONFLICT (garden_id, user_id) DO NOTHING",
+                &[&invitation.garden_id, &user_id, &invitation.role]
+            ).await.map_err(|e| e.to_string())?;
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(Some(invitation))
+    }
+
+    pub async fn revoke_invitation(&self, garden_id: Uuid, id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "UPDATE garden_invitations SET status = 'revoked', responded_at = NOW()
+             WHERE id = $1 AND garden_id = $2 AND status = 'pending'"
+        ).await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id, &garden_id]).await.map_err(|e| e.to_string())
+    }
+
+    pub async fn plant_ids(&self, garden_id: Uuid) -> Result<Vec<Uuid>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("SELECT plant_id FROM garden_plants WHERE garden_id = $1")
+            .await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&garden_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| row.get("plant_id")).collect())
+    }
+
+    // false when the plant is already in a garden
+    pub async fn add_plant(&self, garden_id: Uuid, plant_id: Uuid, added_by: Uuid) -> Result<bool, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "INSERT INTO garden_plants (plant_id, garden_id, added_by, created_at) VALUES ($1, $2, $3, NOW())
+             ON CONFLICT (plant_id) DO NOTHING"
+        ).await.map_err(|e| e.to_string())?;
+
+        let inserted = client.execute(&stmt, &[&plant_id, &garden_id, &added_by]).await.map_err(|e| e.to_string())?;
+        Ok(inserted > 0)
+    }
+
+    pub async fn remove_plant(&self, garden_id: Uuid, plant_id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM garden_plants WHERE garden_id = $1 AND plant_id = $2")
+            .await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&garden_id, &plant_id]).await.map_err(|e| e.to_string())
+    }
+
+    // The plant's owner and the user's role on it: "owner" for their own plants, otherwise their
+    // role in the garden the plant is in. None when the user cannot see the plant at all.
+    pub async fn plant_access(&self, plant_id: Uuid, user_id: Uuid) -> Result<Option<(Uuid, String)>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT p.user_id, CASE WHEN p.user_id = $2 THEN 'owner' ELSE m.role END AS role
+             FROM plants p
+             LEFT JOIN garden_plants gp ON gp.plant_id = p.id
+             LEFT JOIN garden_members m ON m.garden_id = gp.garden_id AND m.user_id = $2
+             WHERE p.id = $1"
+        ).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&plant_id, &user_id]).await.map_err(|e| e.to_string())?;
+        Ok(row.and_then(|row| {
+            let role: Option<String> = row.get("role");
+            role.map(|role| (row.get("user_id"), role))
+        }))
+    }
+
+    // Members of the garden the plant is in, empty when it is in none
+    pub async fn plant_audience(&self, plant_id: Uuid) -> Result<Vec<Uuid>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT m.user_id FROM garden_plants gp JOIN garden_members m ON m.garden_id = gp.garden_id
+             WHERE gp.plant_id = $1"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| row.get("user_id")).collect())
+    }
+}
@@ -0,0 +1,363 @@
+use crate::models::garden::{Garden, GardenInvitation, GardenMember, MemberGarden, ReceivedInvitation, OWNER};
+use crate::repositories::supabase_client::{Order, Prefer, SupabaseClient};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use serde::Deserialize;
+use serde_json::json;
+
+pub struct GardenSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "gardens";
+const MEMBERS: &str = "garden_members";
+const INVITATIONS: &str = "garden_invitations";
+const PLANTS: &str = "garden_plants";
+
+#[derive(Deserialize)]
+struct RoleRow {
+    role: String,
+}
+
+#[derive(Deserialize)]
+struct UserIdRow {
+    user_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct PlantIdRow {
+    plant_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct GardenIdRow {
+    garden_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct MemberGardenRow {
+    role: String,
+    gardens: Garden,
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    name: String,
+    email: String,
+}
+
+#[derive(Deserialize)]
+struct MemberRow {
+    garden_id: Uuid,
+    user_id: Uuid,
+    role: String,
+    created_at: DateTime<Utc>,
+    users: Profile,
+}
+
+#[derive(Deserialize)]
+struct GardenName {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ReceivedRow {
+    #[serde(flatten)]
+    invitation: GardenInvitation,
+    gardens: GardenName,
+}
+
+impl GardenSupabaseRepo {
+    // The creator joins as the owner. Not atomic over PostgREST: a failed member insert removes the garden again.
+    pub async fn create(&self, garden: &Garden) -> Result<Garden, String> {
+        let mut arr: Vec<Garden> = self.client.from(TABLE).insert(garden).await?;
+        let created = arr.pop().ok_or_else(|| "Failed to create garden".to_string())?;
+
+        let owner = json!({
+            "garden_id": garden.id,
+            "user_id": garden.owner_id,
+            "role": OWNER,
+            "created_at": garden.created_at,
+        });
+        let inserted: Result<Vec<serde_json::Value>, _> = self.client.from(MEMBERS).select("user_id").insert(&owner).await;
+        if let Err(e) = inserted {
+            self.delete(garden.id).await?;
+            return Err(e.into());
+        }
+        Ok(created)
+    }
+
+    pub async fn member_gardens(&self, user_id: Uuid) -> Result<Vec<MemberGarden>, String> {
+        let rows: Vec<MemberGardenRow> = self.client.from(MEMBERS)
+            .select("role,gardens(*)")
+            .eq("user_id", user_id)
+            .get()
+            .await?;
+        let mut gardens = rows.into_iter()
+            .map(|row| MemberGarden { garden: row.gardens, role: row.role })
+            .collect::<Vec<_>>();
+        gardens.sort_by(|a, b| (&a.garden.name, a.garden.created_at).cmp(&(&b.garden.name, b.garden.created_at)));
+        Ok(gardens)
+    }
+
+    // The user's role in the garden, None when they are not a member
+    pub async fn role(&self, garden_id: Uuid, user_id: Uuid) -> Result<Option<String>, String> {
+        let mut arr: Vec<RoleRow> = self.client.from(MEMBERS)
+            .select("role")
+            .eq("garden_id", garden_id)
+            .eq("user_id", user_id)
+            .get()
+            .await?;
+        Ok(arr.pop().map(|row| row.role))
+    }
+
+    // Not scoped to a user, the caller checks the role first
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Option<Garden>, String> {
+        let mut arr: Vec<Garden> = self.client.from(TABLE)
+            .eq("id", id)
+            .get()
+            .await?;
+        Ok(arr.pop())
+    }
+
+    pub async fn rename(&self, id: Uuid, name: &str) -> Result<Option<Garden>, String> {
+        let payload = json!({ "name": name, "updated_at": Utc::now() });
+        let mut arr: Vec<Garden> = self.client.from(TABLE)
+            .eq("id", id)
+            .update(&payload)
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // Members, invitations and the plant list go with it; the plants themselves stay with their owners
+    pub async fn delete(&self, id: Uuid) -> Result<u64, String> {
+        let deleted: Vec<serde_json::Value> = self.client.from(TABLE)
+            .select("id")
+            .eq("id", id)
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    // Owner first, then by name
+    pub async fn members(&self, garden_id: Uuid) -> Result<Vec<GardenMember>, String> {
+        let rows: Vec<MemberRow> = self.client.from(MEMBERS)
+            .select("garden_id,user_id,role,created_at,users(name,email)")
+            .eq("garden_id", garden_id)
+            .get()
+            .await?;
+        let mut members = rows.into_iter().map(|row| GardenMember {
+            garden_id: row.garden_id,
+            user_id: row.user_id,
+            role: row.role,
+            name: row.users.name,
+            email: row.users.email,
+            created_at: row.created_at,
+        }).collect::<Vec<_>>();
+        members.sort_by(|a, b| (a.role != OWNER, &a.name).cmp(&(b.role != OWNER, &b.name)));
+        Ok(members)
+    }
+
+    pub async fn member_ids(&self, garden_id: Uuid) -> Result<Vec<Uuid>, String> {
+        let rows: Vec<UserIdRow> = self.client.from(MEMBERS)
+            .select("user_id")
+            .eq("garden_id", garden_id)
+            .get()
+            .await?;
+        Ok(rows.into_iter().map(|row| row.user_id).collect())
+    }
+
+    // The owner's role never changes
+    pub async fn set_role(&self, garden_id: Uuid, user_id: Uuid, role: &str) -> Result<u64, String> {
+        let updated: Vec<serde_json::Value> = self.client.from(MEMBERS)
+            .select("user_id")
+            .eq("garden_id", garden_id)
+            .eq("user_id", user_id)
+            .neq("role", OWNER)
+            .update(&json!({ "role": role }))
+            .await?;
+        Ok(updated.len() as u64)
+    }
+
+    // The owner cannot leave; a member who does takes their plants out of the garden
+    pub async fn remove_member(&self, garden_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let removed: Vec<serde_json::Value> = self.client.from(MEMBERS)
+            .select("user_id")
+            .eq("garden_id", garden_id)
+            .eq("user_id", user_id)
+            .neq("role", OWNER)
+            .delete()
+            .await?;
+        if removed.is_empty() {
+            return Ok(0);
+        }
+
+        let theirs: Vec<PlantIdRow> = self.client.from(PLANTS)
+            .select("plant_id,plants!inner(user_id)")
+            .eq("garden_id", garden_id)
+            .eq("plants.user_id", user_id)
+            .get()
+            .await?;
+        if !theirs.is_empty() {
+            let ids = theirs.iter().map(|row| row.plant_id).collect::<Vec<_>>();
+            let _: Vec<serde_json::Value> = self.client.from(PLANTS)
+                .select("plant_id")
+                .eq("garden_id", garden_id)
+                .in_list("plant_id", &ids)
+                .delete()
+                .await?;
+        }
+        Ok(removed.len() as u64)
+    }
+
+    // None when the address already has a pending invitation to the garden
+    pub async fn create_invitation(&self, invitation: &GardenInvitation) -> Result<Option<GardenInvitation>, String> {
+        match self.client.from(INVITATIONS).insert::<GardenInvitation, _>(invitation).await {
+            Ok(mut arr) => arr.pop().map(Some).ok_or_else(|| "Failed to create invitation".to_string()),
+            Err(e) if e.is_conflict() => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Newest first
+    pub async fn invitations(&self, garden_id: Uuid) -> Result<Vec<GardenInvitation>, String> {
+        Ok(self.client.from(INVITATIONS)
+            .eq("garden_id", garden_id)
+            .order("created_at", Order::Desc)
+            .get()
+            .await?)
+    }
+
+    pub async fn pending_invitations(&self, email: &str) -> Result<Vec<ReceivedInvitation>, String> {
+        let rows: Vec<ReceivedRow> = self.client.from(INVITATIONS)
+            .select("*,gardens(name)")
+            .eq("email", email)
+            .eq("status", "pending")
+            .order("created_at", Order::Desc)
+            .get()
+            .await?;
+        Ok(rows.into_iter().map(|row| ReceivedInvitation {
+            invitation: row.invitation,
+            garden_name: row.gardens.name,
+        }).collect())
+    }
+
+    // Accepting also adds the user to the garden. None when there is no pending invitation for the address.
+    pub async fn respond(&self, id: Uuid, email: &str, user_id: Uuid, accept: bool) -> Result<Option<GardenInvitation>, String> {
+        let payload = json!({
+            "status": if accept { "accepted" } else { "declined" },
+            "responded_at": Utc::now(),
+        });
+        let mut arr: Vec<GardenInvitation> = self.client.from(INVITATIONS)
+            .eq("id", id)
+            .eq("email", email)
+            .eq("status", "pending")
+            .update(&payload)
+            .await?;
+        let Some(invitation) = arr.pop() else {
+            return Ok(None);
+        };
+
+        if accept {
+            let member = json!({
+                "garden_id": invitation.garden_id,
+                "user_id": user_id,
+                "role": invitation.role,
+                "created_at": Utc::now(),
+            });
+            let _: Vec<serde_json::Value> = self.client.from(MEMBERS)
+                .select("user_id")
+                .on_conflict("garden_id,user_id")
+                .prefer(Prefer::IgnoreDuplicates)
+                .insert(&member)
+                .await?;
+        }
+        Ok(Some(invitation))
+    }
+
+    pub async fn revoke_invitation(&self, garden_id: Uuid, id: Uuid) -> Result<u64, String> {
+        let payload = json!({ "status": "revoked", "responded_at": Utc::now() });
+        let revoked: Vec<serde_json::Value> = self.client.from(INVITATIONS)
+            .select("id")
+            .eq("id", id)
+            .eq("garden_id", garden_id)
+            .eq("status", "pending")
+            .update(&payload)
+            .await?;
+        Ok(revoked.len() as u64)
+    }
+
+    pub async fn plant_ids(&self, garden_id: Uuid) -> Result<Vec<Uuid>, String> {
+        let rows: Vec<PlantIdRow> = self.client.from(PLANTS)
+            .select("plant_id")
+            .eq("garden_id", garden_id)
+            .get()
+            .await?;
+        Ok(rows.into_iter().map(|row| row.plant_id).collect())
+    }
+
+    // false when the plant is already in a garden
+    pub async fn add_plant(&self, garden_id: Uuid, plant_id: Uuid, added_by: Uuid) -> Result<bool, String> {
+        let payload = json!({
+            "plant_id": plant_id,
+            "garden_id": garden_id,
+            "added_by": added_by,
+            "created_at": Utc::now(),
+        });
+        let inserted: Vec<serde_json::Value> = self.client.from(PLANTS)
+            .select("plant_id")
+            .on_conflict("plant_id")
+            .prefer(Prefer::IgnoreDuplicates)
+            .insert(&payload)
+            .await?;
+        Ok(!inserted.is_empty())
+    }
+
+    pub async fn remove_plant(&self, garden_id: Uuid, plant_id: Uuid) -> Result<u64, String> {
+        let removed: Vec<serde_json::Value> = self.client.from(PLANTS)
+            .select("plant_id")
+            .eq("garden_id", garden_id)
+            .eq("plant_id", plant_id)
+            .delete()
+            .await?;
+        Ok(removed.len() as u64)
+    }
+
+    async fn plant_garden(&self, plant_id: Uuid) -> Result<Option<Uuid>, String> {
+        let mut arr: Vec<GardenIdRow> = self.client.from(PLANTS)
+            .select("garden_id")
+            .eq("plant_id", plant_id)
+            .get()
+            .await?;
+        Ok(arr.pop().map(|row| row.garden_id))
+    }
+
+    // The plant's owner and the user's role on it: "owner" for their own plants, otherwise their
+    // role in the garden the plant is in. None when the user cannot see the plant at all.
+    pub async fn plant_access(&self, plant_id: Uuid, user_id: Uuid) -> Result<Option<(Uuid, String)>, String> {
+        let mut arr: Vec<UserIdRow> = self.client.from("plants")
+            .select("user_id")
+            .eq("id", plant_id)
+            .get()
+            .await?;
+        let Some(owner_id) = arr.pop().map(|row| row.user_id) else {
+            return Ok(None);
+        };
+        if owner_id == user_id {
+            return Ok(Some((owner_id, OWNER.to_string())));
+        }
+
+        let Some(garden_id) = self.plant_garden(plant_id).await? else {
+            return Ok(None);
+        };
+        Ok(self.role(garden_id, user_id).await?.map(|role| (owner_id, role)))
+    }
+
+    // Members of the garden the plant is in, empty when it is in none
+    pub async fn plant_audience(&self, plant_id: Uuid) -> Result<Vec<Uuid>, String> {
+        match self.plant_garden(plant_id).await? {
+            Some(garden_id) => self.member_ids(garden_id).await,
+            None => Ok(Vec::new()),
+        }
+    }
+}
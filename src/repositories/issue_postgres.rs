@@ -0,0 +1,124 @@
+use crate::models::plant_issue::{AffectedPlant, PlantIssue};
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+use serde_json::json;
+
+pub struct IssuePostgresRepo {
+    pub pool: Pool,
+}
+
+const COLUMNS: &str = "id, plant_id, user_id, issue_type, severity, description, opened_on, resolved_on, resolution, treatments, created_at, updated_at";
+
+fn from_row(row: &Row) -> Result<PlantIssue, String> {
+    let treatments: serde_json::Value = row.try_get("treatments").unwrap_or_else(|_| json!([]));
+
+    Ok(PlantIssue {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        user_id: row.get("user_id"),
+        issue_type: row.get("issue_type"),
+        severity: row.get("severity"),
+        description: row.get("description"),
+        opened_on: row.get("opened_on"),
+        resolved_on: row.get("resolved_on"),
+        resolution: row.get("resolution"),
+        treatments: serde_json::from_value(treatments).map_err(|e| e.to_string())?,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+impl IssuePostgresRepo {
+    // None when the plant does not exist or belongs to someone else
+    pub async fn create(&self, issue: &PlantIssue) -> Result<Option<PlantIssue>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let treatments = json!(issue.treatments);
+
+        let stmt = client.prepare(&format!(
+            "INSERT INTO plant_issues ({})
+             SELECT $1, id, user_id, $4, $5, $6, $7, $8, $9, $10, $11, $12 FROM plants WHERE id = $2 AND user_id = $3
+             RETURNING {}", COLUMNS, COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &issue.id, &issue.plant_id, &issue.user_id, &issue.issue_type, &issue.severity, &issue.description,
+            &issue.opened_on, &issue.resolved_on, &issue.resolution, &treatments, &issue.created_at, &issue.updated_at
+        ]).await.map_err(|e| e.to_string())?;
+
+        row.as_ref().map(from_row).transpose()
+    }
+
+    // Open issues first, then newest
+    pub async fn get_all_by_plant(&self, plant_id: Uuid, user_id: Uuid, open: Option<bool>) -> Result<Vec<PlantIssue>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM plant_issues
+             WHERE plant_id = $1 AND user_id = $2 AND ($3::bool IS NULL OR (resolved_on IS NULL) = $3)
+             ORDER BY resolved_on IS NOT NULL, opened_on DESC, created_at DESC", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id, &user_id, &open]).await.map_err(|e| e.to_string())?;
+        rows.iter().map(from_row).collect()
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantIssue>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM plant_issues WHERE id = $1 AND plant_id = $2 AND user_id = $3", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id, &plant_id, &user_id]).await.map_err(|e| e.to_string())?;
+        row.as_ref().map(from_row).transpose()
+    }
+
+    // Writes every editable field of an issue loaded with get_by_id
+    pub async fn update(&self, issue: &PlantIssue) -> Result<Option<PlantIssue>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let treatments = json!(issue.treatments);
+
+        let stmt = client.prepare(&format!(
+            "UPDATE plant_issues SET issue_type = $4, severity = $5, description = $6, opened_on = $7, resolved_on = $8,
+                 resolution = $9, treatments = $10, updated_at = $11
+             WHERE id = $1 AND plant_id = $2 AND user_id = $3
+             RETURNING {}", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &issue.id, &issue.plant_id, &issue.user_id, &issue.issue_type, &issue.severity, &issue.description,
+            &issue.opened_on, &issue.resolved_on, &issue.resolution, &treatments, &issue.updated_at
+        ]).await.map_err(|e| e.to_string())?;
+
+        row.as_ref().map(from_row).transpose()
+    }
+
+    pub async fn delete(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM plant_issues WHERE id = $1 AND plant_id = $2 AND user_id = $3")
+            .await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id, &plant_id, &user_id]).await.map_err(|e| e.to_string())
+    }
+
+    // Plants with an open issue of this (normalized) type, most severe first
+    pub async fn affected_plants(&self, user_id: Uuid, issue_type: &str) -> Result<Vec<AffectedPlant>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT p.id AS plant_id, p.name AS plant_name, p.plant_type, i.id AS issue_id, i.severity, i.opened_on
+             FROM plant_issues i
+             JOIN plants p ON p.id = i.plant_id
+             WHERE i.user_id = $1 AND i.issue_type = $2 AND i.resolved_on IS NULL
+             ORDER BY array_position(ARRAY['high', 'medium', 'low'], i.severity), i.opened_on, p.name"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_id, &issue_type]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| AffectedPlant {
+            plant_id: row.get("plant_id"),
+            plant_name: row.get("plant_name"),
+            plant_type: row.get("plant_type"),
+            issue_id: row.get("issue_id"),
+            severity: row.get("severity"),
+            opened_on: row.get("opened_on"),
+        }).collect())
+    }
+}
@@ -0,0 +1,138 @@
+use crate::models::plant_issue::{AffectedPlant, PlantIssue};
+use crate::repositories::supabase_client::{Order, SupabaseClient};
+use uuid::Uuid;
+use serde::Deserialize;
+use serde_json::json;
+use chrono::NaiveDate;
+
+pub struct IssueSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "plant_issues";
+
+#[derive(Deserialize)]
+struct PlantSummary {
+    name: String,
+    plant_type: String,
+}
+
+#[derive(Deserialize)]
+struct AffectedRow {
+    id: Uuid,
+    plant_id: Uuid,
+    severity: String,
+    opened_on: NaiveDate,
+    plants: PlantSummary,
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "high" => 0,
+        "medium" => 1,
+        _ => 2,
+    }
+}
+
+impl IssueSupabaseRepo {
+    // None when the plant does not exist or belongs to someone else
+    pub async fn create(&self, issue: &PlantIssue) -> Result<Option<PlantIssue>, String> {
+        let owned: Vec<serde_json::Value> = self.client.from("plants")
+            .select("id")
+            .eq("id", issue.plant_id)
+            .eq("user_id", issue.user_id)
+            .get()
+            .await?;
+        if owned.is_empty() {
+            return Ok(None);
+        }
+
+        let mut arr: Vec<PlantIssue> = self.client.from(TABLE).insert(issue).await?;
+        arr.pop().map(Some).ok_or_else(|| "Failed to create issue".to_string())
+    }
+
+    // Open issues first, then newest
+    pub async fn get_all_by_plant(&self, plant_id: Uuid, user_id: Uuid, open: Option<bool>) -> Result<Vec<PlantIssue>, String> {
+        let mut query = self.client.from(TABLE)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .order("opened_on", Order::Desc)
+            .order("created_at", Order::Desc);
+        query = match open {
+            Some(true) => query.null("resolved_on"),
+            Some(false) => query.not_null("resolved_on"),
+            None => query,
+        };
+
+        let mut issues: Vec<PlantIssue> = query.get().await?;
+        issues.sort_by_key(|issue| issue.resolved_on.is_some());
+        Ok(issues)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantIssue>, String> {
+        let mut arr: Vec<PlantIssue> = self.client.from(TABLE)
+            .eq("id", id)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .get()
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // Writes every editable field of an issue loaded with get_by_id
+    pub async fn update(&self, issue: &PlantIssue) -> Result<Option<PlantIssue>, String> {
+        let payload = json!({
+            "issue_type": issue.issue_type,
+            "severity": issue.severity,
+            "description": issue.description,
+            "opened_on": issue.opened_on,
+            "resolved_on": issue.resolved_on,
+            "resolution": issue.resolution,
+            "treatments": issue.treatments,
+            "updated_at": issue.updated_at.to_rfc3339(),
+        });
+
+        let mut arr: Vec<PlantIssue> = self.client.from(TABLE)
+            .eq("id", issue.id)
+            .eq("plant_id", issue.plant_id)
+            .eq("user_id", issue.user_id)
+            .update(&payload)
+            .await?;
+        Ok(arr.pop())
+    }
+
+    pub async fn delete(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let deleted: Vec<serde_json::Value> = self.client.from(TABLE)
+            .eq("id", id)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .select("id")
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    // Plants with an open issue of this (normalized) type, most severe first
+    pub async fn affected_plants(&self, user_id: Uuid, issue_type: &str) -> Result<Vec<AffectedPlant>, String> {
+        let rows: Vec<AffectedRow> = self.client.from(TABLE)
+            .select("id,plant_id,severity,opened_on,plants(name,plant_type)")
+            .eq("user_id", user_id)
+            .eq("issue_type", issue_type)
+            .null("resolved_on")
+            .get()
+            .await?;
+
+        let mut affected: Vec<AffectedPlant> = rows.into_iter().map(|row| AffectedPlant {
+            plant_id: row.plant_id,
+            plant_name: row.plants.name,
+            plant_type: row.plants.plant_type,
+            issue_id: row.id,
+            severity: row.severity,
+            opened_on: row.opened_on,
+        }).collect();
+        affected.sort_by(|a, b| {
+            (severity_rank(&a.severity), a.opened_on, &a.plant_name).cmp(&(severity_rank(&b.severity), b.opened_on, &b.plant_name))
+        });
+        Ok(affected)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::services::login_throttle::{AttemptState, AttemptStore};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use deadpool_postgres::Pool;
+
+// Login attempt counters shared by every server instance using the same database
+pub struct LoginAttemptPostgresRepo {
+    pub pool: Pool,
+}
+
+#[async_trait]
+impl AttemptStore for LoginAttemptPostgresRepo {
+    async fn get(&self, key: &str) -> Result<Option<AttemptState>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT failures, last_failed_at, locked_until FROM login_attempts WHERE key = $1"
+        ).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&key]).await.map_err(|e| e.to_string())?;
+        Ok(row.map(|row| AttemptState {
+            failures: row.get::<_, i32>("failures") as u32,
+            last_failed_at: row.get("last_failed_at"),
+            locked_until: row.get("locked_until"),
+        }))
+    }
+
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let window_start = Utc::now() - window;
+
+        // Single upsert so concurrent failures cannot lose increments
+        let stmt = client.prepare(
+            "INSERT INTO login_attempts (key, failures, last_failed_at)
+             VALUES ($1, 1, NOW())
+             ON CONFLICT (key) DO UPDATE SET
+                failures = CASE WHEN login_attempts.last_failed_at < $2 THEN 1 ELSE login_attempts.failures + 1 END,
+                last_failed_at = NOW()
+             RETURNING failures"
+        ).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_one(&stmt, &[&key, &window_start]).await.map_err(|e| e.to_string())?;
+        Ok(row.get::<_, i32>("failures") as u32)
+    }
+
+    async fn lock_until(&self, key: &str, until: DateTime<Utc>) -> Result<(), String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("UPDATE login_attempts SET locked_until = $2 WHERE key = $1")
+            .await.map_err(|e| e.to_string())?;
+        client.execute(&stmt, &[&key, &until]).await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> Result<(), String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM login_attempts WHERE key = $1")
+            .await.map_err(|e| e.to_string())?;
+        client.execute(&stmt, &[&key]).await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
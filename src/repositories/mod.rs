@@ -2,3 +2,23 @@ pub mod plant_postgres;
 pub mod plant_supabase;
 pub mod auth_postgres;
 pub mod auth_supabase;
+pub mod login_attempt_postgres;
+pub mod supabase_client;
+pub mod note_postgres;
+pub mod note_supabase;
+pub mod issue_postgres;
+pub mod issue_supabase;
+pub mod alert_postgres;
+pub mod alert_supabase;
+pub mod sensor_postgres;
+pub mod sensor_supabase;
+pub mod care_event_postgres;
+pub mod care_event_supabase;
+pub mod zone_postgres;
+pub mod zone_supabase;
+pub mod webhook_postgres;
+pub mod webhook_supabase;
+pub mod garden_postgres;
+pub mod garden_supabase;
+pub mod share_postgres;
+pub mod share_supabase;
\ No newline at end of file
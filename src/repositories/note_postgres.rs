@@ -0,0 +1,118 @@
+use crate::models::plant_note::{JournalEntry, PlantNote};
+use crate::dtos::note_dto::{CreateNoteDTO, JournalQuery, UpdateNoteDTO};
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+use chrono::{NaiveDate, Utc};
+
+pub struct NotePostgresRepo {
+    pub pool: Pool,
+}
+
+const COLUMNS: &str = "id, plant_id, user_id, date, text, tags, photo, care_action, created_at, updated_at";
+
+fn from_row(row: &Row) -> PlantNote {
+    PlantNote {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        user_id: row.get("user_id"),
+        date: row.get("date"),
+        text: row.get("text"),
+        tags: row.get("tags"),
+        photo: row.get("photo"),
+        care_action: row.get("care_action"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+impl NotePostgresRepo {
+    // None when the plant does not exist or belongs to someone else
+    pub async fn create(&self, plant_id: Uuid, user_id: Uuid, date: NaiveDate, dto: CreateNoteDTO) -> Result<Option<PlantNote>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let now = Utc::now();
+
+        let stmt = client.prepare(&format!(
+            "INSERT INTO plant_notes (id, plant_id, user_id, date, text, tags, photo, care_action, created_at, updated_at)
+             SELECT $1, id, user_id, $4, $5, $6, $7, $8, $9, $9 FROM plants WHERE id = $2 AND user_id = $3
+             RETURNING {}", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &Uuid::new_v4(), &plant_id, &user_id, &date, &dto.text, &dto.tags, &dto.photo, &dto.care_action, &now
+        ]).await.map_err(|e| e.to_string())?;
+
+        Ok(row.as_ref().map(from_row))
+    }
+
+    // Newest first
+    pub async fn get_all_by_plant(&self, plant_id: Uuid, user_id: Uuid) -> Result<Vec<PlantNote>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM plant_notes WHERE plant_id = $1 AND user_id = $2 ORDER BY date DESC, created_at DESC", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id, &user_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantNote>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM plant_notes WHERE id = $1 AND plant_id = $2 AND user_id = $3", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id, &plant_id, &user_id]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    pub async fn update(&self, id: Uuid, plant_id: Uuid, user_id: Uuid, date: Option<NaiveDate>, dto: UpdateNoteDTO) -> Result<Option<PlantNote>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "UPDATE plant_notes SET date = COALESCE($4, date), text = COALESCE($5, text), tags = COALESCE($6, tags),
+                 photo = COALESCE($7, photo), care_action = COALESCE($8, care_action), updated_at = $9
+             WHERE id = $1 AND plant_id = $2 AND user_id = $3
+             RETURNING {}", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &id, &plant_id, &user_id, &date, &dto.text, &dto.tags, &dto.photo, &dto.care_action, &Utc::now()
+        ]).await.map_err(|e| e.to_string())?;
+
+        Ok(row.as_ref().map(from_row))
+    }
+
+    pub async fn delete(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM plant_notes WHERE id = $1 AND plant_id = $2 AND user_id = $3")
+            .await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id, &plant_id, &user_id]).await.map_err(|e| e.to_string())
+    }
+
+    // Notes across all plants; with a search term the best matches come first, otherwise the newest
+    pub async fn journal(&self, user_id: Uuid, query: &JournalQuery) -> Result<Vec<JournalEntry>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT n.id, n.plant_id, n.user_id, n.date, n.text, n.tags, n.photo, n.care_action, n.created_at, n.updated_at,
+                    p.name AS plant_name
+             FROM plant_notes n
+             JOIN plants p ON p.id = n.plant_id
+             WHERE n.user_id = $1
+               AND ($2::text IS NULL OR n.search @@ websearch_to_tsquery('english', $2))
+               AND ($3::text IS NULL OR n.tags @> ARRAY[$3::text])
+               AND ($4::uuid IS NULL OR n.plant_id = $4)
+               AND ($5::text IS NULL OR n.date >= $5::date)
+               AND ($6::text IS NULL OR n.date <= $6::date)
+             ORDER BY CASE WHEN $2::text IS NULL THEN 0 ELSE ts_rank(n.search, websearch_to_tsquery('english', $2)) END DESC,
+                      n.date DESC, n.created_at DESC
+             LIMIT $7 OFFSET $8"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[
+            &user_id, &query.search(), &query.tag(), &query.plant_id, &query.from, &query.to, &query.limit(), &query.offset()
+        ]).await.map_err(|e| e.to_string())?;
+
+        Ok(rows.iter().map(|row| JournalEntry { note: from_row(row), plant_name: row.get("plant_name") }).collect())
+    }
+}
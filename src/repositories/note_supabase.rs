@@ -0,0 +1,154 @@
+use crate::models::plant_note::{JournalEntry, PlantNote};
+use crate::dtos::note_dto::{CreateNoteDTO, JournalQuery, UpdateNoteDTO};
+use crate::repositories::supabase_client::{Order, SupabaseClient};
+use uuid::Uuid;
+use serde::Deserialize;
+use serde_json::json;
+use chrono::{NaiveDate, Utc};
+
+pub struct NoteSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "plant_notes";
+
+// Leaves out the generated search column
+const COLUMNS: &str = "id,plant_id,user_id,date,text,tags,photo,care_action,created_at,updated_at";
+
+#[derive(Deserialize)]
+struct PlantName {
+    name: String,
+}
+
+// Journal row with the plant embedded through the plant_id foreign key
+#[derive(Deserialize)]
+struct JournalRow {
+    #[serde(flatten)]
+    note: PlantNote,
+    plants: PlantName,
+}
+
+impl NoteSupabaseRepo {
+    // None when the plant does not exist or belongs to someone else
+    pub async fn create(&self, plant_id: Uuid, user_id: Uuid, date: NaiveDate, dto: CreateNoteDTO) -> Result<Option<PlantNote>, String> {
+        let owned: Vec<serde_json::Value> = self.client.from("plants")
+            .select("id")
+            .eq("id", plant_id)
+            .eq("user_id", user_id)
+            .get()
+            .await?;
+        if owned.is_empty() {
+            return Ok(None);
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let payload = json!({
+            "id": Uuid::new_v4(),
+            "plant_id": plant_id,
+            "user_id": user_id,
+            "date": date,
+            "text": dto.text,
+            "tags": dto.tags,
+            "photo": dto.photo,
+            "care_action": dto.care_action,
+            "created_at": now,
+            "updated_at": now,
+        });
+
+        let mut arr: Vec<PlantNote> = self.client.from(TABLE).select(COLUMNS).insert(&payload).await?;
+        arr.pop().map(Some).ok_or_else(|| "Failed to create note".to_string())
+    }
+
+    // Newest first
+    pub async fn get_all_by_plant(&self, plant_id: Uuid, user_id: Uuid) -> Result<Vec<PlantNote>, String> {
+        let arr = self.client.from(TABLE)
+            .select(COLUMNS)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .order("date", Order::Desc)
+            .order("created_at", Order::Desc)
+            .get()
+            .await?;
+        Ok(arr)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantNote>, String> {
+        let mut arr: Vec<PlantNote> = self.client.from(TABLE)
+            .select(COLUMNS)
+            .eq("id", id)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .get()
+            .await?;
+        Ok(arr.pop())
+    }
+
+    pub async fn update(&self, id: Uuid, plant_id: Uuid, user_id: Uuid, date: Option<NaiveDate>, dto: UpdateNoteDTO) -> Result<Option<PlantNote>, String> {
+        let mut payload = serde_json::Map::new();
+        if let Some(date) = date {
+            payload.insert("date".to_string(), json!(date));
+        }
+        if let Some(text) = dto.text {
+            payload.insert("text".to_string(), json!(text));
+        }
+        if let Some(tags) = dto.tags {
+            payload.insert("tags".to_string(), json!(tags));
+        }
+        if let Some(photo) = dto.photo {
+            payload.insert("photo".to_string(), json!(photo));
+        }
+        if let Some(care_action) = dto.care_action {
+            payload.insert("care_action".to_string(), json!(care_action));
+        }
+        payload.insert("updated_at".to_string(), json!(Utc::now().to_rfc3339()));
+
+        let mut arr: Vec<PlantNote> = self.client.from(TABLE)
+            .select(COLUMNS)
+            .eq("id", id)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .update(&payload)
+            .await?;
+        Ok(arr.pop())
+    }
+
+    pub async fn delete(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let deleted: Vec<serde_json::Value> = self.client.from(TABLE)
+            .eq("id", id)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .select("id")
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    // Same filters as on Postgres, but PostgREST cannot order by rank so matches come newest first
+    pub async fn journal(&self, user_id: Uuid, query: &JournalQuery) -> Result<Vec<JournalEntry>, String> {
+        let offset = query.offset() as u64;
+        let mut request = self.client.from(TABLE)
+            .select(&format!("{},plants(name)", COLUMNS))
+            .eq("user_id", user_id)
+            .order("date", Order::Desc)
+            .order("created_at", Order::Desc)
+            .range(offset, offset + query.limit() as u64 - 1);
+        if let Some(search) = query.search() {
+            request = request.text_search("search", "english", search);
+        }
+        if let Some(tag) = query.tag() {
+            request = request.contains("tags", &[tag]);
+        }
+        if let Some(plant_id) = query.plant_id {
+            request = request.eq("plant_id", plant_id);
+        }
+        if let Some(from) = &query.from {
+            request = request.gte("date", from);
+        }
+        if let Some(to) = &query.to {
+            request = request.lte("date", to);
+        }
+
+        let rows: Vec<JournalRow> = request.get().await?;
+        Ok(rows.into_iter().map(|row| JournalEntry { note: row.note, plant_name: row.plants.name }).collect())
+    }
+}
@@ -1,10 +1,12 @@
 // plant_postgres.rs - FIXED VERSION
-use crate::models::plant::Plant;
+use crate::models::plant::{Plant, PlantStatus};
+use crate::models::plant_issue::OpenIssueSummary;
 use crate::dtos::plant_dto::{CreatePlantDTO, UpdatePlantDTO, UpdatePlantStatusDTO};
+use crate::dtos::import_dto::{ImportAction, ImportOutcome, PlantImportRow};
 use deadpool_postgres::Pool;
 use uuid::Uuid;
 use tokio_postgres::Row;
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use serde_json::json;
 
 pub struct PlantPostgresRepo {
@@ -24,6 +26,8 @@ fn from_row(row: Row) -> Result<Plant, String> {
         age: row.get("age"),
         user_id: row.get("user_id"),
         status: status_json,
+        external_id: row.get("external_id"),
+        indoor: row.get("indoor"),
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
     })
@@ -37,14 +41,14 @@ impl PlantPostgresRepo {
         let default_status = json!({});
         
         let stmt = client.prepare(
-            "INSERT INTO plants (id, name, plant_type, image, planted_date, age, user_id, status, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) 
-             RETURNING id, name, plant_type, image, planted_date, age, user_id, status, created_at, updated_at"
+            "INSERT INTO plants (id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) 
+             RETURNING id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at"
         ).await.map_err(|e| e.to_string())?;
         
         let row = client.query_one(&stmt, &[
             &id, &dto.name, &dto.plant_type, &dto.image, &now, &0i32, 
-            &dto.user_id, &default_status, &now, &now
+            &dto.user_id, &default_status, &dto.external_id, &dto.indoor, &now, &now
         ]).await.map_err(|e| e.to_string())?;
         
         from_row(row)
@@ -53,7 +57,7 @@ impl PlantPostgresRepo {
     pub async fn get_all_by_user(&self, user_id: Uuid) -> Result<Vec<Plant>, String> {
         let client = self.pool.get().await.map_err(|e| e.to_string())?;
         let stmt = client.prepare(
-            "SELECT id, name, plant_type, image, planted_date, age, user_id, status, created_at, updated_at 
+            "SELECT id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at 
              FROM plants WHERE user_id = $1 ORDER BY created_at DESC"
         ).await.map_err(|e| e.to_string())?;
         
@@ -70,7 +74,7 @@ impl PlantPostgresRepo {
     pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Plant, String> {
         let client = self.pool.get().await.map_err(|e| e.to_string())?;
         let stmt = client.prepare(
-            "SELECT id, name, plant_type, image, planted_date, age, user_id, status, created_at, updated_at 
+            "SELECT id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at 
              FROM plants WHERE id = $1 AND user_id = $2"
         ).await.map_err(|e| e.to_string())?;
         
@@ -78,6 +82,27 @@ impl PlantPostgresRepo {
         from_row(row)
     }
 
+    // Not scoped to a user; for background jobs and callers that checked access themselves
+    pub async fn get_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Plant>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at 
+             FROM plants WHERE id = ANY($1)"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&ids]).await.map_err(|e| e.to_string())?;
+        rows.into_iter().map(from_row).collect()
+    }
+
+    // (user_id, city) of the given users, for the weather at their plants
+    pub async fn owner_cities(&self, user_ids: &[Uuid]) -> Result<Vec<(Uuid, Option<String>)>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("SELECT id, city FROM users WHERE id = ANY($1)").await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_ids]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| (row.get("id"), row.get("city"))).collect())
+    }
+
     pub async fn update(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantDTO) -> Result<Plant, String> {
         let client = self.pool.get().await.map_err(|e| e.to_string())?;
         let current = self.get_by_id(id, user_id).await?;
@@ -86,16 +111,17 @@ impl PlantPostgresRepo {
         let plant_type = dto.plant_type.unwrap_or(current.plant_type);
         let image = dto.image.or(current.image);
         let status = dto.status.unwrap_or(current.status);
+        let indoor = dto.indoor.unwrap_or(current.indoor);
         let now = Utc::now();
 
         let stmt = client.prepare(
-            "UPDATE plants SET name = $1, plant_type = $2, image = $3, status = $4, updated_at = $5 
+            "UPDATE plants SET name = $1, plant_type = $2, image = $3, status = $4, updated_at = $5, indoor = $8 
              WHERE id = $6 AND user_id = $7 
-             RETURNING id, name, plant_type, image, planted_date, age, user_id, status, created_at, updated_at"
+             RETURNING id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at"
         ).await.map_err(|e| e.to_string())?;
         
         let row = client.query_one(&stmt, &[
-            &name, &plant_type, &image, &status, &now, &id, &user_id
+            &name, &plant_type, &image, &status, &now, &id, &user_id, &indoor
         ]).await.map_err(|e| e.to_string())?;
         
         from_row(row)
@@ -107,11 +133,7 @@ impl PlantPostgresRepo {
         
         let mut status = current.status.clone();
         if let serde_json::Value::Object(mut map) = status {
-            let date_status = map.entry(&dto.date).or_insert_with(|| json!({
-                "watered": false,
-                "fertilized": false,
-                "harvested": false
-            }));
+            let date_status = map.entry(&dto.date).or_insert_with(|| json!(PlantStatus::default()));
             
             // FIX: Remove ref mut - works in edition 2021
             if let serde_json::Value::Object(date_map) = date_status {
@@ -133,7 +155,7 @@ impl PlantPostgresRepo {
         let stmt = client.prepare(
             "UPDATE plants SET status = $1, updated_at = $2 
              WHERE id = $3 AND user_id = $4 
-             RETURNING id, name, plant_type, image, planted_date, age, user_id, status, created_at, updated_at"
+             RETURNING id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at"
         ).await.map_err(|e| e.to_string())?;
         
         let row = client.query_one(&stmt, &[&status, &now, &id, &user_id]).await.map_err(|e| e.to_string())?;
@@ -147,10 +169,92 @@ impl PlantPostgresRepo {
         Ok(res)
     }
 
+    // Inserts the plant as-is (id, status history and dates included), updating it if the id already exists
+    pub async fn restore(&self, plant: &Plant) -> Result<Plant, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "INSERT INTO plants (id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) 
+             ON CONFLICT (id) DO UPDATE SET 
+                name = EXCLUDED.name, plant_type = EXCLUDED.plant_type, image = EXCLUDED.image, 
+                planted_date = EXCLUDED.planted_date, age = EXCLUDED.age, status = EXCLUDED.status, 
+                external_id = EXCLUDED.external_id, indoor = EXCLUDED.indoor, 
+                updated_at = EXCLUDED.updated_at 
+             WHERE plants.user_id = EXCLUDED.user_id 
+             RETURNING id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at"
+        ).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &plant.id, &plant.name, &plant.plant_type, &plant.image, &plant.planted_date, &plant.age,
+            &plant.user_id, &plant.status, &plant.external_id, &plant.indoor, &plant.created_at, &plant.updated_at
+        ]).await.map_err(|e| e.to_string())?;
+
+        match row {
+            Some(row) => from_row(row),
+            None => Err(format!("Plant {} belongs to another user", plant.id)),
+        }
+    }
+
+    // Upserts by (user_id, external_id), rows without one are always inserted. Each row runs under a
+    // savepoint so every failure is reported; the transaction commits only when all rows succeed.
+    // Per row what was done and the written plant; a dry run rolls the rows back after writing them
+    pub async fn import(&self, user_id: Uuid, rows: &[&PlantImportRow], dry_run: bool) -> Result<Vec<ImportOutcome>, String> {
+        let mut client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let mut tx = client.transaction().await.map_err(|e| e.to_string())?;
+
+        let stmt = tx.prepare(
+            "INSERT INTO plants (id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at) 
+             VALUES ($1, $2, $3, $4, NOW(), 0, $5, COALESCE($6::jsonb, '{}'::jsonb), $7, COALESCE($8, FALSE), NOW(), NOW()) 
+             ON CONFLICT (user_id, external_id) DO UPDATE SET 
+                name = EXCLUDED.name, plant_type = EXCLUDED.plant_type, 
+                image = COALESCE($4, plants.image), status = COALESCE($6::jsonb, plants.status), 
+                indoor = COALESCE($8, plants.indoor), 
+                updated_at = NOW() 
+             RETURNING id, name, plant_type, image, planted_date, age, user_id, status, external_id, indoor, created_at, updated_at, 
+                (xmax = 0) AS inserted"
+        ).await.map_err(|e| e.to_string())?;
+
+        let mut outcomes = Vec::with_capacity(rows.len());
+        for row in rows {
+            let savepoint = tx.savepoint("import_row").await.map_err(|e| e.to_string())?;
+            let result = savepoint.query_one(&stmt, &[
+                &Uuid::new_v4(), &row.name, &row.plant_type, &row.image, &user_id, &row.status, &row.external_id, &row.indoor
+            ]).await;
+
+            match result {
+                Ok(row) => {
+                    savepoint.commit().await.map_err(|e| e.to_string())?;
+                    let action = if row.get("inserted") { ImportAction::Created } else { ImportAction::Updated };
+                    outcomes.push(Ok((action, Some(from_row(row)?))));
+                }
+                Err(e) => {
+                    savepoint.rollback().await.map_err(|e| e.to_string())?;
+                    outcomes.push(Err(e.to_string()));
+                }
+            }
+        }
+
+        if dry_run || outcomes.iter().any(|o| o.is_err()) {
+            tx.rollback().await.map_err(|e| e.to_string())?;
+        } else {
+            tx.commit().await.map_err(|e| e.to_string())?;
+        }
+        Ok(outcomes)
+    }
+
     // Dashboard specific queries
     pub async fn get_dashboard_stats(&self, user_id: Uuid, date: &str) -> Result<serde_json::Value, String> {
-        let _client = self.pool.get().await.map_err(|e| e.to_string())?; // FIX: Add underscore
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
         let plants = self.get_all_by_user(user_id).await?;
+
+        let stmt = client.prepare(
+            "SELECT issue_type, severity, plant_id FROM plant_issues WHERE user_id = $1 AND resolved_on IS NULL"
+        ).await.map_err(|e| e.to_string())?;
+        let open_issues = OpenIssueSummary::from_open(
+            client.query(&stmt, &[&user_id]).await.map_err(|e| e.to_string())?
+                .iter()
+                .map(|row| (row.get("issue_type"), row.get("severity"), row.get("plant_id")))
+        );
         
         let mut watered_count = 0;
         let mut fertilized_count = 0;
@@ -159,20 +263,14 @@ impl PlantPostgresRepo {
         
         for plant in &plants {
             if let Some(date_status) = plant.status.get(date) {
-                if let Some(watered) = date_status.get("watered") {
-                    if watered.as_bool().unwrap_or(false) {
-                        watered_count += 1;
-                    }
+                if date_status.get("watered").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    watered_count += 1;
                 }
-                if let Some(fertilized) = date_status.get("fertilized") {
-                    if fertilized.as_bool().unwrap_or(false) {
-                        fertilized_count += 1;
-                    }
+                if date_status.get("fertilized").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    fertilized_count += 1;
                 }
-                if let Some(harvested) = date_status.get("harvested") {
-                    if harvested.as_bool().unwrap_or(false) {
-                        harvested_count += 1;
-                    }
+                if date_status.get("harvested").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    harvested_count += 1;
                 }
             }
         }
@@ -184,6 +282,7 @@ impl PlantPostgresRepo {
             "harvested_today": harvested_count,
             "need_watering": total_plants - watered_count,
             "need_fertilizing": total_plants - fertilized_count,
+            "open_issues": open_issues,
             "ready_to_harvest": plants.iter().filter(|p| {
                 if let Some(date_status) = p.status.get(date) {
                     let watered = date_status.get("watered").and_then(|v| v.as_bool()).unwrap_or(false);
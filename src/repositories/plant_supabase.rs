@@ -1,27 +1,28 @@
 // plant_supabase.rs - FIXED VERSION
-use crate::models::plant::Plant;
+use crate::models::plant::{Plant, PlantStatus};
+use crate::models::plant_issue::OpenIssueSummary;
 use crate::dtos::plant_dto::{CreatePlantDTO, UpdatePlantDTO, UpdatePlantStatusDTO};
-use reqwest::Client;
+use crate::dtos::import_dto::{ImportAction, ImportOutcome, PlantImportRow};
+use crate::repositories::supabase_client::{Order, Prefer, SupabaseClient};
 use uuid::Uuid;
+use serde::Deserialize;
 use serde_json::json;
 use chrono::Utc;
 
 pub struct PlantSupabaseRepo {
-    pub project_url: String,
-    pub api_key: String,
+    pub client: SupabaseClient,
 }
 
-impl PlantSupabaseRepo {
-    fn base_url(&self) -> String {
-        let mut url = self.project_url.clone();
-        if !url.ends_with("/rest/v1") {
-            url = format!("{}/rest/v1", url.trim_end_matches('/'));
-        }
-        format!("{}/plants", url)
-    }
+const TABLE: &str = "plants";
+
+#[derive(Deserialize)]
+struct OwnerCityRow {
+    id: Uuid,
+    city: Option<String>,
+}
 
+impl PlantSupabaseRepo {
     pub async fn add(&self, dto: CreatePlantDTO) -> Result<Plant, String> {
-        let client = Client::new();
         let id = Uuid::new_v4();
         let now = Utc::now();
         
@@ -34,67 +35,59 @@ impl PlantSupabaseRepo {
             "age": 0,
             "user_id": dto.user_id,
             "status": json!({}),
+            "external_id": dto.external_id,
+            "indoor": dto.indoor,
             "created_at": now.to_rfc3339(),
             "updated_at": now.to_rfc3339(),
         });
         
-        let res = client
-            .post(&self.base_url())
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .header("Content-Type", "application/json")
-            .header("Prefer", "return=representation")
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let status = res.status();
-        let text = res.text().await.map_err(|e| e.to_string())?;
-        if !status.is_success() {
-            return Err(format!("Supabase error: {}", text));
-        }
-        
-        let mut arr: Vec<Plant> = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        let mut arr: Vec<Plant> = self.client.from(TABLE).insert(&payload).await?;
         arr.pop().ok_or_else(|| "Failed to create plant".to_string())
     }
 
     pub async fn get_all_by_user(&self, user_id: Uuid) -> Result<Vec<Plant>, String> {
-        let client = Client::new();
-        let url = format!("{}?user_id=eq.{}&order=created_at.desc", self.base_url(), user_id);
-        
-        let res = client
-            .get(&url)
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-            
-        let arr: Vec<Plant> = res.json().await.map_err(|e| e.to_string())?;
+        let arr = self.client.from(TABLE)
+            .eq("user_id", user_id)
+            .order("created_at", Order::Desc)
+            .get()
+            .await?;
         Ok(arr)
     }
 
     pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Plant, String> {
-        let client = Client::new();
-        let url = format!("{}?id=eq.{}&user_id=eq.{}", self.base_url(), id, user_id);
-        
-        let res = client
-            .get(&url)
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-            
-        let mut arr: Vec<Plant> = res.json().await.map_err(|e| e.to_string())?;
+        let mut arr: Vec<Plant> = self.client.from(TABLE)
+            .eq("id", id)
+            .eq("user_id", user_id)
+            .get()
+            .await?;
         arr.pop().ok_or_else(|| "Plant not found".to_string())
     }
 
+    // Not scoped to a user; for background jobs and callers that checked access themselves
+    pub async fn get_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Plant>, String> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(self.client.from(TABLE)
+            .in_list("id", ids)
+            .get()
+            .await?)
+    }
+
+    // (user_id, city) of the given users, for the weather at their plants
+    pub async fn owner_cities(&self, user_ids: &[Uuid]) -> Result<Vec<(Uuid, Option<String>)>, String> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rows: Vec<OwnerCityRow> = self.client.from("users")
+            .select("id,city")
+            .in_list("id", user_ids)
+            .get()
+            .await?;
+        Ok(rows.into_iter().map(|row| (row.id, row.city)).collect())
+    }
+
     pub async fn update(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantDTO) -> Result<Plant, String> {
-        let client = Client::new();
-        let url = format!("{}?id=eq.{}&user_id=eq.{}", self.base_url(), id, user_id);
-        
         let mut payload = serde_json::Map::new();
         if let Some(name) = dto.name { 
             payload.insert("name".to_string(), json!(name)); 
@@ -108,20 +101,16 @@ impl PlantSupabaseRepo {
         if let Some(status) = dto.status { 
             payload.insert("status".to_string(), status); 
         }
+        if let Some(indoor) = dto.indoor {
+            payload.insert("indoor".to_string(), json!(indoor));
+        }
         payload.insert("updated_at".to_string(), json!(Utc::now().to_rfc3339()));
 
-        let res = client
-            .patch(&url)
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .header("Content-Type", "application/json")
-            .header("Prefer", "return=representation")
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let mut arr: Vec<Plant> = res.json().await.map_err(|e| e.to_string())?;
+        let mut arr: Vec<Plant> = self.client.from(TABLE)
+            .eq("id", id)
+            .eq("user_id", user_id)
+            .update(&payload)
+            .await?;
         arr.pop().ok_or_else(|| "Plant not found/updated".to_string())
     }
 
@@ -132,11 +121,7 @@ impl PlantSupabaseRepo {
         let mut status = current.status.clone();
         // FIX: Simplified pattern matching for Rust 2021
         if let serde_json::Value::Object(mut map) = status {
-            let date_status = map.entry(&dto.date).or_insert_with(|| json!({
-                "watered": false,
-                "fertilized": false,
-                "harvested": false
-            }));
+            let date_status = map.entry(&dto.date).or_insert_with(|| json!(PlantStatus::default()));
             
             if let serde_json::Value::Object(date_map) = date_status {
                 date_map.insert(dto.status_type, json!(dto.value));
@@ -157,33 +142,115 @@ impl PlantSupabaseRepo {
             plant_type: None,
             image: None,
             status: Some(status),
+            indoor: None,
         };
         
         self.update(id, user_id, update_dto).await
     }
 
     pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
-        let client = Client::new();
-        let url = format!("{}?id=eq.{}&user_id=eq.{}", self.base_url(), id, user_id);
-        
-        let res = client
-            .delete(&url)
-            .bearer_auth(&self.api_key)
-            .header("apikey", &self.api_key)
-            .header("Prefer", "return=representation")
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if res.status().is_success() {
-            Ok(1)
-        } else {
-            Err("Failed to delete plant".to_string())
+        let deleted: Vec<serde_json::Value> = self.client.from(TABLE)
+            .eq("id", id)
+            .eq("user_id", user_id)
+            .select("id")
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    // Inserts the plant as-is (id, status history and dates included), updating it if the id already exists
+    pub async fn restore(&self, plant: &Plant) -> Result<Plant, String> {
+        let mut arr: Vec<Plant> = self.client.from(TABLE)
+            .on_conflict("id")
+            .prefer(Prefer::MergeDuplicates)
+            .insert(plant)
+            .await?;
+        arr.pop().ok_or_else(|| "Failed to restore plant".to_string())
+    }
+
+    // PostgREST cannot span requests with a transaction, so rows are written one by one and a
+    // failing row does not undo the others. A dry run only looks up which rows would be updated.
+    // Per row what was done and the written plant, which a dry run does not have
+    pub async fn import(&self, user_id: Uuid, rows: &[&PlantImportRow], dry_run: bool) -> Result<Vec<ImportOutcome>, String> {
+        let mut outcomes = Vec::with_capacity(rows.len());
+        for row in rows {
+            let existing: Vec<Plant> = match &row.external_id {
+                Some(external_id) => self.client.from(TABLE)
+                    .eq("user_id", user_id)
+                    .eq("external_id", external_id)
+                    .get()
+                    .await?,
+                None => Vec::new(),
+            };
+
+            if dry_run {
+                outcomes.push(Ok((if existing.is_empty() { ImportAction::Created } else { ImportAction::Updated }, None)));
+                continue;
+            }
+
+            let now = Utc::now().to_rfc3339();
+            let result = match existing.first() {
+                Some(plant) => {
+                    let mut payload = serde_json::Map::new();
+                    payload.insert("name".to_string(), json!(row.name));
+                    payload.insert("plant_type".to_string(), json!(row.plant_type));
+                    if let Some(image) = &row.image {
+                        payload.insert("image".to_string(), json!(image));
+                    }
+                    if let Some(status) = &row.status {
+                        payload.insert("status".to_string(), status.clone());
+                    }
+                    if let Some(indoor) = row.indoor {
+                        payload.insert("indoor".to_string(), json!(indoor));
+                    }
+                    payload.insert("updated_at".to_string(), json!(now));
+
+                    self.client.from(TABLE)
+                        .eq("id", plant.id)
+                        .eq("user_id", user_id)
+                        .update::<Plant, _>(&payload)
+                        .await
+                        .map(|mut plants| (ImportAction::Updated, plants.pop()))
+                }
+                None => self.client.from(TABLE)
+                    .insert::<Plant, _>(&json!({
+                        "id": Uuid::new_v4(),
+                        "name": row.name,
+                        "plant_type": row.plant_type,
+                        "image": row.image,
+                        "planted_date": now,
+                        "age": 0,
+                        "user_id": user_id,
+                        "status": row.status.clone().unwrap_or_else(|| json!({})),
+                        "external_id": row.external_id,
+                        "indoor": row.indoor.unwrap_or(false),
+                        "created_at": now,
+                        "updated_at": now,
+                    }))
+                    .await
+                    .map(|mut plants| (ImportAction::Created, plants.pop())),
+            };
+            outcomes.push(result.map_err(|e| e.to_string()));
         }
+        Ok(outcomes)
     }
 
     pub async fn get_dashboard_stats(&self, user_id: Uuid, date: &str) -> Result<serde_json::Value, String> {
+        #[derive(serde::Deserialize)]
+        struct OpenIssue {
+            issue_type: String,
+            severity: String,
+            plant_id: Uuid,
+        }
+
         let plants = self.get_all_by_user(user_id).await?;
+        let open: Vec<OpenIssue> = self.client.from("plant_issues")
+            .select("issue_type,severity,plant_id")
+            .eq("user_id", user_id)
+            .null("resolved_on")
+            .get()
+            .await?;
+        let open_issues = OpenIssueSummary::from_open(open.into_iter().map(|i| (i.issue_type, i.severity, i.plant_id)));
         
         let mut watered_count = 0;
         let mut fertilized_count = 0;
@@ -192,20 +259,14 @@ impl PlantSupabaseRepo {
         
         for plant in &plants {
             if let Some(date_status) = plant.status.get(date) {
-                if let Some(watered) = date_status.get("watered") {
-                    if watered.as_bool().unwrap_or(false) {
-                        watered_count += 1;
-                    }
+                if date_status.get("watered").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    watered_count += 1;
                 }
-                if let Some(fertilized) = date_status.get("fertilized") {
-                    if fertilized.as_bool().unwrap_or(false) {
-                        fertilized_count += 1;
-                    }
+                if date_status.get("fertilized").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    fertilized_count += 1;
                 }
-                if let Some(harvested) = date_status.get("harvested") {
-                    if harvested.as_bool().unwrap_or(false) {
-                        harvested_count += 1;
-                    }
+                if date_status.get("harvested").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    harvested_count += 1;
                 }
             }
         }
@@ -217,6 +278,7 @@ impl PlantSupabaseRepo {
             "harvested_today": harvested_count,
             "need_watering": total_plants - watered_count,
             "need_fertilizing": total_plants - fertilized_count,
+            "open_issues": open_issues,
             "ready_to_harvest": plants.iter().filter(|p| {
                 if let Some(date_status) = p.status.get(date) {
                     let watered = date_status.get("watered").and_then(|v| v.as_bool()).unwrap_or(false);
@@ -0,0 +1,225 @@
+use crate::models::sensor::{MetricSummary, PlantThresholds, ReadingBucket, SensorDevice, SensorReading};
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+
+pub struct SensorPostgresRepo {
+    pub pool: Pool,
+}
+
+const DEVICE_COLUMNS: &str = "id, user_id, name, key_prefix, last_seen_at, created_at";
+
+fn device_from_row(row: &Row) -> SensorDevice {
+    SensorDevice {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        key_prefix: row.get("key_prefix"),
+        last_seen_at: row.get("last_seen_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn thresholds_from_row(row: &Row) -> PlantThresholds {
+    PlantThresholds {
+        plant_id: row.get("plant_id"),
+        user_id: row.get("user_id"),
+        moisture_min_pct: row.get("moisture_min_pct"),
+        watering_rise_pct: row.get("watering_rise_pct"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn metric(row: &Row, name: &str) -> MetricSummary {
+    MetricSummary {
+        min: row.get(format!("{}_min", name).as_str()),
+        avg: row.get(format!("{}_avg", name).as_str()),
+        max: row.get(format!("{}_max", name).as_str()),
+    }
+}
+
+impl SensorPostgresRepo {
+    pub async fn create_device(&self, device: &SensorDevice, key_hash: &str) -> Result<SensorDevice, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO sensor_devices (id, user_id, name, key_hash, key_prefix, last_seen_at, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {}", DEVICE_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_one(&stmt, &[
+            &device.id, &device.user_id, &device.name, &key_hash, &device.key_prefix, &device.last_seen_at, &device.created_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(device_from_row(&row))
+    }
+
+    pub async fn get_devices(&self, user_id: Uuid) -> Result<Vec<SensorDevice>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM sensor_devices WHERE user_id = $1 ORDER BY created_at", DEVICE_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(device_from_row).collect())
+    }
+
+    // Removes the device together with its readings
+    pub async fn delete_device(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "DELETE FROM sensor_devices WHERE id = $1 AND user_id = $2"
+        ).await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id, &user_id]).await.map_err(|e| e.to_string())
+    }
+
+    // Also records that the device has been seen
+    pub async fn device_by_key_hash(&self, key_hash: &str) -> Result<Option<SensorDevice>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "UPDATE sensor_devices SET last_seen_at = NOW() WHERE key_hash = $1 RETURNING {}", DEVICE_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&key_hash]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(device_from_row))
+    }
+
+    // For bridges that identify devices by id instead of API key; also records that the device has been seen
+    pub async fn device_by_id(&self, id: Uuid) -> Result<Option<SensorDevice>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "UPDATE sensor_devices SET last_seen_at = NOW() WHERE id = $1 RETURNING {}", DEVICE_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(device_from_row))
+    }
+
+    // The subset of plant_ids that belong to the user
+    pub async fn owned_plants(&self, user_id: Uuid, plant_ids: &[Uuid]) -> Result<Vec<Uuid>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT id FROM plants WHERE user_id = $1 AND id = ANY($2)"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_id, &plant_ids]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+
+    // Returns how many readings were new
+    pub async fn insert_readings(&self, readings: &[SensorReading]) -> Result<usize, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "INSERT INTO sensor_readings (device_id, plant_id, recorded_at, moisture_pct, temperature_c, light_lux)
+             SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::timestamptz[], $4::float8[], $5::float8[], $6::float8[])
+             ON CONFLICT (device_id, plant_id, recorded_at) DO NOTHING"
+        ).await.map_err(|e| e.to_string())?;
+
+        let device_ids: Vec<Uuid> = readings.iter().map(|r| r.device_id).collect();
+        let plant_ids: Vec<Uuid> = readings.iter().map(|r| r.plant_id).collect();
+        let recorded_at: Vec<DateTime<Utc>> = readings.iter().map(|r| r.recorded_at).collect();
+        let moisture: Vec<Option<f64>> = readings.iter().map(|r| r.moisture_pct).collect();
+        let temperature: Vec<Option<f64>> = readings.iter().map(|r| r.temperature_c).collect();
+        let light: Vec<Option<f64>> = readings.iter().map(|r| r.light_lux).collect();
+
+        let stored = client.execute(&stmt, &[&device_ids, &plant_ids, &recorded_at, &moisture, &temperature, &light])
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(stored as usize)
+    }
+
+    // Buckets are aligned to multiples of bucket_secs since the Unix epoch; empty buckets are left out
+    pub async fn readings(&self, plant_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, bucket_secs: i64) -> Result<Vec<ReadingBucket>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT to_timestamp(floor(extract(epoch FROM recorded_at)::float8 / $4) * $4) AS start, count(*) AS count,
+                    min(moisture_pct) AS moisture_min, avg(moisture_pct) AS moisture_avg, max(moisture_pct) AS moisture_max,
+                    min(temperature_c) AS temperature_min, avg(temperature_c) AS temperature_avg, max(temperature_c) AS temperature_max,
+                    min(light_lux) AS light_min, avg(light_lux) AS light_avg, max(light_lux) AS light_max
+             FROM sensor_readings
+             WHERE plant_id = $1 AND recorded_at >= $2 AND recorded_at < $3
+             GROUP BY 1 ORDER BY 1"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id, &from, &to, &(bucket_secs as f64)]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| ReadingBucket {
+            start: row.get("start"),
+            count: row.get("count"),
+            moisture_pct: metric(row, "moisture"),
+            temperature_c: metric(row, "temperature"),
+            light_lux: metric(row, "light"),
+        }).collect())
+    }
+
+    // Every reading of a plant, oldest first, for account exports
+    pub async fn all_readings(&self, plant_id: Uuid) -> Result<Vec<SensorReading>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT device_id, plant_id, recorded_at, moisture_pct, temperature_c, light_lux
+             FROM sensor_readings WHERE plant_id = $1 ORDER BY recorded_at"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| SensorReading {
+            device_id: row.get("device_id"),
+            plant_id: row.get("plant_id"),
+            recorded_at: row.get("recorded_at"),
+            moisture_pct: row.get("moisture_pct"),
+            temperature_c: row.get("temperature_c"),
+            light_lux: row.get("light_lux"),
+        }).collect())
+    }
+
+    // Moisture readings of a plant from all of its devices, oldest first
+    pub async fn moisture_series(&self, plant_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<(DateTime<Utc>, f64)>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT recorded_at, moisture_pct FROM sensor_readings
+             WHERE plant_id = $1 AND recorded_at BETWEEN $2 AND $3 AND moisture_pct IS NOT NULL
+             ORDER BY recorded_at"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id, &from, &to]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| (row.get("recorded_at"), row.get("moisture_pct"))).collect())
+    }
+
+    // (device_id, plant_id) pairs with readings since `since`
+    pub async fn device_plants(&self, since: DateTime<Utc>) -> Result<Vec<(Uuid, Uuid)>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT DISTINCT device_id, plant_id FROM sensor_readings WHERE recorded_at >= $1"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&since]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| (row.get("device_id"), row.get("plant_id"))).collect())
+    }
+
+    pub async fn thresholds(&self, plant_ids: &[Uuid]) -> Result<Vec<PlantThresholds>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT plant_id, user_id, moisture_min_pct, watering_rise_pct, updated_at FROM plant_thresholds WHERE plant_id = ANY($1)"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_ids]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(thresholds_from_row).collect())
+    }
+
+    pub async fn set_thresholds(&self, thresholds: &PlantThresholds) -> Result<PlantThresholds, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "INSERT INTO plant_thresholds (plant_id, user_id, moisture_min_pct, watering_rise_pct, updated_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (plant_id) DO UPDATE SET
+                moisture_min_pct = EXCLUDED.moisture_min_pct,
+                watering_rise_pct = EXCLUDED.watering_rise_pct,
+                updated_at = EXCLUDED.updated_at
+             RETURNING plant_id, user_id, moisture_min_pct, watering_rise_pct, updated_at"
+        ).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_one(&stmt, &[
+            &thresholds.plant_id, &thresholds.user_id, &thresholds.moisture_min_pct, &thresholds.watering_rise_pct, &thresholds.updated_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(thresholds_from_row(&row))
+    }
+}
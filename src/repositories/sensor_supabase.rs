@@ -0,0 +1,203 @@
+use crate::models::sensor::{PlantThresholds, SensorDevice, SensorReading};
+use crate::repositories::supabase_client::{Order, Prefer, SupabaseClient};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use serde::Deserialize;
+use serde_json::json;
+
+pub struct SensorSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const DEVICES: &str = "sensor_devices";
+const READINGS: &str = "sensor_readings";
+const THRESHOLDS: &str = "plant_thresholds";
+const DEVICE_COLUMNS: &str = "id,user_id,name,key_prefix,last_seen_at,created_at";
+
+// PostgREST returns at most this many rows per request by default
+const PAGE_SIZE: u64 = 1000;
+
+#[derive(Deserialize)]
+struct IdRow {
+    id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct DevicePlantRow {
+    device_id: Uuid,
+    plant_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct MoistureRow {
+    recorded_at: DateTime<Utc>,
+    moisture_pct: f64,
+}
+
+impl SensorSupabaseRepo {
+    pub async fn create_device(&self, device: &SensorDevice, key_hash: &str) -> Result<SensorDevice, String> {
+        let payload = json!({
+            "id": device.id,
+            "user_id": device.user_id,
+            "name": device.name,
+            "key_hash": key_hash,
+            "key_prefix": device.key_prefix,
+            "last_seen_at": device.last_seen_at,
+            "created_at": device.created_at,
+        });
+        let mut arr: Vec<SensorDevice> = self.client.from(DEVICES)
+            .select(DEVICE_COLUMNS)
+            .insert(&payload)
+            .await?;
+        arr.pop().ok_or_else(|| "Failed to register device".to_string())
+    }
+
+    pub async fn get_devices(&self, user_id: Uuid) -> Result<Vec<SensorDevice>, String> {
+        Ok(self.client.from(DEVICES)
+            .select(DEVICE_COLUMNS)
+            .eq("user_id", user_id)
+            .order("created_at", Order::Asc)
+            .get()
+            .await?)
+    }
+
+    // Removes the device together with its readings
+    pub async fn delete_device(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let deleted: Vec<IdRow> = self.client.from(DEVICES)
+            .select("id")
+            .eq("id", id)
+            .eq("user_id", user_id)
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    // Also records that the device has been seen
+    pub async fn device_by_key_hash(&self, key_hash: &str) -> Result<Option<SensorDevice>, String> {
+        let mut arr: Vec<SensorDevice> = self.client.from(DEVICES)
+            .select(DEVICE_COLUMNS)
+            .eq("key_hash", key_hash)
+            .update(&json!({ "last_seen_at": Utc::now() }))
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // For bridges that identify devices by id instead of API key; also records that the device has been seen
+    pub async fn device_by_id(&self, id: Uuid) -> Result<Option<SensorDevice>, String> {
+        let mut arr: Vec<SensorDevice> = self.client.from(DEVICES)
+            .select(DEVICE_COLUMNS)
+            .eq("id", id)
+            .update(&json!({ "last_seen_at": Utc::now() }))
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // The subset of plant_ids that belong to the user
+    pub async fn owned_plants(&self, user_id: Uuid, plant_ids: &[Uuid]) -> Result<Vec<Uuid>, String> {
+        let rows: Vec<IdRow> = self.client.from("plants")
+            .select("id")
+            .eq("user_id", user_id)
+            .in_list("id", plant_ids)
+            .get()
+            .await?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    // Returns how many readings were new
+    pub async fn insert_readings(&self, readings: &[SensorReading]) -> Result<usize, String> {
+        let stored: Vec<SensorReading> = self.client.from(READINGS)
+            .on_conflict("device_id,plant_id,recorded_at")
+            .prefer(Prefer::IgnoreDuplicates)
+            .insert(readings)
+            .await?;
+        Ok(stored.len())
+    }
+
+    // Raw readings, oldest first; the service downsamples them
+    pub async fn readings(&self, plant_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<SensorReading>, String> {
+        let mut readings = Vec::new();
+        loop {
+            let offset = readings.len() as u64;
+            let page: Vec<SensorReading> = self.client.from(READINGS)
+                .eq("plant_id", plant_id)
+                .gte("recorded_at", from.to_rfc3339())
+                .lt("recorded_at", to.to_rfc3339())
+                .order("recorded_at", Order::Asc)
+                .range(offset, offset + PAGE_SIZE - 1)
+                .get()
+                .await?;
+            let last_page = (page.len() as u64) < PAGE_SIZE;
+            readings.extend(page);
+            if last_page {
+                return Ok(readings);
+            }
+        }
+    }
+
+    // Moisture readings of a plant from all of its devices, oldest first
+    pub async fn moisture_series(&self, plant_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<(DateTime<Utc>, f64)>, String> {
+        let mut series = Vec::new();
+        loop {
+            let offset = series.len() as u64;
+            let page: Vec<MoistureRow> = self.client.from(READINGS)
+                .select("recorded_at,moisture_pct")
+                .eq("plant_id", plant_id)
+                .gte("recorded_at", from.to_rfc3339())
+                .lte("recorded_at", to.to_rfc3339())
+                .not_null("moisture_pct")
+                .order("recorded_at", Order::Asc)
+                .range(offset, offset + PAGE_SIZE - 1)
+                .get()
+                .await?;
+            let last_page = (page.len() as u64) < PAGE_SIZE;
+            series.extend(page.into_iter().map(|row| (row.recorded_at, row.moisture_pct)));
+            if last_page {
+                return Ok(series);
+            }
+        }
+    }
+
+    // (device_id, plant_id) pairs with readings since `since`; PostgREST has no DISTINCT, so they are deduplicated here
+    pub async fn device_plants(&self, since: DateTime<Utc>) -> Result<Vec<(Uuid, Uuid)>, String> {
+        let mut pairs = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page: Vec<DevicePlantRow> = self.client.from(READINGS)
+                .select("device_id,plant_id")
+                .gte("recorded_at", since.to_rfc3339())
+                .order("recorded_at", Order::Asc)
+                .range(offset, offset + PAGE_SIZE - 1)
+                .get()
+                .await?;
+            let last_page = (page.len() as u64) < PAGE_SIZE;
+            offset += page.len() as u64;
+            for row in page {
+                if !pairs.contains(&(row.device_id, row.plant_id)) {
+                    pairs.push((row.device_id, row.plant_id));
+                }
+            }
+            if last_page {
+                return Ok(pairs);
+            }
+        }
+    }
+
+    pub async fn thresholds(&self, plant_ids: &[Uuid]) -> Result<Vec<PlantThresholds>, String> {
+        if plant_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(self.client.from(THRESHOLDS)
+            .in_list("plant_id", plant_ids)
+            .get()
+            .await?)
+    }
+
+    pub async fn set_thresholds(&self, thresholds: &PlantThresholds) -> Result<PlantThresholds, String> {
+        let mut arr: Vec<PlantThresholds> = self.client.from(THRESHOLDS)
+            .on_conflict("plant_id")
+            .prefer(Prefer::MergeDuplicates)
+            .insert(thresholds)
+            .await?;
+        arr.pop().ok_or_else(|| "Failed to save thresholds".to_string())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::models::plant_share::{GardenShare, PlantShare};
+use chrono::Utc;
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+
+pub struct SharePostgresRepo {
+    pub pool: Pool,
+}
+
+const COLUMNS: &str = "id, plant_id, user_id, token, expires_at, created_at";
+const GARDEN_COLUMNS: &str = "id, garden_id, user_id, token, expires_at, created_at";
+
+fn from_row(row: &Row) -> PlantShare {
+    PlantShare {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        user_id: row.get("user_id"),
+        token: row.get("token"),
+        expires_at: row.get("expires_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn garden_from_row(row: &Row) -> GardenShare {
+    GardenShare {
+        id: row.get("id"),
+        garden_id: row.get("garden_id"),
+        user_id: row.get("user_id"),
+        token: row.get("token"),
+        expires_at: row.get("expires_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+impl SharePostgresRepo {
+    // None when the plant does not exist or belongs to someone else
+    pub async fn create(&self, share: &PlantShare) -> Result<Option<PlantShare>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO plant_shares (id, plant_id, user_id, token, expires_at, created_at)
+             SELECT $1, id, user_id, $4, $5, $6 FROM plants WHERE id = $2 AND user_id = $3
+             RETURNING {}", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &share.id, &share.plant_id, &share.user_id, &share.token, &share.expires_at, &share.created_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    // Newest first, expired ones included so the owner can see and clean them up
+    pub async fn get_all_by_plant(&self, plant_id: Uuid, user_id: Uuid) -> Result<Vec<PlantShare>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM plant_shares WHERE plant_id = $1 AND user_id = $2 ORDER BY created_at DESC", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&plant_id, &user_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    // Revoking deletes the share, so a revoked token looks exactly like one that never existed
+    pub async fn delete(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM plant_shares WHERE id = $1 AND plant_id = $2 AND user_id = $3")
+            .await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id, &plant_id, &user_id]).await.map_err(|e| e.to_string())
+    }
+
+    // None for unknown and expired tokens alike
+    pub async fn get_valid(&self, token: &str) -> Result<Option<PlantShare>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM plant_shares WHERE token = $1 AND (expires_at IS NULL OR expires_at > $2)", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&token, &Utc::now()]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    // None when the garden does not exist or the user is not its owner
+    pub async fn create_garden(&self, share: &GardenShare) -> Result<Option<GardenShare>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO garden_shares (id, garden_id, user_id, token, expires_at, created_at)
+             SELECT $1, id, owner_id, $4, $5, $6 FROM gardens WHERE id = $2 AND owner_id = $3
+             RETURNING {}", GARDEN_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &share.id, &share.garden_id, &share.user_id, &share.token, &share.expires_at, &share.created_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(garden_from_row))
+    }
+
+    pub async fn get_all_by_garden(&self, garden_id: Uuid, user_id: Uuid) -> Result<Vec<GardenShare>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM garden_shares WHERE garden_id = $1 AND user_id = $2 ORDER BY created_at DESC", GARDEN_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&garden_id, &user_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(garden_from_row).collect())
+    }
+
+    pub async fn delete_garden(&self, id: Uuid, garden_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM garden_shares WHERE id = $1 AND garden_id = $2 AND user_id = $3")
+            .await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id, &garden_id, &user_id]).await.map_err(|e| e.to_string())
+    }
+
+    pub async fn get_valid_garden(&self, token: &str) -> Result<Option<GardenShare>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM garden_shares WHERE token = $1 AND (expires_at IS NULL OR expires_at > $2)", GARDEN_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&token, &Utc::now()]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(garden_from_row))
+    }
+}
@@ -0,0 +1,107 @@
+use crate::models::plant_share::{GardenShare, PlantShare};
+use crate::repositories::supabase_client::{Order, SupabaseClient};
+use chrono::Utc;
+use uuid::Uuid;
+
+pub struct ShareSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "plant_shares";
+const GARDEN_TABLE: &str = "garden_shares";
+
+impl ShareSupabaseRepo {
+    // None when the plant does not exist or belongs to someone else
+    pub async fn create(&self, share: &PlantShare) -> Result<Option<PlantShare>, String> {
+        let owned: Vec<serde_json::Value> = self.client.from("plants")
+            .select("id")
+            .eq("id", share.plant_id)
+            .eq("user_id", share.user_id)
+            .get()
+            .await?;
+        if owned.is_empty() {
+            return Ok(None);
+        }
+
+        let mut arr: Vec<PlantShare> = self.client.from(TABLE).insert(share).await?;
+        arr.pop().map(Some).ok_or_else(|| "Failed to create share".to_string())
+    }
+
+    // Newest first, expired ones included so the owner can see and clean them up
+    pub async fn get_all_by_plant(&self, plant_id: Uuid, user_id: Uuid) -> Result<Vec<PlantShare>, String> {
+        Ok(self.client.from(TABLE)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .order("created_at", Order::Desc)
+            .get()
+            .await?)
+    }
+
+    // Revoking deletes the share, so a revoked token looks exactly like one that never existed
+    pub async fn delete(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let deleted: Vec<serde_json::Value> = self.client.from(TABLE)
+            .select("id")
+            .eq("id", id)
+            .eq("plant_id", plant_id)
+            .eq("user_id", user_id)
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    // None for unknown and expired tokens alike; expiry is checked here since `or` quotes its values,
+    // which PostgREST does not accept for is.null
+    pub async fn get_valid(&self, token: &str) -> Result<Option<PlantShare>, String> {
+        let mut arr: Vec<PlantShare> = self.client.from(TABLE)
+            .eq("token", token)
+            .get()
+            .await?;
+        let now = Utc::now();
+        Ok(arr.pop().filter(|share| share.expires_at.is_none_or(|at| at > now)))
+    }
+
+    // None when the garden does not exist or the user is not its owner
+    pub async fn create_garden(&self, share: &GardenShare) -> Result<Option<GardenShare>, String> {
+        let owned: Vec<serde_json::Value> = self.client.from("gardens")
+            .select("id")
+            .eq("id", share.garden_id)
+            .eq("owner_id", share.user_id)
+            .get()
+            .await?;
+        if owned.is_empty() {
+            return Ok(None);
+        }
+
+        let mut arr: Vec<GardenShare> = self.client.from(GARDEN_TABLE).insert(share).await?;
+        arr.pop().map(Some).ok_or_else(|| "Failed to create share".to_string())
+    }
+
+    pub async fn get_all_by_garden(&self, garden_id: Uuid, user_id: Uuid) -> Result<Vec<GardenShare>, String> {
+        Ok(self.client.from(GARDEN_TABLE)
+            .eq("garden_id", garden_id)
+            .eq("user_id", user_id)
+            .order("created_at", Order::Desc)
+            .get()
+            .await?)
+    }
+
+    pub async fn delete_garden(&self, id: Uuid, garden_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let deleted: Vec<serde_json::Value> = self.client.from(GARDEN_TABLE)
+            .select("id")
+            .eq("id", id)
+            .eq("garden_id", garden_id)
+            .eq("user_id", user_id)
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    pub async fn get_valid_garden(&self, token: &str) -> Result<Option<GardenShare>, String> {
+        let mut arr: Vec<GardenShare> = self.client.from(GARDEN_TABLE)
+            .eq("token", token)
+            .get()
+            .await?;
+        let now = Utc::now();
+        Ok(arr.pop().filter(|share| share.expires_at.is_none_or(|at| at > now)))
+    }
+}
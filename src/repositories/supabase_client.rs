@@ -0,0 +1,330 @@
+use crate::errors::ServiceError;
+use crate::secret::Secret;
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug)]
+pub enum SupabaseError {
+    // Connection problems and timeouts
+    Transport(String),
+    // PostgREST answered with a non-2xx status
+    Api {
+        status: StatusCode,
+        code: Option<String>,
+        message: String,
+    },
+    // 2xx response whose body did not match the expected type
+    Decode(String),
+}
+
+impl SupabaseError {
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, SupabaseError::Api { status, .. } if *status == StatusCode::CONFLICT)
+    }
+}
+
+impl fmt::Display for SupabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SupabaseError::Transport(msg) => write!(f, "Supabase request failed: {}", msg),
+            SupabaseError::Api { status, code: Some(code), message } => {
+                write!(f, "Supabase error ({}, {}): {}", status, code, message)
+            }
+            SupabaseError::Api { status, code: None, message } => {
+                write!(f, "Supabase error ({}): {}", status, message)
+            }
+            SupabaseError::Decode(msg) => write!(f, "Failed to parse Supabase response: {}", msg),
+        }
+    }
+}
+
+impl From<SupabaseError> for String {
+    fn from(err: SupabaseError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<SupabaseError> for ServiceError {
+    fn from(err: SupabaseError) -> Self {
+        ServiceError::Other(err.to_string())
+    }
+}
+
+// Error body returned by PostgREST
+#[derive(Deserialize)]
+struct PostgrestError {
+    code: Option<String>,
+    message: Option<String>,
+}
+
+// One pooled HTTP client shared by every Supabase repository
+#[derive(Clone)]
+pub struct SupabaseClient {
+    http: Client,
+    rest_url: String,
+    api_key: Secret<String>,
+}
+
+impl SupabaseClient {
+    pub fn new(project_url: &str, api_key: Secret<String>) -> Result<Self, String> {
+        let http = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("cannot build Supabase HTTP client: {}", e))?;
+
+        let mut rest_url = project_url.trim_end_matches('/').to_string();
+        if !rest_url.ends_with("/rest/v1") {
+            rest_url = format!("{}/rest/v1", rest_url);
+        }
+
+        Ok(SupabaseClient { http, rest_url, api_key })
+    }
+
+    pub fn from(&self, table: &str) -> QueryBuilder<'_> {
+        QueryBuilder {
+            client: self,
+            table: table.to_string(),
+            params: Vec::new(),
+            prefer: Vec::new(),
+            range: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Prefer {
+    ReturnRepresentation,
+    ReturnMinimal,
+    CountExact,
+    MergeDuplicates, // upsert on primary key / on_conflict columns
+    IgnoreDuplicates, // insert only rows without a conflict, the others are left out of the response
+}
+
+impl Prefer {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Prefer::ReturnRepresentation => "return=representation",
+            Prefer::ReturnMinimal => "return=minimal",
+            Prefer::CountExact => "count=exact",
+            Prefer::MergeDuplicates => "resolution=merge-duplicates",
+            Prefer::IgnoreDuplicates => "resolution=ignore-duplicates",
+        }
+    }
+}
+
+// Builds a PostgREST request; values are passed as query parameters so reqwest URL-encodes them
+pub struct QueryBuilder<'a> {
+    client: &'a SupabaseClient,
+    table: String,
+    params: Vec<(String, String)>,
+    prefer: Vec<Prefer>,
+    range: Option<(u64, u64)>,
+}
+
+// Quotes a value for in.(...) and or=(...) lists so commas, parentheses and quotes survive
+fn quote_list_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl<'a> QueryBuilder<'a> {
+    fn filter(mut self, column: &str, operator: &str, value: impl ToString) -> Self {
+        self.params.push((column.to_string(), format!("{}.{}", operator, value.to_string())));
+        self
+    }
+
+    pub fn eq(self, column: &str, value: impl ToString) -> Self {
+        self.filter(column, "eq", value)
+    }
+
+    pub fn neq(self, column: &str, value: impl ToString) -> Self {
+        self.filter(column, "neq", value)
+    }
+
+    pub fn gt(self, column: &str, value: impl ToString) -> Self {
+        self.filter(column, "gt", value)
+    }
+
+    pub fn gte(self, column: &str, value: impl ToString) -> Self {
+        self.filter(column, "gte", value)
+    }
+
+    pub fn lt(self, column: &str, value: impl ToString) -> Self {
+        self.filter(column, "lt", value)
+    }
+
+    pub fn lte(self, column: &str, value: impl ToString) -> Self {
+        self.filter(column, "lte", value)
+    }
+
+    // column=is.null
+    pub fn null(self, column: &str) -> Self {
+        self.filter(column, "is", "null")
+    }
+
+    // column=not.is.null
+    pub fn not_null(self, column: &str) -> Self {
+        self.filter(column, "not.is", "null")
+    }
+
+    pub fn in_list<T: ToString>(self, column: &str, values: &[T]) -> Self {
+        let list = values.iter().map(|v| quote_list_value(&v.to_string())).collect::<Vec<_>>().join(",");
+        self.filter(column, "in", format!("({})", list))
+    }
+
+    // `*` is the wildcard, e.g. ilike("name", "*rose*")
+    pub fn ilike(self, column: &str, pattern: &str) -> Self {
+        self.filter(column, "ilike", pattern)
+    }
+
+    // Array column holding all of the values, column=cs.{a,b}
+    pub fn contains<T: ToString>(self, column: &str, values: &[T]) -> Self {
+        let list = values.iter().map(|v| quote_list_value(&v.to_string())).collect::<Vec<_>>().join(",");
+        self.filter(column, "cs", format!("{{{}}}", list))
+    }
+
+    // Full-text match on a tsvector column using websearch syntax, e.g. text_search("search", "english", "yellow leaves")
+    pub fn text_search(self, column: &str, config: &str, query: &str) -> Self {
+        self.filter(column, &format!("wfts({})", config), query)
+    }
+
+    // Matches rows satisfying any of the (column, operator, value) conditions
+    pub fn or(mut self, conditions: &[(&str, &str, &str)]) -> Self {
+        let list = conditions.iter()
+            .map(|(column, operator, value)| format!("{}.{}.{}", column, operator, quote_list_value(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.params.push(("or".to_string(), format!("({})", list)));
+        self
+    }
+
+    pub fn select(mut self, columns: &str) -> Self {
+        self.params.push(("select".to_string(), columns.to_string()));
+        self
+    }
+
+    pub fn order(mut self, column: &str, order: Order) -> Self {
+        let direction = match order {
+            Order::Asc => "asc",
+            Order::Desc => "desc",
+        };
+        // Later calls add tie-breakers: order=created_at.asc,id.asc
+        match self.params.iter_mut().find(|(key, _)| key == "order") {
+            Some((_, value)) => value.push_str(&format!(",{}.{}", column, direction)),
+            None => self.params.push(("order".to_string(), format!("{}.{}", column, direction))),
+        }
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.params.push(("limit".to_string(), limit.to_string()));
+        self
+    }
+
+    // Inclusive item range, sent as a Range header
+    pub fn range(mut self, from: u64, to: u64) -> Self {
+        self.range = Some((from, to));
+        self
+    }
+
+    // Columns used to detect duplicates for upserts
+    pub fn on_conflict(mut self, columns: &str) -> Self {
+        self.params.push(("on_conflict".to_string(), columns.to_string()));
+        self
+    }
+
+    pub fn prefer(mut self, prefer: Prefer) -> Self {
+        self.prefer.push(prefer);
+        self
+    }
+
+    fn request(&self, method: Method) -> RequestBuilder {
+        let url = format!("{}/{}", self.client.rest_url, self.table);
+        let key = self.client.api_key.expose();
+
+        let mut req = self.client.http
+            .request(method, url)
+            .bearer_auth(key)
+            .header("apikey", key)
+            .query(&self.params);
+
+        if !self.prefer.is_empty() {
+            let prefer = self.prefer.iter().map(Prefer::as_str).collect::<Vec<_>>().join(",");
+            req = req.header("Prefer", prefer);
+        }
+        if let Some((from, to)) = self.range {
+            req = req.header("Range-Unit", "items").header("Range", format!("{}-{}", from, to));
+        }
+        req
+    }
+
+    async fn send(req: RequestBuilder) -> Result<(StatusCode, reqwest::header::HeaderMap, String), SupabaseError> {
+        let res = req.send().await.map_err(|e| SupabaseError::Transport(e.to_string()))?;
+        let status = res.status();
+        let headers = res.headers().clone();
+        let text = res.text().await.map_err(|e| SupabaseError::Transport(e.to_string()))?;
+
+        if !status.is_success() {
+            let parsed = serde_json::from_str::<PostgrestError>(&text).ok();
+            return Err(SupabaseError::Api {
+                status,
+                code: parsed.as_ref().and_then(|p| p.code.clone()),
+                message: parsed.and_then(|p| p.message).unwrap_or(text),
+            });
+        }
+        Ok((status, headers, text))
+    }
+
+    fn decode<T: DeserializeOwned>(text: &str) -> Result<Vec<T>, SupabaseError> {
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(text).map_err(|e| SupabaseError::Decode(e.to_string()))
+    }
+
+    pub async fn get<T: DeserializeOwned>(self) -> Result<Vec<T>, SupabaseError> {
+        let (_, _, text) = Self::send(self.request(Method::GET)).await?;
+        Self::decode(&text)
+    }
+
+    pub async fn insert<T: DeserializeOwned, B: Serialize + ?Sized>(self, body: &B) -> Result<Vec<T>, SupabaseError> {
+        let req = self.prefer(Prefer::ReturnRepresentation);
+        let (_, _, text) = Self::send(req.request(Method::POST).json(body)).await?;
+        Self::decode(&text)
+    }
+
+    pub async fn update<T: DeserializeOwned, B: Serialize + ?Sized>(self, body: &B) -> Result<Vec<T>, SupabaseError> {
+        let req = self.prefer(Prefer::ReturnRepresentation);
+        let (_, _, text) = Self::send(req.request(Method::PATCH).json(body)).await?;
+        Self::decode(&text)
+    }
+
+    pub async fn delete<T: DeserializeOwned>(self) -> Result<Vec<T>, SupabaseError> {
+        let req = self.prefer(Prefer::ReturnRepresentation);
+        let (_, _, text) = Self::send(req.request(Method::DELETE)).await?;
+        Self::decode(&text)
+    }
+
+    // Number of matching rows, read from the Content-Range header ("0-24/3573" or "*/0")
+    pub async fn count(self) -> Result<u64, SupabaseError> {
+        let req = self.prefer(Prefer::CountExact).limit(0);
+        let (_, headers, _) = Self::send(req.request(Method::GET)).await?;
+        headers.get("content-range")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit('/').next())
+            .and_then(|total| total.parse().ok())
+            .ok_or_else(|| SupabaseError::Decode("missing Content-Range total".to_string()))
+    }
+}
@@ -0,0 +1,175 @@
+use crate::models::webhook::{DueDelivery, Webhook, WebhookDelivery};
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+
+pub struct WebhookPostgresRepo {
+    pub pool: Pool,
+}
+
+const COLUMNS: &str = "id, user_id, url, secret_prefix, events, active, created_at, updated_at";
+const DELIVERY_COLUMNS: &str = "id, webhook_id, user_id, event, payload, status, attempts, next_attempt_at, last_status_code, last_error, created_at, delivered_at";
+
+fn from_row(row: &Row) -> Webhook {
+    Webhook {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        url: row.get("url"),
+        secret_prefix: row.get("secret_prefix"),
+        events: row.get("events"),
+        active: row.get("active"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn delivery_from_row(row: &Row) -> WebhookDelivery {
+    WebhookDelivery {
+        id: row.get("id"),
+        webhook_id: row.get("webhook_id"),
+        user_id: row.get("user_id"),
+        event: row.get("event"),
+        payload: row.get("payload"),
+        status: row.get("status"),
+        attempts: row.get("attempts"),
+        next_attempt_at: row.get("next_attempt_at"),
+        last_status_code: row.get("last_status_code"),
+        last_error: row.get("last_error"),
+        created_at: row.get("created_at"),
+        delivered_at: row.get("delivered_at"),
+    }
+}
+
+impl WebhookPostgresRepo {
+    pub async fn create(&self, webhook: &Webhook, secret: &str) -> Result<Webhook, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO webhooks (id, user_id, url, secret, secret_prefix, events, active, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING {}", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_one(&stmt, &[
+            &webhook.id, &webhook.user_id, &webhook.url, &secret, &webhook.secret_prefix, &webhook.events, &webhook.active,
+            &webhook.created_at, &webhook.updated_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(from_row(&row))
+    }
+
+    pub async fn get_all_by_user(&self, user_id: Uuid) -> Result<Vec<Webhook>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM webhooks WHERE user_id = $1 ORDER BY created_at", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<Webhook>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM webhooks WHERE id = $1 AND user_id = $2", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id, &user_id]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    // Writes every editable field of a webhook loaded with get_by_id
+    pub async fn update(&self, webhook: &Webhook) -> Result<Option<Webhook>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "UPDATE webhooks SET url = $3, events = $4, active = $5, updated_at = $6
+             WHERE id = $1 AND user_id = $2
+             RETURNING {}", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &webhook.id, &webhook.user_id, &webhook.url, &webhook.events, &webhook.active, &webhook.updated_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    // Removes the webhook together with its delivery log
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM webhooks WHERE id = $1 AND user_id = $2")
+            .await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id, &user_id]).await.map_err(|e| e.to_string())
+    }
+
+    // Active webhooks of the user that want the event
+    pub async fn subscribers(&self, user_id: Uuid, event: &str) -> Result<Vec<Webhook>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM webhooks WHERE user_id = $1 AND active AND $2 = ANY(events)", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_id, &event]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    pub async fn enqueue(&self, deliveries: &[WebhookDelivery]) -> Result<(), String> {
+        let mut client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let tx = client.transaction().await.map_err(|e| e.to_string())?;
+        let stmt = tx.prepare(&format!(
+            "INSERT INTO webhook_deliveries ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)", DELIVERY_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        for d in deliveries {
+            tx.execute(&stmt, &[
+                &d.id, &d.webhook_id, &d.user_id, &d.event, &d.payload, &d.status, &d.attempts, &d.next_attempt_at,
+                &d.last_status_code, &d.last_error, &d.created_at, &d.delivered_at
+            ]).await.map_err(|e| e.to_string())?;
+        }
+        tx.commit().await.map_err(|e| e.to_string())
+    }
+
+    // Pending deliveries of active webhooks whose next attempt is due, oldest first
+    pub async fn due_deliveries(&self, limit: i64) -> Result<Vec<DueDelivery>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "SELECT d.*, w.url, w.secret FROM webhook_deliveries d
+             JOIN webhooks w ON w.id = d.webhook_id
+             WHERE d.status = 'pending' AND d.next_attempt_at <= NOW() AND w.active
+             ORDER BY d.next_attempt_at LIMIT $1"
+        ).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&limit]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(|row| DueDelivery {
+            delivery: delivery_from_row(row),
+            url: row.get("url"),
+            secret: row.get("secret"),
+        }).collect())
+    }
+
+    // Stores the outcome of an attempt: status, attempts, next_attempt_at and the last response
+    pub async fn record_attempt(&self, delivery: &WebhookDelivery) -> Result<(), String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "UPDATE webhook_deliveries
+             SET status = $2, attempts = $3, next_attempt_at = $4, last_status_code = $5, last_error = $6, delivered_at = $7
+             WHERE id = $1"
+        ).await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[
+            &delivery.id, &delivery.status, &delivery.attempts, &delivery.next_attempt_at, &delivery.last_status_code,
+            &delivery.last_error, &delivery.delivered_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    // Newest first
+    pub async fn get_deliveries(&self, webhook_id: Uuid, user_id: Uuid, status: Option<&str>, limit: i64) -> Result<Vec<WebhookDelivery>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM webhook_deliveries
+             WHERE webhook_id = $1 AND user_id = $2 AND ($3::text IS NULL OR status = $3)
+             ORDER BY created_at DESC LIMIT $4", DELIVERY_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&webhook_id, &user_id, &status, &limit]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(delivery_from_row).collect())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::models::webhook::{DueDelivery, Webhook, WebhookDelivery};
+use crate::repositories::supabase_client::{Order, SupabaseClient};
+use chrono::Utc;
+use uuid::Uuid;
+use serde::Deserialize;
+use serde_json::json;
+
+pub struct WebhookSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "webhooks";
+const DELIVERIES: &str = "webhook_deliveries";
+const COLUMNS: &str = "id,user_id,url,secret_prefix,events,active,created_at,updated_at";
+
+#[derive(Deserialize)]
+struct Target {
+    url: String,
+    secret: String,
+}
+
+#[derive(Deserialize)]
+struct DueRow {
+    #[serde(flatten)]
+    delivery: WebhookDelivery,
+    webhooks: Target,
+}
+
+impl WebhookSupabaseRepo {
+    pub async fn create(&self, webhook: &Webhook, secret: &str) -> Result<Webhook, String> {
+        let payload = json!({
+            "id": webhook.id,
+            "user_id": webhook.user_id,
+            "url": webhook.url,
+            "secret": secret,
+            "secret_prefix": webhook.secret_prefix,
+            "events": webhook.events,
+            "active": webhook.active,
+            "created_at": webhook.created_at,
+            "updated_at": webhook.updated_at,
+        });
+        let mut arr: Vec<Webhook> = self.client.from(TABLE)
+            .select(COLUMNS)
+            .insert(&payload)
+            .await?;
+        arr.pop().ok_or_else(|| "Failed to create webhook".to_string())
+    }
+
+    pub async fn get_all_by_user(&self, user_id: Uuid) -> Result<Vec<Webhook>, String> {
+        Ok(self.client.from(TABLE)
+            .select(COLUMNS)
+            .eq("user_id", user_id)
+            .order("created_at", Order::Asc)
+            .get()
+            .await?)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<Webhook>, String> {
+        let mut arr: Vec<Webhook> = self.client.from(TABLE)
+            .select(COLUMNS)
+            .eq("id", id)
+            .eq("user_id", user_id)
+            .get()
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // Writes every editable field of a webhook loaded with get_by_id
+    pub async fn update(&self, webhook: &Webhook) -> Result<Option<Webhook>, String> {
+        let payload = json!({
+            "url": webhook.url,
+            "events": webhook.events,
+            "active": webhook.active,
+            "updated_at": webhook.updated_at,
+        });
+
+        let mut arr: Vec<Webhook> = self.client.from(TABLE)
+            .select(COLUMNS)
+            .eq("id", webhook.id)
+            .eq("user_id", webhook.user_id)
+            .update(&payload)
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // Removes the webhook together with its delivery log
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let deleted: Vec<serde_json::Value> = self.client.from(TABLE)
+            .select("id")
+            .eq("id", id)
+            .eq("user_id", user_id)
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    // Active webhooks of the user that want the event
+    pub async fn subscribers(&self, user_id: Uuid, event: &str) -> Result<Vec<Webhook>, String> {
+        Ok(self.client.from(TABLE)
+            .select(COLUMNS)
+            .eq("user_id", user_id)
+            .eq("active", true)
+            .contains("events", &[event])
+            .get()
+            .await?)
+    }
+
+    pub async fn enqueue(&self, deliveries: &[WebhookDelivery]) -> Result<(), String> {
+        let _: Vec<serde_json::Value> = self.client.from(DELIVERIES)
+            .select("id")
+            .insert(deliveries)
+            .await?;
+        Ok(())
+    }
+
+    // Pending deliveries of active webhooks whose next attempt is due, oldest first
+    pub async fn due_deliveries(&self, limit: i64) -> Result<Vec<DueDelivery>, String> {
+        let rows: Vec<DueRow> = self.client.from(DELIVERIES)
+            .select("*,webhooks!inner(url,secret)")
+            .eq("status", "pending")
+            .lte("next_attempt_at", Utc::now().to_rfc3339())
+            .eq("webhooks.active", true)
+            .order("next_attempt_at", Order::Asc)
+            .limit(limit as u64)
+            .get()
+            .await?;
+        Ok(rows.into_iter().map(|row| DueDelivery {
+            delivery: row.delivery,
+            url: row.webhooks.url,
+            secret: row.webhooks.secret,
+        }).collect())
+    }
+
+    // Stores the outcome of an attempt: status, attempts, next_attempt_at and the last response
+    pub async fn record_attempt(&self, delivery: &WebhookDelivery) -> Result<(), String> {
+        let payload = json!({
+            "status": delivery.status,
+            "attempts": delivery.attempts,
+            "next_attempt_at": delivery.next_attempt_at,
+            "last_status_code": delivery.last_status_code,
+            "last_error": delivery.last_error,
+            "delivered_at": delivery.delivered_at,
+        });
+
+        let _: Vec<serde_json::Value> = self.client.from(DELIVERIES)
+            .select("id")
+            .eq("id", delivery.id)
+            .update(&payload)
+            .await?;
+        Ok(())
+    }
+
+    // Newest first
+    pub async fn get_deliveries(&self, webhook_id: Uuid, user_id: Uuid, status: Option<&str>, limit: i64) -> Result<Vec<WebhookDelivery>, String> {
+        let mut query = self.client.from(DELIVERIES)
+            .eq("webhook_id", webhook_id)
+            .eq("user_id", user_id);
+        if let Some(status) = status {
+            query = query.eq("status", status);
+        }
+        Ok(query
+            .order("created_at", Order::Desc)
+            .limit(limit as u64)
+            .get()
+            .await?)
+    }
+}
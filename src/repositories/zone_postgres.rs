@@ -0,0 +1,159 @@
+use crate::models::zone::{Zone, ZoneRun};
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+
+pub struct ZonePostgresRepo {
+    pub pool: Pool,
+}
+
+const COLUMNS: &str = "id, user_id, name, valve, plant_ids, run_minutes, scheduled, created_at, updated_at";
+const RUN_COLUMNS: &str = "id, zone_id, user_id, minutes, triggered_by, status, error, started_at, finished_at";
+
+fn from_row(row: &Row) -> Zone {
+    Zone {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        valve: row.get("valve"),
+        plant_ids: row.get("plant_ids"),
+        run_minutes: row.get("run_minutes"),
+        scheduled: row.get("scheduled"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn run_from_row(row: &Row) -> ZoneRun {
+    ZoneRun {
+        id: row.get("id"),
+        zone_id: row.get("zone_id"),
+        user_id: row.get("user_id"),
+        minutes: row.get("minutes"),
+        triggered_by: row.get("triggered_by"),
+        status: row.get("status"),
+        error: row.get("error"),
+        started_at: row.get("started_at"),
+        finished_at: row.get("finished_at"),
+    }
+}
+
+impl ZonePostgresRepo {
+    pub async fn create(&self, zone: &Zone) -> Result<Zone, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO zones ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING {}", COLUMNS, COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_one(&stmt, &[
+            &zone.id, &zone.user_id, &zone.name, &zone.valve, &zone.plant_ids, &zone.run_minutes, &zone.scheduled,
+            &zone.created_at, &zone.updated_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(from_row(&row))
+    }
+
+    pub async fn get_all_by_user(&self, user_id: Uuid) -> Result<Vec<Zone>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM zones WHERE user_id = $1 ORDER BY name, created_at", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&user_id]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<Zone>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM zones WHERE id = $1 AND user_id = $2", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&id, &user_id]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    // Writes every editable field of a zone loaded with get_by_id
+    pub async fn update(&self, zone: &Zone) -> Result<Option<Zone>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "UPDATE zones SET name = $3, valve = $4, plant_ids = $5, run_minutes = $6, scheduled = $7, updated_at = $8
+             WHERE id = $1 AND user_id = $2
+             RETURNING {}", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &zone.id, &zone.user_id, &zone.name, &zone.valve, &zone.plant_ids, &zone.run_minutes, &zone.scheduled, &zone.updated_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(from_row))
+    }
+
+    // Removes the zone together with its runs
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare("DELETE FROM zones WHERE id = $1 AND user_id = $2")
+            .await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[&id, &user_id]).await.map_err(|e| e.to_string())
+    }
+
+    // Zones of every user that run on the care schedule
+    pub async fn scheduled_zones(&self) -> Result<Vec<Zone>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM zones WHERE scheduled AND cardinality(plant_ids) > 0", COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    // None when the zone already has a run in progress
+    pub async fn start_run(&self, run: &ZoneRun) -> Result<Option<ZoneRun>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "INSERT INTO zone_runs ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (zone_id) WHERE status = 'running' DO NOTHING
+             RETURNING {}", RUN_COLUMNS, RUN_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[
+            &run.id, &run.zone_id, &run.user_id, &run.minutes, &run.triggered_by, &run.status, &run.error,
+            &run.started_at, &run.finished_at
+        ]).await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(run_from_row))
+    }
+
+    // None when the zone was deleted while it ran
+    pub async fn finish_run(&self, run: &ZoneRun) -> Result<Option<ZoneRun>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "UPDATE zone_runs SET status = $2, error = $3, finished_at = $4 WHERE id = $1 RETURNING {}", RUN_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let row = client.query_opt(&stmt, &[&run.id, &run.status, &run.error, &run.finished_at])
+            .await.map_err(|e| e.to_string())?;
+        Ok(row.as_ref().map(run_from_row))
+    }
+
+    // Newest first
+    pub async fn get_runs(&self, zone_id: Uuid, user_id: Uuid, limit: i64) -> Result<Vec<ZoneRun>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!(
+            "SELECT {} FROM zone_runs WHERE zone_id = $1 AND user_id = $2 ORDER BY started_at DESC LIMIT $3", RUN_COLUMNS
+        )).await.map_err(|e| e.to_string())?;
+
+        let rows = client.query(&stmt, &[&zone_id, &user_id, &limit]).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(run_from_row).collect())
+    }
+
+    // Runs still marked as running when the server starts were cut off by a shutdown
+    pub async fn fail_interrupted(&self) -> Result<u64, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(
+            "UPDATE zone_runs SET status = 'failed', error = 'interrupted by a server restart', finished_at = NOW()
+             WHERE status = 'running'"
+        ).await.map_err(|e| e.to_string())?;
+
+        client.execute(&stmt, &[]).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,127 @@
+use crate::models::zone::{Zone, ZoneRun};
+use crate::repositories::supabase_client::{Order, SupabaseClient};
+use chrono::Utc;
+use uuid::Uuid;
+use serde_json::json;
+
+pub struct ZoneSupabaseRepo {
+    pub client: SupabaseClient,
+}
+
+const TABLE: &str = "zones";
+const RUNS: &str = "zone_runs";
+
+impl ZoneSupabaseRepo {
+    pub async fn create(&self, zone: &Zone) -> Result<Zone, String> {
+        let mut arr: Vec<Zone> = self.client.from(TABLE).insert(zone).await?;
+        arr.pop().ok_or_else(|| "Failed to create zone".to_string())
+    }
+
+    pub async fn get_all_by_user(&self, user_id: Uuid) -> Result<Vec<Zone>, String> {
+        Ok(self.client.from(TABLE)
+            .eq("user_id", user_id)
+            .order("name", Order::Asc)
+            .order("created_at", Order::Asc)
+            .get()
+            .await?)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<Zone>, String> {
+        let mut arr: Vec<Zone> = self.client.from(TABLE)
+            .eq("id", id)
+            .eq("user_id", user_id)
+            .get()
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // Writes every editable field of a zone loaded with get_by_id
+    pub async fn update(&self, zone: &Zone) -> Result<Option<Zone>, String> {
+        let payload = json!({
+            "name": zone.name,
+            "valve": zone.valve,
+            "plant_ids": zone.plant_ids,
+            "run_minutes": zone.run_minutes,
+            "scheduled": zone.scheduled,
+            "updated_at": zone.updated_at.to_rfc3339(),
+        });
+
+        let mut arr: Vec<Zone> = self.client.from(TABLE)
+            .eq("id", zone.id)
+            .eq("user_id", zone.user_id)
+            .update(&payload)
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // Removes the zone together with its runs
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        let deleted: Vec<serde_json::Value> = self.client.from(TABLE)
+            .select("id")
+            .eq("id", id)
+            .eq("user_id", user_id)
+            .delete()
+            .await?;
+        Ok(deleted.len() as u64)
+    }
+
+    // Zones of every user that run on the care schedule
+    pub async fn scheduled_zones(&self) -> Result<Vec<Zone>, String> {
+        Ok(self.client.from(TABLE)
+            .eq("scheduled", true)
+            .neq("plant_ids", "{}")
+            .get()
+            .await?)
+    }
+
+    // None when the zone already has a run in progress; the partial unique index answers 409
+    pub async fn start_run(&self, run: &ZoneRun) -> Result<Option<ZoneRun>, String> {
+        match self.client.from(RUNS).insert::<ZoneRun, _>(run).await {
+            Ok(mut arr) => arr.pop().map(Some).ok_or_else(|| "Failed to start run".to_string()),
+            Err(e) if e.is_conflict() => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // None when the zone was deleted while it ran
+    pub async fn finish_run(&self, run: &ZoneRun) -> Result<Option<ZoneRun>, String> {
+        let payload = json!({
+            "status": run.status,
+            "error": run.error,
+            "finished_at": run.finished_at,
+        });
+
+        let mut arr: Vec<ZoneRun> = self.client.from(RUNS)
+            .eq("id", run.id)
+            .update(&payload)
+            .await?;
+        Ok(arr.pop())
+    }
+
+    // Newest first
+    pub async fn get_runs(&self, zone_id: Uuid, user_id: Uuid, limit: i64) -> Result<Vec<ZoneRun>, String> {
+        Ok(self.client.from(RUNS)
+            .eq("zone_id", zone_id)
+            .eq("user_id", user_id)
+            .order("started_at", Order::Desc)
+            .limit(limit as u64)
+            .get()
+            .await?)
+    }
+
+    // Runs still marked as running when the server starts were cut off by a shutdown
+    pub async fn fail_interrupted(&self) -> Result<u64, String> {
+        let payload = json!({
+            "status": "failed",
+            "error": "interrupted by a server restart",
+            "finished_at": Utc::now(),
+        });
+
+        let failed: Vec<serde_json::Value> = self.client.from(RUNS)
+            .select("id")
+            .eq("status", "running")
+            .update(&payload)
+            .await?;
+        Ok(failed.len() as u64)
+    }
+}
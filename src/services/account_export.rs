@@ -0,0 +1,224 @@
+use crate::models::care_event::CareEvent;
+use crate::models::garden::{MemberGarden, OWNER};
+use crate::models::plant::Plant;
+use crate::models::plant_issue::PlantIssue;
+use crate::models::plant_note::PlantNote;
+use crate::models::plant_share::{GardenShare, PlantShare};
+use crate::models::sensor::{SensorDevice, SensorReading};
+use crate::models::user::SafeUser;
+use crate::models::webhook::Webhook;
+use crate::models::zone::{Zone, ZoneRun};
+use crate::repositories::care_event_postgres::CareEventPostgresRepo;
+use crate::repositories::care_event_supabase::CareEventSupabaseRepo;
+use crate::repositories::garden_postgres::GardenPostgresRepo;
+use crate::repositories::garden_supabase::GardenSupabaseRepo;
+use crate::repositories::issue_postgres::IssuePostgresRepo;
+use crate::repositories::issue_supabase::IssueSupabaseRepo;
+use crate::repositories::note_postgres::NotePostgresRepo;
+use crate::repositories::note_supabase::NoteSupabaseRepo;
+use crate::repositories::sensor_postgres::SensorPostgresRepo;
+use crate::repositories::sensor_supabase::SensorSupabaseRepo;
+use crate::repositories::share_postgres::SharePostgresRepo;
+use crate::repositories::share_supabase::ShareSupabaseRepo;
+use crate::repositories::webhook_postgres::WebhookPostgresRepo;
+use crate::repositories::webhook_supabase::WebhookSupabaseRepo;
+use crate::repositories::zone_postgres::ZonePostgresRepo;
+use crate::repositories::zone_supabase::ZoneSupabaseRepo;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+use uuid::Uuid;
+
+// No limit in practice, an export has to contain everything
+const ALL: i64 = i64::MAX;
+
+// Everything an account owns beside its profile and plants. Webhook secrets and device API keys are
+// credentials and stay out of it.
+#[derive(Default)]
+pub struct AccountData {
+    pub notes: Vec<PlantNote>,
+    pub issues: Vec<PlantIssue>,
+    pub care_events: Vec<CareEvent>,
+    pub devices: Vec<SensorDevice>,
+    pub readings: Vec<SensorReading>,
+    pub zones: Vec<Zone>,
+    pub zone_runs: Vec<ZoneRun>,
+    pub webhooks: Vec<Webhook>,
+    pub gardens: Vec<MemberGarden>,
+    pub plant_shares: Vec<PlantShare>,
+    pub garden_shares: Vec<GardenShare>,
+}
+
+// Gathers AccountData for the export ZIP
+pub struct AccountExportService {
+    pub note_pg_repo: NotePostgresRepo,
+    pub note_sb_repo: NoteSupabaseRepo,
+    pub issue_pg_repo: IssuePostgresRepo,
+    pub issue_sb_repo: IssueSupabaseRepo,
+    pub care_pg_repo: CareEventPostgresRepo,
+    pub care_sb_repo: CareEventSupabaseRepo,
+    pub sensor_pg_repo: SensorPostgresRepo,
+    pub sensor_sb_repo: SensorSupabaseRepo,
+    pub zone_pg_repo: ZonePostgresRepo,
+    pub zone_sb_repo: ZoneSupabaseRepo,
+    pub webhook_pg_repo: WebhookPostgresRepo,
+    pub webhook_sb_repo: WebhookSupabaseRepo,
+    pub garden_pg_repo: GardenPostgresRepo,
+    pub garden_sb_repo: GardenSupabaseRepo,
+    pub share_pg_repo: SharePostgresRepo,
+    pub share_sb_repo: ShareSupabaseRepo,
+}
+
+// One row per plant per day in plants.status
+#[derive(Serialize)]
+struct HistoryEntry<'a> {
+    plant_id: String,
+    plant_name: &'a str,
+    date: &'a str,
+    watered: bool,
+    fertilized: bool,
+    harvested: bool,
+}
+
+// Photos are stored inline as data URLs or bare base64; links to other sites stay links
+fn decode_photo(image: &str) -> Option<Vec<u8>> {
+    if image.starts_with("http://") || image.starts_with("https://") {
+        return None;
+    }
+    let data = match image.strip_prefix("data:") {
+        Some(rest) => rest.split_once(";base64,")?.1,
+        None => image,
+    };
+    STANDARD.decode(data.trim()).ok()
+}
+
+fn photo_extension(bytes: &[u8]) -> &'static str {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => "png",
+        [0xFF, 0xD8, 0xFF, ..] => "jpg",
+        [b'G', b'I', b'F', b'8', ..] => "gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "webp",
+        _ => "bin",
+    }
+}
+
+impl AccountExportService {
+    // PostgreSQL methods
+    // plants are the user's own, as exported to plants.json
+    pub async fn collect_pg(&self, user_id: Uuid, plants: &[Plant]) -> Result<AccountData, String> {
+        let mut data = AccountData::default();
+        for plant in plants {
+            data.notes.extend(self.note_pg_repo.get_all_by_plant(plant.id, user_id).await?);
+            data.issues.extend(self.issue_pg_repo.get_all_by_plant(plant.id, user_id, None).await?);
+            data.care_events.extend(self.care_pg_repo.get_all_by_plant(plant.id, None, ALL).await?);
+            data.readings.extend(self.sensor_pg_repo.all_readings(plant.id).await?);
+            data.plant_shares.extend(self.share_pg_repo.get_all_by_plant(plant.id, user_id).await?);
+        }
+        data.devices = self.sensor_pg_repo.get_devices(user_id).await?;
+        data.zones = self.zone_pg_repo.get_all_by_user(user_id).await?;
+        for zone in &data.zones {
+            data.zone_runs.extend(self.zone_pg_repo.get_runs(zone.id, user_id, ALL).await?);
+        }
+        data.webhooks = self.webhook_pg_repo.get_all_by_user(user_id).await?;
+        data.gardens = self.garden_pg_repo.member_gardens(user_id).await?;
+        for garden in data.gardens.iter().filter(|g| g.role == OWNER) {
+            data.garden_shares.extend(self.share_pg_repo.get_all_by_garden(garden.garden.id, user_id).await?);
+        }
+        Ok(data)
+    }
+
+    // Supabase methods
+    pub async fn collect_sb(&self, user_id: Uuid, plants: &[Plant]) -> Result<AccountData, String> {
+        let mut data = AccountData::default();
+        for plant in plants {
+            data.notes.extend(self.note_sb_repo.get_all_by_plant(plant.id, user_id).await?);
+            data.issues.extend(self.issue_sb_repo.get_all_by_plant(plant.id, user_id, None).await?);
+            data.care_events.extend(self.care_sb_repo.get_all_by_plant(plant.id, None, ALL).await?);
+            data.readings.extend(self.sensor_sb_repo.readings(plant.id, DateTime::UNIX_EPOCH, Utc::now()).await?);
+            data.plant_shares.extend(self.share_sb_repo.get_all_by_plant(plant.id, user_id).await?);
+        }
+        data.devices = self.sensor_sb_repo.get_devices(user_id).await?;
+        data.zones = self.zone_sb_repo.get_all_by_user(user_id).await?;
+        for zone in &data.zones {
+            data.zone_runs.extend(self.zone_sb_repo.get_runs(zone.id, user_id, ALL).await?);
+        }
+        data.webhooks = self.webhook_sb_repo.get_all_by_user(user_id).await?;
+        data.gardens = self.garden_sb_repo.member_gardens(user_id).await?;
+        for garden in data.gardens.iter().filter(|g| g.role == OWNER) {
+            data.garden_shares.extend(self.share_sb_repo.get_all_by_garden(garden.garden.id, user_id).await?);
+        }
+        Ok(data)
+    }
+}
+
+// ZIP with profile.json, plants.json, history.json, photos/<plant id>.<ext> and one JSON file
+// per kind of AccountData (notes.json, care_events.json, ...)
+pub fn build_account_zip(user: &SafeUser, plants: &[Plant], data: &AccountData) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    let mut exported_plants = Vec::with_capacity(plants.len());
+    let mut history = Vec::new();
+    let mut photos = Vec::new();
+
+    for plant in plants {
+        let mut exported = plant.clone();
+        if let Some(bytes) = plant.image.as_deref().and_then(decode_photo) {
+            let path = format!("photos/{}.{}", plant.id, photo_extension(&bytes));
+            exported.image = Some(path.clone());
+            photos.push((path, bytes));
+        }
+        exported_plants.push(exported);
+
+        if let Some(days) = plant.status.as_object() {
+            for (date, status) in days {
+                let flag = |name: &str| status.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+                history.push(HistoryEntry {
+                    plant_id: plant.id.to_string(),
+                    plant_name: &plant.name,
+                    date,
+                    watered: flag("watered"),
+                    fertilized: flag("fertilized"),
+                    harvested: flag("harvested"),
+                });
+            }
+        }
+    }
+    history.sort_by(|a, b| (a.date, &a.plant_id).cmp(&(b.date, &b.plant_id)));
+
+    let files = [
+        ("profile.json", serde_json::to_vec_pretty(&json!({ "exported_at": Utc::now(), "user": user }))),
+        ("plants.json", serde_json::to_vec_pretty(&exported_plants)),
+        ("history.json", serde_json::to_vec_pretty(&history)),
+        ("notes.json", serde_json::to_vec_pretty(&data.notes)),
+        ("issues.json", serde_json::to_vec_pretty(&data.issues)),
+        ("care_events.json", serde_json::to_vec_pretty(&data.care_events)),
+        ("sensor_devices.json", serde_json::to_vec_pretty(&data.devices)),
+        ("sensor_readings.json", serde_json::to_vec_pretty(&data.readings)),
+        ("zones.json", serde_json::to_vec_pretty(&data.zones)),
+        ("zone_runs.json", serde_json::to_vec_pretty(&data.zone_runs)),
+        ("webhooks.json", serde_json::to_vec_pretty(&data.webhooks)),
+        ("gardens.json", serde_json::to_vec_pretty(&data.gardens)),
+        ("shares.json", serde_json::to_vec_pretty(&json!({ "plants": data.plant_shares, "gardens": data.garden_shares }))),
+    ];
+    for (name, content) in files {
+        let content = content.map_err(|e| e.to_string())?;
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(&content).map_err(|e| e.to_string())?;
+    }
+
+    // Images are already compressed, storing them avoids wasting time on deflate
+    let stored = options.compression_method(zip::CompressionMethod::Stored);
+    for (path, bytes) in photos {
+        zip.start_file(path, stored).map_err(|e| e.to_string())?;
+        zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+
+    let cursor = zip.finish().map_err(|e| e.to_string())?;
+    Ok(cursor.into_inner())
+}
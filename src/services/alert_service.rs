@@ -0,0 +1,394 @@
+use crate::config::WeatherConfig;
+use crate::dtos::alert_dto::AlertListQuery;
+use crate::models::plant_alert::{AlertRecipient, PlantAlert, WatchedPlant};
+use crate::repositories::alert_postgres::AlertPostgresRepo;
+use crate::repositories::alert_supabase::AlertSupabaseRepo;
+use crate::repositories::supabase_client::SupabaseClient;
+use crate::services::care_schedule::species_profile;
+use crate::services::notifier::{Notification, Notifier};
+use crate::services::weather::WeatherService;
+use chrono::{Duration, NaiveDate, Utc};
+use deadpool_postgres::Pool;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct AlertService {
+    pub pg_repo: AlertPostgresRepo,
+    pub sb_repo: AlertSupabaseRepo,
+    pub weather: Arc<WeatherService>,
+    pub notifier: Arc<Notifier>,
+    pub frost_threshold_c: f64, // forecast minimum at or below this warns frost-sensitive plants
+    pub heat_threshold_c: f64, // forecast maximum at or above this warns heat-sensitive plants
+    pub horizon_days: i64, // how many days ahead the forecast is checked
+}
+
+pub fn new_alert(user_id: Uuid, plant_id: Uuid, plant_name: &str, kind: &str, date: NaiveDate, value: f64, threshold: f64) -> PlantAlert {
+    PlantAlert {
+        id: Uuid::new_v4(),
+        user_id,
+        plant_id,
+        plant_name: plant_name.to_string(),
+        kind: kind.to_string(),
+        date,
+        value,
+        threshold,
+        notified_at: None,
+        created_at: Utc::now(),
+    }
+}
+
+fn describe(alert: &PlantAlert) -> String {
+    match alert.kind.as_str() {
+        "frost" => format!("- {}: frost expected for {} ({:.1} °C)", alert.date, alert.plant_name, alert.value),
+        "heat" => format!("- {}: heat expected for {} ({:.1} °C)", alert.date, alert.plant_name, alert.value),
+        _ => format!("- {}: soil of {} is dry ({:.0} %, below {:.0} %)", alert.date, alert.plant_name, alert.value, alert.threshold),
+    }
+}
+
+// One message per user covering all of their new alerts
+fn notification(recipient: &AlertRecipient, alerts: &[&PlantAlert]) -> Notification {
+    let lines = alerts.iter().map(|a| describe(a)).collect::<Vec<_>>().join("\n");
+    Notification {
+        user_id: recipient.id,
+        email: recipient.email.clone(),
+        event: "plant_alert".to_string(),
+        subject: if alerts.len() == 1 {
+            "One of your plants needs attention".to_string()
+        } else {
+            format!("{} alerts for your plants", alerts.len())
+        },
+        body: format!(
+            "Hi {},\n\n{}\n\nFor frost and heat consider covering the plants, watering early or moving pots \
+             to a sheltered spot. Dry soil needs watering.",
+            recipient.name, lines
+        ),
+        data: json!({ "alerts": alerts }),
+    }
+}
+
+// The query is validated by the handlers before it gets here
+fn from_date(query: &AlertListQuery) -> NaiveDate {
+    query.from.as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .unwrap_or_else(|| Utc::now().date_naive())
+}
+
+impl AlertService {
+    pub fn new(pg_pool: &Pool, supabase: &SupabaseClient, weather: Arc<WeatherService>, notifier: Arc<Notifier>, config: &WeatherConfig) -> Self {
+        AlertService {
+            pg_repo: AlertPostgresRepo { pool: pg_pool.clone() },
+            sb_repo: AlertSupabaseRepo { client: supabase.clone() },
+            weather,
+            notifier,
+            frost_threshold_c: config.frost_threshold_c,
+            heat_threshold_c: config.heat_threshold_c,
+            horizon_days: config.alert_horizon_days,
+        }
+    }
+
+    // Checks the forecast once per city and compares it with each plant's species profile
+    async fn detect(&self, watched: &[WatchedPlant], today: NaiveDate) -> Vec<PlantAlert> {
+        let mut by_city: BTreeMap<String, Vec<&WatchedPlant>> = BTreeMap::new();
+        for plant in watched {
+            by_city.entry(plant.city.trim().to_lowercase()).or_default().push(plant);
+        }
+
+        let mut alerts = Vec::new();
+        for (city, plants) in by_city {
+            let days = self.weather.daily(Some(&city), today, today + Duration::days(self.horizon_days)).await;
+            for plant in plants {
+                let profile = species_profile(&plant.plant_type);
+                let alert = |kind, day: NaiveDate, value, threshold| {
+                    new_alert(plant.user_id, plant.plant_id, &plant.plant_name, kind, day, value, threshold)
+                };
+                for day in &days {
+                    if profile.frost_sensitive && day.temp_min_c <= self.frost_threshold_c {
+                        alerts.push(alert("frost", day.date, day.temp_min_c, self.frost_threshold_c));
+                    }
+                    if profile.heat_sensitive && day.temp_max_c >= self.heat_threshold_c {
+                        alerts.push(alert("heat", day.date, day.temp_max_c, self.heat_threshold_c));
+                    }
+                }
+            }
+        }
+        alerts
+    }
+
+    // Ids of the alerts that reached the user through at least one channel
+    async fn deliver(&self, created: &[PlantAlert], recipients: &[AlertRecipient]) -> Vec<Uuid> {
+        let mut by_user: BTreeMap<Uuid, Vec<&PlantAlert>> = BTreeMap::new();
+        for alert in created {
+            by_user.entry(alert.user_id).or_default().push(alert);
+        }
+
+        let mut delivered = Vec::new();
+        for (user_id, alerts) in by_user {
+            let Some(recipient) = recipients.iter().find(|r| r.id == user_id) else {
+                continue;
+            };
+            if self.notifier.notify(&notification(recipient, &alerts)).await {
+                delivered.extend(alerts.iter().map(|a| a.id));
+            }
+        }
+        delivered
+    }
+
+    fn user_ids(alerts: &[PlantAlert]) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = alerts.iter().map(|a| a.user_id).collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    // PostgreSQL methods
+    // Stores the alerts not raised before and notifies their users; returns how many were new.
+    // Alerts no channel could deliver stay listed at /alerts but are not sent again.
+    pub async fn raise_pg(&self, alerts: &[PlantAlert]) -> Result<usize, String> {
+        let created = self.pg_repo.insert_new(alerts).await?;
+        if created.is_empty() {
+            return Ok(0);
+        }
+        let recipients = self.pg_repo.recipients(&Self::user_ids(&created)).await?;
+        let delivered = self.deliver(&created, &recipients).await;
+        self.pg_repo.mark_notified(&delivered).await?;
+        Ok(created.len())
+    }
+
+    // Frost and heat from the forecast; returns the number of new alerts
+    pub async fn check_pg(&self) -> Result<usize, String> {
+        if !self.weather.enabled() {
+            return Ok(0);
+        }
+        let watched = self.pg_repo.watched_plants().await?;
+        let alerts = self.detect(&watched, Utc::now().date_naive()).await;
+        self.raise_pg(&alerts).await
+    }
+
+    pub async fn get_all_pg(&self, user_id: Uuid, query: &AlertListQuery) -> Result<Vec<PlantAlert>, String> {
+        self.pg_repo.get_all_by_user(user_id, from_date(query), query.kind.as_deref()).await
+    }
+
+    // Supabase methods
+    // Stores the alerts not raised before and notifies their users; returns how many were new.
+    // Alerts no channel could deliver stay listed at /alerts but are not sent again.
+    pub async fn raise_sb(&self, alerts: &[PlantAlert]) -> Result<usize, String> {
+        let created = self.sb_repo.insert_new(alerts).await?;
+        if created.is_empty() {
+            return Ok(0);
+        }
+        let recipients = self.sb_repo.recipients(&Self::user_ids(&created)).await?;
+        let delivered = self.deliver(&created, &recipients).await;
+        self.sb_repo.mark_notified(&delivered).await?;
+        Ok(created.len())
+    }
+
+    // Frost and heat from the forecast; returns the number of new alerts
+    pub async fn check_sb(&self) -> Result<usize, String> {
+        if !self.weather.enabled() {
+            return Ok(0);
+        }
+        let watched = self.sb_repo.watched_plants().await?;
+        let alerts = self.detect(&watched, Utc::now().date_naive()).await;
+        self.raise_sb(&alerts).await
+    }
+
+    pub async fn get_all_sb(&self, user_id: Uuid, query: &AlertListQuery) -> Result<Vec<PlantAlert>, String> {
+        self.sb_repo.get_all_by_user(user_id, from_date(query), query.kind.as_deref()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::notifier::NotificationChannel;
+    use crate::test_support::{pg_pool, supabase, weather_config, write_weather_fixture, FROST_C, HEAT_C};
+    use async_trait::async_trait;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 7, 15).unwrap()
+    }
+
+    // Day 0 hits the frost threshold exactly, day 1 the heat threshold, day 2 misses both by a little
+    fn write_fixture() -> PathBuf {
+        let day = |offset: i64| (today() + Duration::days(offset)).to_string();
+        let fixture = json!({
+            "Berlin": [
+                { "date": day(0), "precipitation_mm": 0.0, "temp_min_c": FROST_C, "temp_max_c": 20.0 },
+                { "date": day(1), "precipitation_mm": 0.0, "temp_min_c": 15.0, "temp_max_c": HEAT_C },
+                { "date": day(2), "precipitation_mm": 0.0, "temp_min_c": FROST_C + 0.1, "temp_max_c": HEAT_C - 0.1 }
+            ]
+        });
+        write_weather_fixture(&fixture)
+    }
+
+    // Records what it was asked to send; `ok` decides whether delivering works
+    struct TestChannel {
+        ok: bool,
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl NotificationChannel for TestChannel {
+        fn name(&self) -> &'static str {
+            "test"
+        }
+
+        async fn deliver(&self, notification: &Notification) -> Result<(), String> {
+            self.sent.lock().unwrap().push(notification.clone());
+            if self.ok { Ok(()) } else { Err("channel down".to_string()) }
+        }
+    }
+
+    fn service(fixture: PathBuf, channel: Arc<TestChannel>) -> AlertService {
+        let config = weather_config(&fixture);
+        let weather = Arc::new(WeatherService::from_config(&config).unwrap());
+        AlertService::new(&pg_pool(), &supabase(), weather, Arc::new(Notifier { channels: vec![channel] }), &config)
+    }
+
+    fn channel(ok: bool) -> Arc<TestChannel> {
+        Arc::new(TestChannel { ok, sent: Mutex::new(Vec::new()) })
+    }
+
+    fn watched(plant_type: &str, user_id: Uuid, plant_id: Uuid) -> WatchedPlant {
+        WatchedPlant {
+            plant_id,
+            plant_name: format!("My {}", plant_type),
+            plant_type: plant_type.to_string(),
+            user_id,
+            city: " berlin ".to_string(),
+        }
+    }
+
+    fn kinds(alerts: &[PlantAlert]) -> Vec<(String, NaiveDate)> {
+        alerts.iter().map(|a| (a.kind.clone(), a.date)).collect()
+    }
+
+    #[tokio::test]
+    async fn detect_warns_at_the_frost_and_heat_thresholds() {
+        let fixture = write_fixture();
+        let svc = service(fixture.clone(), channel(true));
+
+        // Vegetables are both frost and heat sensitive
+        let alerts = svc.detect(&[watched("Vegetable", Uuid::new_v4(), Uuid::new_v4())], today()).await;
+        std::fs::remove_file(fixture).unwrap();
+
+        assert_eq!(kinds(&alerts), vec![
+            ("frost".to_string(), today()),
+            ("heat".to_string(), today() + Duration::days(1)),
+        ]);
+        assert_eq!(alerts[0].value, FROST_C);
+        assert_eq!(alerts[0].threshold, FROST_C);
+        assert_eq!(alerts[1].value, HEAT_C);
+        assert_eq!(alerts[1].threshold, HEAT_C);
+    }
+
+    #[tokio::test]
+    async fn detect_skips_plants_that_are_not_sensitive() {
+        let fixture = write_fixture();
+        let svc = service(fixture.clone(), channel(true));
+
+        let user_id = Uuid::new_v4();
+        let (flower, fruit, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let alerts = svc.detect(&[
+            watched("Flower", user_id, flower), // heat only
+            watched("Fruit", user_id, fruit), // frost only
+            watched("Cactus", user_id, other), // neither
+        ], today()).await;
+        std::fs::remove_file(fixture).unwrap();
+
+        let of = |plant_id: Uuid| kinds(&alerts.iter().filter(|a| a.plant_id == plant_id).cloned().collect::<Vec<_>>());
+        assert_eq!(of(flower), vec![("heat".to_string(), today() + Duration::days(1))]);
+        assert_eq!(of(fruit), vec![("frost".to_string(), today())]);
+        assert!(of(other).is_empty());
+    }
+
+    #[tokio::test]
+    async fn detect_ignores_cities_without_weather() {
+        let fixture = write_fixture();
+        let svc = service(fixture.clone(), channel(true));
+
+        let mut plant = watched("Vegetable", Uuid::new_v4(), Uuid::new_v4());
+        plant.city = "Lisbon".to_string();
+        let alerts = svc.detect(&[plant], today()).await;
+        std::fs::remove_file(fixture).unwrap();
+
+        assert!(alerts.is_empty());
+    }
+
+    // A user with one outdoor vegetable, removed again (with its alerts) by drop_user
+    async fn create_user(pool: &Pool) -> (Uuid, Uuid) {
+        crate::migrations::run_pending(pool).await.unwrap();
+        let client = pool.get().await.unwrap();
+        let (user_id, plant_id) = (Uuid::new_v4(), Uuid::new_v4());
+        client.execute(
+            "INSERT INTO users (id, name, email, password, city) VALUES ($1, 'Alert Test', $2, 'x', 'Berlin')",
+            &[&user_id, &format!("alerts-{}@example.com", user_id)],
+        ).await.unwrap();
+        client.execute(
+            "INSERT INTO plants (id, name, plant_type, user_id) VALUES ($1, 'My Vegetable', 'Vegetable', $2)",
+            &[&plant_id, &user_id],
+        ).await.unwrap();
+        (user_id, plant_id)
+    }
+
+    async fn drop_user(pool: &Pool, user_id: Uuid) {
+        let client = pool.get().await.unwrap();
+        client.execute("DELETE FROM users WHERE id = $1", &[&user_id]).await.unwrap();
+    }
+
+    async fn stored(svc: &AlertService, user_id: Uuid) -> Vec<PlantAlert> {
+        svc.pg_repo.get_all_by_user(user_id, today(), None).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Postgres (PG_HOST, PG_USER, PG_PASS, PG_DB)"]
+    async fn raise_pg_skips_alerts_raised_before() {
+        let fixture = write_fixture();
+        let sent = channel(true);
+        let svc = service(fixture.clone(), sent.clone());
+        let (user_id, plant_id) = create_user(&svc.pg_repo.pool).await;
+
+        let watched = [watched("Vegetable", user_id, plant_id)];
+        let first = svc.raise_pg(&svc.detect(&watched, today()).await).await.unwrap();
+        // The next run sees the same forecast and builds new alerts with new ids for it
+        let second = svc.raise_pg(&svc.detect(&watched, today()).await).await.unwrap();
+        let alerts = stored(&svc, user_id).await;
+        drop_user(&svc.pg_repo.pool, user_id).await;
+        std::fs::remove_file(fixture).unwrap();
+
+        assert_eq!(first, 2);
+        assert_eq!(second, 0);
+        assert_eq!(alerts.len(), 2);
+        // One message for both alerts, none for the repeated ones
+        assert_eq!(sent.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Postgres (PG_HOST, PG_USER, PG_PASS, PG_DB)"]
+    async fn raise_pg_sets_notified_at_only_when_a_channel_delivers() {
+        let fixture = write_fixture();
+        let failing = channel(false);
+        let svc = service(fixture.clone(), failing.clone());
+        let (user_id, plant_id) = create_user(&svc.pg_repo.pool).await;
+
+        let alerts = svc.detect(&[watched("Vegetable", user_id, plant_id)], today()).await;
+        let (frost, heat) = (&alerts[..1], &alerts[1..]);
+
+        svc.raise_pg(frost).await.unwrap();
+        let working = service(fixture.clone(), channel(true));
+        working.raise_pg(heat).await.unwrap();
+
+        let stored = stored(&svc, user_id).await;
+        drop_user(&svc.pg_repo.pool, user_id).await;
+        std::fs::remove_file(fixture).unwrap();
+
+        assert_eq!(failing.sent.lock().unwrap().len(), 1);
+        let notified = |kind: &str| stored.iter().find(|a| a.kind == kind).unwrap().notified_at.is_some();
+        assert!(!notified("frost"));
+        assert!(notified("heat"));
+    }
+}
@@ -1,11 +1,16 @@
-use crate::dtos::auth_dto::{RegisterDTO, LoginResponse, UserInfo};
+use crate::dtos::auth_dto::{normalize_email, AccountDeletionResponse, RegisterDTO, LoginResponse, UserInfo};
+use crate::errors::ServiceError;
+use crate::secret::{random_token, Secret};
 use crate::models::user::SafeUser;
 use crate::repositories::auth_postgres::AuthPostgresRepo;
 use crate::repositories::auth_supabase::AuthSupabaseRepo;
+use crate::services::mailer::{Email, Mailer};
+use crate::services::login_throttle::LoginThrottle;
+use std::sync::Arc;
 use uuid::Uuid;
 use jsonwebtoken::{encode, decode, Header, Algorithm, Validation, EncodingKey, DecodingKey};
 use serde::{Deserialize, Serialize};
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Utc, Duration};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
@@ -14,16 +19,33 @@ pub struct Claims {
     iat: usize,  // issued at
 }
 
+// Audience for email verification tokens, so they can never be used as login tokens
+const EMAIL_VERIFICATION_AUDIENCE: &str = "email-verification";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EmailVerificationClaims {
+    sub: String,
+    aud: String,
+    exp: usize,
+    iat: usize,
+}
+
 pub struct AuthService {
     pub pg_repo: AuthPostgresRepo,
     pub sb_repo: AuthSupabaseRepo,
-    pub jwt_secret: String,
+    pub jwt_secret: Secret<String>,
+    pub jwt_ttl_hours: i64,
+    pub mailer: Arc<dyn Mailer>,
+    pub app_base_url: String,
+    pub require_email_verification: bool,
+    pub login_throttle: LoginThrottle,
+    pub account_deletion_grace_days: i64,
 }
 
 impl AuthService {
     fn generate_token(&self, user_id: Uuid) -> Result<String, String> {
         let now = Utc::now();
-        let expires_at = now + Duration::hours(24); // Token valid for 24 hours
+        let expires_at = now + Duration::hours(self.jwt_ttl_hours);
         
         let claims = Claims {
             sub: user_id.to_string(),
@@ -34,24 +56,91 @@ impl AuthService {
         encode(
             &Header::default(),
             &claims,
-            &EncodingKey::from_secret(self.jwt_secret.as_ref()),
+            &EncodingKey::from_secret(self.jwt_secret.expose().as_ref()),
         ).map_err(|e| e.to_string())
     }
 
     pub fn verify_token(&self, token: &str) -> Result<Uuid, String> {
         let token_data = decode::<Claims>(
             token,
-            &DecodingKey::from_secret(self.jwt_secret.as_ref()),
+            &DecodingKey::from_secret(self.jwt_secret.expose().as_ref()),
             &Validation::new(Algorithm::HS256),
         ).map_err(|e| e.to_string())?;
 
         Uuid::parse_str(&token_data.claims.sub).map_err(|e| e.to_string())
     }
 
+    fn generate_verification_token(&self, user_id: Uuid) -> Result<String, String> {
+        let now = Utc::now();
+        let expires_at = now + Duration::hours(48); // Verification link valid for 48 hours
+
+        let claims = EmailVerificationClaims {
+            sub: user_id.to_string(),
+            aud: EMAIL_VERIFICATION_AUDIENCE.to_string(),
+            exp: expires_at.timestamp() as usize,
+            iat: now.timestamp() as usize,
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.jwt_secret.expose().as_ref()),
+        ).map_err(|e| e.to_string())
+    }
+
+    fn verify_verification_token(&self, token: &str) -> Result<Uuid, String> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_audience(&[EMAIL_VERIFICATION_AUDIENCE]);
+
+        let token_data = decode::<EmailVerificationClaims>(
+            token,
+            &DecodingKey::from_secret(self.jwt_secret.expose().as_ref()),
+            &validation,
+        ).map_err(|_| "Invalid or expired verification link".to_string())?;
+
+        Uuid::parse_str(&token_data.claims.sub).map_err(|e| e.to_string())
+    }
+
+    // verify_path is the backend specific endpoint, e.g. "/pg/auth/verify"
+    async fn send_verification_email(&self, user: &SafeUser, verify_path: &str) -> Result<(), String> {
+        let token = self.generate_verification_token(user.id)?;
+        let link = format!("{}{}?token={}", self.app_base_url.trim_end_matches('/'), verify_path, token);
+
+        let email = Email {
+            to: user.email.clone(),
+            subject: "Verify your Gardenary email address".to_string(),
+            body: format!(
+                "Hi {},\n\nPlease confirm your email address by opening the link below:\n\n{}\n\nThe link is valid for 48 hours.",
+                user.name, link
+            ),
+        };
+
+        self.mailer.send(&email).await
+    }
+
+    async fn send_deletion_scheduled_email(&self, user: &SafeUser, at: DateTime<Utc>) -> Result<(), String> {
+        let email = Email {
+            to: user.email.clone(),
+            subject: "Your Gardenary account will be deleted".to_string(),
+            body: format!(
+                "Hi {},\n\nYour account and all of your plants will be deleted on {}.\n\nChanged your mind? Just log in before then and the deletion is cancelled.",
+                user.name, at.format("%Y-%m-%d %H:%M UTC")
+            ),
+        };
+
+        self.mailer.send(&email).await
+    }
+
     // PostgreSQL methods
-    pub async fn register_pg(&self, dto: RegisterDTO) -> Result<LoginResponse, String> {
+    pub async fn register_pg(&self, mut dto: RegisterDTO) -> Result<LoginResponse, ServiceError> {
+        dto.email = normalize_email(&dto.email);
         let user = self.pg_repo.register(dto).await?;
         let token = self.generate_token(user.id)?;
+
+        // The account already exists at this point, a failed email should not fail the registration
+        if let Err(e) = self.send_verification_email(&user, "/pg/auth/verify").await {
+            log::warn!("Failed to send verification email to user {}: {}", user.id, e);
+        }
         
         Ok(LoginResponse {
             user: UserInfo {
@@ -60,13 +149,35 @@ impl AuthService {
                 email: user.email,
                 city: user.city,
                 birth_date: user.birth_date,
+                email_verified: user.email_verified_at.is_some(),
             },
             token,
         })
     }
 
-    pub async fn login_pg(&self, email: &str, password: &str) -> Result<LoginResponse, String> {
-        let user = self.pg_repo.login(email, password).await?;
+    // ip is the client address, used next to the email for throttling failed attempts
+    pub async fn login_pg(&self, email: &str, password: &str, ip: Option<&str>) -> Result<LoginResponse, ServiceError> {
+        let email = normalize_email(email);
+        if let Some(retry_after) = self.login_throttle.check(&email, ip).await? {
+            return Err(ServiceError::TooManyRequests { retry_after });
+        }
+
+        let user = match self.pg_repo.login(&email, password).await {
+            Ok(user) => user,
+            Err(ServiceError::Unauthorized(msg)) => {
+                self.login_throttle.record_failure(&email, ip).await?;
+                return Err(ServiceError::Unauthorized(msg));
+            }
+            Err(err) => return Err(err),
+        };
+        self.login_throttle.record_success(&email).await?;
+
+        // Logging in during the grace period keeps the account
+        if user.deletion_scheduled_at.is_some() {
+            self.pg_repo.schedule_deletion(user.id, None).await?;
+            log::info!("Cancelled scheduled deletion of account {}", user.id);
+        }
+
         let token = self.generate_token(user.id)?;
         
         Ok(LoginResponse {
@@ -76,6 +187,7 @@ impl AuthService {
                 email: user.email,
                 city: user.city,
                 birth_date: user.birth_date,
+                email_verified: user.email_verified_at.is_some(),
             },
             token,
         })
@@ -85,10 +197,88 @@ impl AuthService {
         self.pg_repo.get_user_by_id(id).await
     }
 
+    pub async fn verify_email_pg(&self, token: &str) -> Result<SafeUser, String> {
+        let user_id = self.verify_verification_token(token)?;
+        self.pg_repo.mark_email_verified(user_id).await
+    }
+
+    pub async fn delete_account_pg(&self, user_id: Uuid, password: &str) -> Result<AccountDeletionResponse, ServiceError> {
+        let user = self.pg_repo.get_user_by_id(user_id).await?;
+
+        // Wrong passwords count towards the same lockout as failed logins
+        if let Some(retry_after) = self.login_throttle.check(&user.email, None).await? {
+            return Err(ServiceError::TooManyRequests { retry_after });
+        }
+        match self.pg_repo.login(&user.email, password).await {
+            Ok(_) => self.login_throttle.record_success(&user.email).await?,
+            Err(ServiceError::Unauthorized(_)) => {
+                self.login_throttle.record_failure(&user.email, None).await?;
+                return Err(ServiceError::Unauthorized("Password is incorrect".to_string()));
+            }
+            Err(err) => return Err(err),
+        }
+
+        if self.account_deletion_grace_days == 0 {
+            // Plants, their care events, notes, issues and sensor data, the user's devices, zones, webhooks,
+            // gardens and share links are all removed by delete_user, see there for how
+            let deleted = self.pg_repo.delete_user(user_id).await?;
+            log::info!("Deleted account {} with {}", user_id, deleted);
+            return Ok(AccountDeletionResponse { deleted: true, plants_deleted: deleted.plants(), scheduled_for: None });
+        }
+
+        let at = Utc::now() + Duration::days(self.account_deletion_grace_days);
+        self.pg_repo.schedule_deletion(user_id, Some(at)).await?;
+        if let Err(e) = self.send_deletion_scheduled_email(&user, at).await {
+            log::warn!("Failed to send deletion notice to user {}: {}", user.id, e);
+        }
+        Ok(AccountDeletionResponse { deleted: false, plants_deleted: 0, scheduled_for: Some(at) })
+    }
+
+    // Removes accounts whose grace period is over, returns how many were deleted
+    pub async fn purge_deleted_accounts_pg(&self) -> Result<usize, String> {
+        let due = self.pg_repo.users_due_for_deletion().await?;
+        for user_id in &due {
+            let deleted = self.pg_repo.delete_user(*user_id).await?;
+            log::info!("Purged account {} with {}", user_id, deleted);
+        }
+        Ok(due.len())
+    }
+
+    // Current feed token, created on first use; regenerate revokes the old one
+    pub async fn calendar_token_pg(&self, user_id: Uuid, regenerate: bool) -> Result<String, String> {
+        if !regenerate && let Some(token) = self.pg_repo.calendar_token(user_id).await? {
+            return Ok(token);
+        }
+        let token = random_token();
+        self.pg_repo.set_calendar_token(user_id, &token).await?;
+        Ok(token)
+    }
+
+    pub async fn user_by_calendar_token_pg(&self, token: &str) -> Result<Option<SafeUser>, String> {
+        match self.pg_repo.user_id_by_calendar_token(token).await? {
+            Some(user_id) => self.pg_repo.get_user_by_id(user_id).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn can_add_plants_pg(&self, user_id: Uuid) -> Result<bool, String> {
+        if !self.require_email_verification {
+            return Ok(true);
+        }
+        let user = self.pg_repo.get_user_by_id(user_id).await?;
+        Ok(user.email_verified_at.is_some())
+    }
+
     // Supabase methods
-    pub async fn register_sb(&self, dto: RegisterDTO) -> Result<LoginResponse, String> {
+    pub async fn register_sb(&self, mut dto: RegisterDTO) -> Result<LoginResponse, ServiceError> {
+        dto.email = normalize_email(&dto.email);
         let user = self.sb_repo.register(dto).await?;
         let token = self.generate_token(user.id)?;
+
+        // The account already exists at this point, a failed email should not fail the registration
+        if let Err(e) = self.send_verification_email(&user, "/sb/auth/verify").await {
+            log::warn!("Failed to send verification email to user {}: {}", user.id, e);
+        }
         
         Ok(LoginResponse {
             user: UserInfo {
@@ -97,13 +287,35 @@ impl AuthService {
                 email: user.email,
                 city: user.city,
                 birth_date: user.birth_date,
+                email_verified: user.email_verified_at.is_some(),
             },
             token,
         })
     }
 
-    pub async fn login_sb(&self, email: &str, password: &str) -> Result<LoginResponse, String> {
-        let user = self.sb_repo.login(email, password).await?;
+    // ip is the client address, used next to the email for throttling failed attempts
+    pub async fn login_sb(&self, email: &str, password: &str, ip: Option<&str>) -> Result<LoginResponse, ServiceError> {
+        let email = normalize_email(email);
+        if let Some(retry_after) = self.login_throttle.check(&email, ip).await? {
+            return Err(ServiceError::TooManyRequests { retry_after });
+        }
+
+        let user = match self.sb_repo.login(&email, password).await {
+            Ok(user) => user,
+            Err(ServiceError::Unauthorized(msg)) => {
+                self.login_throttle.record_failure(&email, ip).await?;
+                return Err(ServiceError::Unauthorized(msg));
+            }
+            Err(err) => return Err(err),
+        };
+        self.login_throttle.record_success(&email).await?;
+
+        // Logging in during the grace period keeps the account
+        if user.deletion_scheduled_at.is_some() {
+            self.sb_repo.schedule_deletion(user.id, None).await?;
+            log::info!("Cancelled scheduled deletion of account {}", user.id);
+        }
+
         let token = self.generate_token(user.id)?;
         
         Ok(LoginResponse {
@@ -113,6 +325,7 @@ impl AuthService {
                 email: user.email,
                 city: user.city,
                 birth_date: user.birth_date,
+                email_verified: user.email_verified_at.is_some(),
             },
             token,
         })
@@ -121,4 +334,76 @@ impl AuthService {
     pub async fn get_user_by_id_sb(&self, id: Uuid) -> Result<SafeUser, String> {
         self.sb_repo.get_user_by_id(id).await
     }
+
+    pub async fn verify_email_sb(&self, token: &str) -> Result<SafeUser, String> {
+        let user_id = self.verify_verification_token(token)?;
+        self.sb_repo.mark_email_verified(user_id).await
+    }
+
+    pub async fn delete_account_sb(&self, user_id: Uuid, password: &str) -> Result<AccountDeletionResponse, ServiceError> {
+        let user = self.sb_repo.get_user_by_id(user_id).await?;
+
+        // Wrong passwords count towards the same lockout as failed logins
+        if let Some(retry_after) = self.login_throttle.check(&user.email, None).await? {
+            return Err(ServiceError::TooManyRequests { retry_after });
+        }
+        match self.sb_repo.login(&user.email, password).await {
+            Ok(_) => self.login_throttle.record_success(&user.email).await?,
+            Err(ServiceError::Unauthorized(_)) => {
+                self.login_throttle.record_failure(&user.email, None).await?;
+                return Err(ServiceError::Unauthorized("Password is incorrect".to_string()));
+            }
+            Err(err) => return Err(err),
+        }
+
+        if self.account_deletion_grace_days == 0 {
+            // Plants, their care events, notes, issues and sensor data, the user's devices, zones, webhooks,
+            // gardens and share links are all removed by delete_user, see there for how
+            let deleted = self.sb_repo.delete_user(user_id).await?;
+            log::info!("Deleted account {} with {}", user_id, deleted);
+            return Ok(AccountDeletionResponse { deleted: true, plants_deleted: deleted.plants(), scheduled_for: None });
+        }
+
+        let at = Utc::now() + Duration::days(self.account_deletion_grace_days);
+        self.sb_repo.schedule_deletion(user_id, Some(at)).await?;
+        if let Err(e) = self.send_deletion_scheduled_email(&user, at).await {
+            log::warn!("Failed to send deletion notice to user {}: {}", user.id, e);
+        }
+        Ok(AccountDeletionResponse { deleted: false, plants_deleted: 0, scheduled_for: Some(at) })
+    }
+
+    // Removes accounts whose grace period is over, returns how many were deleted
+    pub async fn purge_deleted_accounts_sb(&self) -> Result<usize, String> {
+        let due = self.sb_repo.users_due_for_deletion().await?;
+        for user_id in &due {
+            let deleted = self.sb_repo.delete_user(*user_id).await?;
+            log::info!("Purged account {} with {}", user_id, deleted);
+        }
+        Ok(due.len())
+    }
+
+    // Current feed token, created on first use; regenerate revokes the old one
+    pub async fn calendar_token_sb(&self, user_id: Uuid, regenerate: bool) -> Result<String, String> {
+        if !regenerate && let Some(token) = self.sb_repo.calendar_token(user_id).await? {
+            return Ok(token);
+        }
+        let token = random_token();
+        self.sb_repo.set_calendar_token(user_id, &token).await?;
+        Ok(token)
+    }
+
+    pub async fn user_by_calendar_token_sb(&self, token: &str) -> Result<Option<SafeUser>, String> {
+        match self.sb_repo.user_id_by_calendar_token(token).await? {
+            Some(user_id) => self.sb_repo.get_user_by_id(user_id).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn can_add_plants_sb(&self, user_id: Uuid) -> Result<bool, String> {
+        if !self.require_email_verification {
+            return Ok(true);
+        }
+        let user = self.sb_repo.get_user_by_id(user_id).await?;
+        Ok(user.email_verified_at.is_some())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,143 @@
+use crate::models::plant::Plant;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CareKind {
+    Water,
+    Fertilize,
+}
+
+impl CareKind {
+    pub fn status_key(&self) -> &'static str {
+        match self {
+            CareKind::Water => "watered",
+            CareKind::Fertilize => "fertilized",
+        }
+    }
+
+    pub fn verb(&self) -> &'static str {
+        match self {
+            CareKind::Water => "Water",
+            CareKind::Fertilize => "Fertilize",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CareTask {
+    pub plant_id: Uuid,
+    pub plant_name: String,
+    pub kind: CareKind,
+    pub due: NaiveDate,
+    pub overdue: bool, // the previous due date already passed without the task being done
+    pub skip_reason: Option<String>, // e.g. rain took care of watering, see WeatherService::mark_rain_skips
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MilestoneKind {
+    Planted,
+    Harvest,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Milestone {
+    pub plant_id: Uuid,
+    pub plant_name: String,
+    pub kind: MilestoneKind,
+    pub date: NaiveDate,
+}
+
+// Rough defaults per plant type, which stands in for the species
+#[derive(Debug, Clone, Copy)]
+pub struct SpeciesProfile {
+    pub water_every: i64, // days
+    pub fertilize_every: i64, // days
+    pub harvest_days: Option<i64>, // from planting, crops only
+    pub frost_sensitive: bool,
+    pub heat_sensitive: bool,
+}
+
+pub fn species_profile(plant_type: &str) -> SpeciesProfile {
+    let (water_every, fertilize_every, harvest_days, frost_sensitive, heat_sensitive) = match plant_type.to_lowercase().as_str() {
+        "vegetable" => (1, 14, Some(70), true, true),
+        "fruit" => (2, 21, Some(90), true, false),
+        "herb" => (2, 28, Some(45), true, false),
+        "flower" => (3, 21, None, false, true),
+        _ => (2, 21, None, false, false),
+    };
+    SpeciesProfile { water_every, fertilize_every, harvest_days, frost_sensitive, heat_sensitive }
+}
+
+// Latest "YYYY-MM-DD" key in plant.status where the given flag is true
+pub fn last_done(plant: &Plant, kind: CareKind) -> Option<NaiveDate> {
+    plant.status.as_object()?
+        .iter()
+        .filter(|(_, day)| day.get(kind.status_key()).and_then(|v| v.as_bool()).unwrap_or(false))
+        .filter_map(|(date, _)| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .max()
+}
+
+// Every due date from today up to today + horizon_days, soonest first
+pub fn upcoming_tasks(plants: &[Plant], today: NaiveDate, horizon_days: i64) -> Vec<CareTask> {
+    let until = today + Duration::days(horizon_days);
+    let mut tasks = Vec::new();
+
+    for plant in plants {
+        let profile = species_profile(&plant.plant_type);
+        for (kind, every) in [(CareKind::Water, profile.water_every), (CareKind::Fertilize, profile.fertilize_every)] {
+            // Never done yet counts as due today
+            let next = last_done(plant, kind).map(|d| d + Duration::days(every)).unwrap_or(today);
+            let overdue = next < today;
+            let mut due = next.max(today);
+            while due <= until {
+                tasks.push(CareTask {
+                    plant_id: plant.id,
+                    plant_name: plant.name.clone(),
+                    kind,
+                    due,
+                    overdue: overdue && due == today,
+                    skip_reason: None,
+                });
+                due += Duration::days(every);
+            }
+        }
+    }
+
+    tasks.sort_by(|a, b| (a.due, &a.plant_name).cmp(&(b.due, &b.plant_name)));
+    tasks
+}
+
+// Planting date and, for crops, the expected harvest unless it was already harvested
+pub fn milestones(plants: &[Plant]) -> Vec<Milestone> {
+    let mut milestones = Vec::new();
+    for plant in plants {
+        let planted = to_date(plant.planted_date);
+        milestones.push(Milestone {
+            plant_id: plant.id,
+            plant_name: plant.name.clone(),
+            kind: MilestoneKind::Planted,
+            date: planted,
+        });
+
+        let harvested = plant.status.as_object()
+            .map(|days| days.values().any(|d| d.get("harvested").and_then(|v| v.as_bool()).unwrap_or(false)))
+            .unwrap_or(false);
+        if let Some(days) = species_profile(&plant.plant_type).harvest_days && !harvested {
+            milestones.push(Milestone {
+                plant_id: plant.id,
+                plant_name: plant.name.clone(),
+                kind: MilestoneKind::Harvest,
+                date: planted + Duration::days(days),
+            });
+        }
+    }
+    milestones
+}
+
+fn to_date(at: DateTime<Utc>) -> NaiveDate {
+    at.date_naive()
+}
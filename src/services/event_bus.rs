@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+// Events a slow subscriber may fall behind by before it misses some and is told to reload
+const BUS_CAPACITY: usize = 256;
+
+// A change pushed to live dashboards, e.g. "plant.updated" or "care.recorded"
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveEvent {
+    #[serde(skip)]
+    pub audience: Vec<Uuid>, // whose dashboards get it: the plant's owner and the members of its garden
+    pub event: String,
+    pub data: serde_json::Value,
+    pub at: DateTime<Utc>,
+}
+
+// In-process only: every server instance has its own bus and only sees the writes it handled
+pub struct EventBus {
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus { sender: broadcast::channel(BUS_CAPACITY).0 }
+    }
+}
+
+impl EventBus {
+    // Nobody listening is fine, the event is simply dropped
+    pub fn publish(&self, audience: Vec<Uuid>, event: &str, data: serde_json::Value) {
+        let _ = self.sender.send(LiveEvent { audience, event: event.to_string(), data, at: Utc::now() });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,399 @@
+use crate::dtos::auth_dto::normalize_email;
+use crate::dtos::garden_dto::{GardenDTO, InviteMemberDTO, UpdateMemberDTO};
+use crate::errors::ServiceError;
+use crate::models::garden::{can_edit, Garden, GardenInvitation, GardenMember, MemberGarden, ReceivedInvitation, OWNER};
+use crate::models::plant::Plant;
+use crate::models::user::SafeUser;
+use crate::repositories::auth_postgres::AuthPostgresRepo;
+use crate::repositories::auth_supabase::AuthSupabaseRepo;
+use crate::repositories::garden_postgres::GardenPostgresRepo;
+use crate::repositories::garden_supabase::GardenSupabaseRepo;
+use crate::repositories::plant_postgres::PlantPostgresRepo;
+use crate::repositories::plant_supabase::PlantSupabaseRepo;
+use crate::services::mailer::{Email, Mailer};
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// Shared gardens: the owner invites people by email as editors or viewers. Methods return None
+// when the garden does not exist or the user is not one of its members, so both look the same.
+pub struct GardenService {
+    pub pg_repo: GardenPostgresRepo,
+    pub sb_repo: GardenSupabaseRepo,
+    pub plant_pg_repo: PlantPostgresRepo,
+    pub plant_sb_repo: PlantSupabaseRepo,
+    pub auth_pg_repo: AuthPostgresRepo,
+    pub auth_sb_repo: AuthSupabaseRepo,
+    pub mailer: Arc<dyn Mailer>,
+    pub app_base_url: String,
+}
+
+fn owner_only(role: &str, action: &str) -> Result<(), ServiceError> {
+    if role == OWNER {
+        Ok(())
+    } else {
+        Err(ServiceError::Forbidden(format!("Only the garden's owner can {}", action)))
+    }
+}
+
+// Invitations go to an address, so only someone who proved they own it may see or answer them
+fn verified_email(user: &SafeUser) -> Result<String, ServiceError> {
+    if user.email_verified_at.is_none() {
+        return Err(ServiceError::Forbidden("Verify your email address to see and answer garden invitations".to_string()));
+    }
+    Ok(normalize_email(&user.email))
+}
+
+fn new_garden(user_id: Uuid, dto: GardenDTO) -> Garden {
+    let now = Utc::now();
+    Garden {
+        id: Uuid::new_v4(),
+        owner_id: user_id,
+        name: dto.name.trim().to_string(),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn new_invitation(garden_id: Uuid, invited_by: Uuid, dto: InviteMemberDTO) -> GardenInvitation {
+    GardenInvitation {
+        id: Uuid::new_v4(),
+        garden_id,
+        email: normalize_email(&dto.email),
+        role: dto.role,
+        invited_by,
+        status: "pending".to_string(),
+        created_at: Utc::now(),
+        responded_at: None,
+    }
+}
+
+impl GardenService {
+    // Best effort: the invitation stands even if the email does not go out, the invitee also sees
+    // it under GET /invitations
+    async fn send_invitation_email(&self, invitation: &GardenInvitation, garden: &Garden, inviter: &str) {
+        let email = Email {
+            to: invitation.email.clone(),
+            subject: format!("{} invited you to a garden on Gardenary", inviter),
+            body: format!(
+                "Hi,\n\n{} invited you to join the garden \"{}\" as {}.\n\nLog in at {} with this email address to accept or decline the invitation.",
+                inviter, garden.name, invitation.role, self.app_base_url
+            ),
+        };
+        if let Err(e) = self.mailer.send(&email).await {
+            log::warn!("Sending the invitation to {} failed: {}", invitation.email, e);
+        }
+    }
+
+    // PostgreSQL methods
+    pub async fn create_pg(&self, user_id: Uuid, dto: GardenDTO) -> Result<Garden, String> {
+        self.pg_repo.create(&new_garden(user_id, dto)).await
+    }
+
+    pub async fn get_all_pg(&self, user_id: Uuid) -> Result<Vec<MemberGarden>, String> {
+        self.pg_repo.member_gardens(user_id).await
+    }
+
+    pub async fn get_by_id_pg(&self, id: Uuid, user_id: Uuid) -> Result<Option<MemberGarden>, String> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        Ok(self.pg_repo.get_by_id(id).await?.map(|garden| MemberGarden { garden, role }))
+    }
+
+    pub async fn rename_pg(&self, id: Uuid, user_id: Uuid, dto: GardenDTO) -> Result<Option<Garden>, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        owner_only(&role, "rename it")?;
+        Ok(self.pg_repo.rename(id, dto.name.trim()).await?)
+    }
+
+    pub async fn delete_pg(&self, id: Uuid, user_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        owner_only(&role, "delete it")?;
+        Ok(self.pg_repo.delete(id).await?)
+    }
+
+    pub async fn members_pg(&self, id: Uuid, user_id: Uuid) -> Result<Option<Vec<GardenMember>>, String> {
+        if self.pg_repo.role(id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        self.pg_repo.members(id).await.map(Some)
+    }
+
+    // 0 when there is no such member; the owner's own role cannot be changed
+    pub async fn set_role_pg(&self, id: Uuid, user_id: Uuid, member_id: Uuid, dto: UpdateMemberDTO) -> Result<u64, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        owner_only(&role, "change roles")?;
+        Ok(self.pg_repo.set_role(id, member_id, &dto.role).await?)
+    }
+
+    // The owner removes anyone, everyone else can only leave; the owner deletes the garden instead
+    pub async fn remove_member_pg(&self, id: Uuid, user_id: Uuid, member_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        if member_id != user_id {
+            owner_only(&role, "remove members")?;
+        } else if role == OWNER {
+            return Err(ServiceError::Conflict("The owner cannot leave the garden, delete it instead".to_string()));
+        }
+        Ok(self.pg_repo.remove_member(id, member_id).await?)
+    }
+
+    pub async fn invite_pg(&self, id: Uuid, user_id: Uuid, dto: InviteMemberDTO) -> Result<Option<GardenInvitation>, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        owner_only(&role, "invite members")?;
+        let Some(garden) = self.pg_repo.get_by_id(id).await? else {
+            return Ok(None);
+        };
+
+        let invitation = new_invitation(id, user_id, dto);
+        let members = self.pg_repo.members(id).await?;
+        if members.iter().any(|m| normalize_email(&m.email) == invitation.email) {
+            return Err(ServiceError::Conflict("This person is already a member of the garden".to_string()));
+        }
+        let Some(invitation) = self.pg_repo.create_invitation(&invitation).await? else {
+            return Err(ServiceError::Conflict("This email address already has a pending invitation".to_string()));
+        };
+
+        let inviter = self.auth_pg_repo.get_user_by_id(user_id).await?;
+        self.send_invitation_email(&invitation, &garden, &inviter.name).await;
+        Ok(Some(invitation))
+    }
+
+    pub async fn invitations_pg(&self, id: Uuid, user_id: Uuid) -> Result<Option<Vec<GardenInvitation>>, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        owner_only(&role, "see its invitations")?;
+        Ok(Some(self.pg_repo.invitations(id).await?))
+    }
+
+    // Only pending invitations can be revoked
+    pub async fn revoke_invitation_pg(&self, id: Uuid, user_id: Uuid, invitation_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        owner_only(&role, "revoke invitations")?;
+        Ok(self.pg_repo.revoke_invitation(id, invitation_id).await?)
+    }
+
+    // Invitations are addressed by email, so they show up for whoever has verified the address now
+    pub async fn received_invitations_pg(&self, user_id: Uuid) -> Result<Vec<ReceivedInvitation>, ServiceError> {
+        let email = verified_email(&self.auth_pg_repo.get_user_by_id(user_id).await?)?;
+        Ok(self.pg_repo.pending_invitations(&email).await?)
+    }
+
+    // None when there is no pending invitation for the user's address
+    pub async fn respond_pg(&self, invitation_id: Uuid, user_id: Uuid, accept: bool) -> Result<Option<GardenInvitation>, ServiceError> {
+        let email = verified_email(&self.auth_pg_repo.get_user_by_id(user_id).await?)?;
+        Ok(self.pg_repo.respond(invitation_id, &email, user_id, accept).await?)
+    }
+
+    pub async fn plants_pg(&self, id: Uuid, user_id: Uuid) -> Result<Option<Vec<Plant>>, String> {
+        if self.pg_repo.role(id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        let plant_ids = self.pg_repo.plant_ids(id).await?;
+        let mut plants = self.plant_pg_repo.get_by_ids(&plant_ids).await?;
+        plants.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Some(plants))
+    }
+
+    // Editors and the owner share their own plants; None when the garden or the plant is not found
+    pub async fn add_plant_pg(&self, id: Uuid, user_id: Uuid, plant_id: Uuid) -> Result<Option<Plant>, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        if !can_edit(&role) {
+            return Err(ServiceError::Forbidden("Viewers cannot add plants to the garden".to_string()));
+        }
+        match self.pg_repo.plant_access(plant_id, user_id).await? {
+            Some((owner_id, _)) if owner_id == user_id => {}
+            Some(_) => return Err(ServiceError::Forbidden("Only the plant's owner can add it to a garden".to_string())),
+            None => return Ok(None),
+        }
+        if !self.pg_repo.add_plant(id, plant_id, user_id).await? {
+            return Err(ServiceError::Conflict("This plant is already in a garden".to_string()));
+        }
+        Ok(Some(self.plant_pg_repo.get_by_id(plant_id, user_id).await?))
+    }
+
+    // Editors and the owner, or the plant's owner taking it back
+    pub async fn remove_plant_pg(&self, id: Uuid, user_id: Uuid, plant_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(role) = self.pg_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        if !can_edit(&role) {
+            let owns_plant = matches!(self.pg_repo.plant_access(plant_id, user_id).await?, Some((owner_id, _)) if owner_id == user_id);
+            if !owns_plant {
+                return Err(ServiceError::Forbidden("Viewers can only take their own plants out of the garden".to_string()));
+            }
+        }
+        Ok(self.pg_repo.remove_plant(id, plant_id).await?)
+    }
+
+    // Supabase methods
+    pub async fn create_sb(&self, user_id: Uuid, dto: GardenDTO) -> Result<Garden, String> {
+        self.sb_repo.create(&new_garden(user_id, dto)).await
+    }
+
+    pub async fn get_all_sb(&self, user_id: Uuid) -> Result<Vec<MemberGarden>, String> {
+        self.sb_repo.member_gardens(user_id).await
+    }
+
+    pub async fn get_by_id_sb(&self, id: Uuid, user_id: Uuid) -> Result<Option<MemberGarden>, String> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        Ok(self.sb_repo.get_by_id(id).await?.map(|garden| MemberGarden { garden, role }))
+    }
+
+    pub async fn rename_sb(&self, id: Uuid, user_id: Uuid, dto: GardenDTO) -> Result<Option<Garden>, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        owner_only(&role, "rename it")?;
+        Ok(self.sb_repo.rename(id, dto.name.trim()).await?)
+    }
+
+    pub async fn delete_sb(&self, id: Uuid, user_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        owner_only(&role, "delete it")?;
+        Ok(self.sb_repo.delete(id).await?)
+    }
+
+    pub async fn members_sb(&self, id: Uuid, user_id: Uuid) -> Result<Option<Vec<GardenMember>>, String> {
+        if self.sb_repo.role(id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        self.sb_repo.members(id).await.map(Some)
+    }
+
+    // 0 when there is no such member; the owner's own role cannot be changed
+    pub async fn set_role_sb(&self, id: Uuid, user_id: Uuid, member_id: Uuid, dto: UpdateMemberDTO) -> Result<u64, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        owner_only(&role, "change roles")?;
+        Ok(self.sb_repo.set_role(id, member_id, &dto.role).await?)
+    }
+
+    // The owner removes anyone, everyone else can only leave; the owner deletes the garden instead
+    pub async fn remove_member_sb(&self, id: Uuid, user_id: Uuid, member_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        if member_id != user_id {
+            owner_only(&role, "remove members")?;
+        } else if role == OWNER {
+            return Err(ServiceError::Conflict("The owner cannot leave the garden, delete it instead".to_string()));
+        }
+        Ok(self.sb_repo.remove_member(id, member_id).await?)
+    }
+
+    pub async fn invite_sb(&self, id: Uuid, user_id: Uuid, dto: InviteMemberDTO) -> Result<Option<GardenInvitation>, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        owner_only(&role, "invite members")?;
+        let Some(garden) = self.sb_repo.get_by_id(id).await? else {
+            return Ok(None);
+        };
+
+        let invitation = new_invitation(id, user_id, dto);
+        let members = self.sb_repo.members(id).await?;
+        if members.iter().any(|m| normalize_email(&m.email) == invitation.email) {
+            return Err(ServiceError::Conflict("This person is already a member of the garden".to_string()));
+        }
+        let Some(invitation) = self.sb_repo.create_invitation(&invitation).await? else {
+            return Err(ServiceError::Conflict("This email address already has a pending invitation".to_string()));
+        };
+
+        let inviter = self.auth_sb_repo.get_user_by_id(user_id).await?;
+        self.send_invitation_email(&invitation, &garden, &inviter.name).await;
+        Ok(Some(invitation))
+    }
+
+    pub async fn invitations_sb(&self, id: Uuid, user_id: Uuid) -> Result<Option<Vec<GardenInvitation>>, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        owner_only(&role, "see its invitations")?;
+        Ok(Some(self.sb_repo.invitations(id).await?))
+    }
+
+    // Only pending invitations can be revoked
+    pub async fn revoke_invitation_sb(&self, id: Uuid, user_id: Uuid, invitation_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        owner_only(&role, "revoke invitations")?;
+        Ok(self.sb_repo.revoke_invitation(id, invitation_id).await?)
+    }
+
+    // Invitations are addressed by email, so they show up for whoever has verified the address now
+    pub async fn received_invitations_sb(&self, user_id: Uuid) -> Result<Vec<ReceivedInvitation>, ServiceError> {
+        let email = verified_email(&self.auth_sb_repo.get_user_by_id(user_id).await?)?;
+        Ok(self.sb_repo.pending_invitations(&email).await?)
+    }
+
+    // None when there is no pending invitation for the user's address
+    pub async fn respond_sb(&self, invitation_id: Uuid, user_id: Uuid, accept: bool) -> Result<Option<GardenInvitation>, ServiceError> {
+        let email = verified_email(&self.auth_sb_repo.get_user_by_id(user_id).await?)?;
+        Ok(self.sb_repo.respond(invitation_id, &email, user_id, accept).await?)
+    }
+
+    pub async fn plants_sb(&self, id: Uuid, user_id: Uuid) -> Result<Option<Vec<Plant>>, String> {
+        if self.sb_repo.role(id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        let plant_ids = self.sb_repo.plant_ids(id).await?;
+        let mut plants = self.plant_sb_repo.get_by_ids(&plant_ids).await?;
+        plants.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Some(plants))
+    }
+
+    // Editors and the owner share their own plants; None when the garden or the plant is not found
+    pub async fn add_plant_sb(&self, id: Uuid, user_id: Uuid, plant_id: Uuid) -> Result<Option<Plant>, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(None);
+        };
+        if !can_edit(&role) {
+            return Err(ServiceError::Forbidden("Viewers cannot add plants to the garden".to_string()));
+        }
+        match self.sb_repo.plant_access(plant_id, user_id).await? {
+            Some((owner_id, _)) if owner_id == user_id => {}
+            Some(_) => return Err(ServiceError::Forbidden("Only the plant's owner can add it to a garden".to_string())),
+            None => return Ok(None),
+        }
+        if !self.sb_repo.add_plant(id, plant_id, user_id).await? {
+            return Err(ServiceError::Conflict("This plant is already in a garden".to_string()));
+        }
+        Ok(Some(self.plant_sb_repo.get_by_id(plant_id, user_id).await?))
+    }
+
+    // Editors and the owner, or the plant's owner taking it back
+    pub async fn remove_plant_sb(&self, id: Uuid, user_id: Uuid, plant_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(role) = self.sb_repo.role(id, user_id).await? else {
+            return Ok(0);
+        };
+        if !can_edit(&role) {
+            let owns_plant = matches!(self.sb_repo.plant_access(plant_id, user_id).await?, Some((owner_id, _)) if owner_id == user_id);
+            if !owns_plant {
+                return Err(ServiceError::Forbidden("Viewers can only take their own plants out of the garden".to_string()));
+            }
+        }
+        Ok(self.sb_repo.remove_plant(id, plant_id).await?)
+    }
+}
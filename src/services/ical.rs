@@ -0,0 +1,100 @@
+use crate::models::plant::Plant;
+use crate::models::user::SafeUser;
+use crate::services::care_schedule::{milestones, CareTask, MilestoneKind};
+use chrono::{Duration, NaiveDate, Utc};
+
+// How far ahead care tasks are listed; milestones are always included
+pub const FEED_HORIZON_DAYS: i64 = 30;
+
+// RFC 5545 TEXT escaping
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Lines longer than 75 octets continue on the next line after a single space, never inside a UTF-8 character
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn date_value(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+// tasks come from care_schedule::upcoming_tasks; todos = true renders them as VTODO,
+// which most calendar apps hide on subscribed calendars
+pub fn render_feed(user: &SafeUser, plants: &[Plant], tasks: &[CareTask], todos: bool) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    let mut line = |text: String| push_line(&mut out, &text);
+
+    line("BEGIN:VCALENDAR".to_string());
+    line("VERSION:2.0".to_string());
+    line("PRODID:-//Gardenary//Garden calendar//EN".to_string());
+    line("CALSCALE:GREGORIAN".to_string());
+    line("METHOD:PUBLISH".to_string());
+    line(format!("X-WR-CALNAME:{}", escape(&format!("Gardenary - {}", user.name))));
+    line("REFRESH-INTERVAL;VALUE=DURATION:PT6H".to_string());
+    line("X-PUBLISHED-TTL:PT6H".to_string());
+
+    for task in tasks {
+        let summary = match (&task.skip_reason, task.overdue) {
+            (Some(reason), _) => format!("{} {} ({})", task.kind.verb(), task.plant_name, reason),
+            (None, true) => format!("{} {} (overdue)", task.kind.verb(), task.plant_name),
+            (None, false) => format!("{} {}", task.kind.verb(), task.plant_name),
+        };
+        let uid = format!("{}-{}-{}@gardenary", task.kind.status_key(), task.plant_id, date_value(task.due));
+
+        if todos {
+            line("BEGIN:VTODO".to_string());
+            line(format!("UID:{}", uid));
+            line(format!("DTSTAMP:{}", stamp));
+            line(format!("DUE;VALUE=DATE:{}", date_value(task.due)));
+            line(format!("SUMMARY:{}", escape(&summary)));
+            line("STATUS:NEEDS-ACTION".to_string());
+            line("CATEGORIES:Care".to_string());
+            line("END:VTODO".to_string());
+        } else {
+            line("BEGIN:VEVENT".to_string());
+            line(format!("UID:{}", uid));
+            line(format!("DTSTAMP:{}", stamp));
+            line(format!("DTSTART;VALUE=DATE:{}", date_value(task.due)));
+            line(format!("DTEND;VALUE=DATE:{}", date_value(task.due + Duration::days(1))));
+            line(format!("SUMMARY:{}", escape(&summary)));
+            line("TRANSP:TRANSPARENT".to_string());
+            line("CATEGORIES:Care".to_string());
+            line("END:VEVENT".to_string());
+        }
+    }
+
+    for milestone in milestones(plants) {
+        let (kind, summary) = match milestone.kind {
+            MilestoneKind::Planted => ("planted", format!("Planted {}", milestone.plant_name)),
+            MilestoneKind::Harvest => ("harvest", format!("Expected harvest: {}", milestone.plant_name)),
+        };
+        line("BEGIN:VEVENT".to_string());
+        line(format!("UID:{}-{}@gardenary", kind, milestone.plant_id));
+        line(format!("DTSTAMP:{}", stamp));
+        line(format!("DTSTART;VALUE=DATE:{}", date_value(milestone.date)));
+        line(format!("DTEND;VALUE=DATE:{}", date_value(milestone.date + Duration::days(1))));
+        line(format!("SUMMARY:{}", escape(&summary)));
+        line("TRANSP:TRANSPARENT".to_string());
+        line("CATEGORIES:Milestone".to_string());
+        line("END:VEVENT".to_string());
+    }
+
+    line("END:VCALENDAR".to_string());
+    out
+}
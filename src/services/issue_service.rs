@@ -0,0 +1,156 @@
+use crate::dtos::issue_dto::{normalize_issue_type, AddTreatmentDTO, CreateIssueDTO, UpdateIssueDTO};
+use crate::errors::ServiceError;
+use crate::models::plant_issue::{AffectedPlant, PlantIssue, Treatment};
+use crate::repositories::issue_postgres::IssuePostgresRepo;
+use crate::repositories::issue_supabase::IssueSupabaseRepo;
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+pub struct IssueService {
+    pub pg_repo: IssuePostgresRepo,
+    pub sb_repo: IssueSupabaseRepo,
+}
+
+// DTO dates are validated by the handlers before they get here
+fn parse_date(date: Option<&str>) -> Option<NaiveDate> {
+    date.and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+fn new_issue(plant_id: Uuid, user_id: Uuid, dto: CreateIssueDTO) -> PlantIssue {
+    let now = Utc::now();
+    PlantIssue {
+        id: Uuid::new_v4(),
+        plant_id,
+        user_id,
+        issue_type: normalize_issue_type(&dto.issue_type),
+        severity: dto.severity,
+        description: dto.description,
+        opened_on: parse_date(dto.opened_on.as_deref()).unwrap_or_else(|| now.date_naive()),
+        resolved_on: None,
+        resolution: None,
+        treatments: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+// Applies the changes to a stored issue; the dates are only checked against each other here
+// because either one may come from the stored issue
+fn apply_update(issue: &mut PlantIssue, dto: UpdateIssueDTO) -> Result<(), ServiceError> {
+    if let Some(issue_type) = dto.issue_type {
+        issue.issue_type = normalize_issue_type(&issue_type);
+    }
+    if let Some(severity) = dto.severity {
+        issue.severity = severity;
+    }
+    if let Some(description) = dto.description {
+        issue.description = Some(description);
+    }
+    if let Some(opened_on) = parse_date(dto.opened_on.as_deref()) {
+        issue.opened_on = opened_on;
+    }
+    if dto.reopen {
+        issue.resolved_on = None;
+        issue.resolution = None;
+    }
+    if let Some(resolved_on) = parse_date(dto.resolved_on.as_deref()) {
+        issue.resolved_on = Some(resolved_on);
+    }
+    if let Some(resolution) = dto.resolution {
+        // A resolution without a date resolves the issue today
+        issue.resolved_on = issue.resolved_on.or_else(|| Some(Utc::now().date_naive()));
+        issue.resolution = Some(resolution);
+    }
+
+    if let Some(resolved_on) = issue.resolved_on && resolved_on < issue.opened_on {
+        return Err(ServiceError::Conflict("resolved_on must not be before opened_on".to_string()));
+    }
+    issue.updated_at = Utc::now();
+    Ok(())
+}
+
+fn add_treatment(issue: &mut PlantIssue, dto: AddTreatmentDTO) {
+    issue.treatments.push(Treatment {
+        date: parse_date(dto.date.as_deref()).unwrap_or_else(|| Utc::now().date_naive()),
+        treatment: dto.treatment.trim().to_string(),
+        notes: dto.notes,
+    });
+    issue.treatments.sort_by_key(|t| t.date);
+    issue.updated_at = Utc::now();
+}
+
+impl IssueService {
+    // PostgreSQL methods
+    pub async fn create_pg(&self, plant_id: Uuid, user_id: Uuid, dto: CreateIssueDTO) -> Result<Option<PlantIssue>, String> {
+        self.pg_repo.create(&new_issue(plant_id, user_id, dto)).await
+    }
+
+    pub async fn get_all_pg(&self, plant_id: Uuid, user_id: Uuid, open: Option<bool>) -> Result<Vec<PlantIssue>, String> {
+        self.pg_repo.get_all_by_plant(plant_id, user_id, open).await
+    }
+
+    pub async fn get_by_id_pg(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantIssue>, String> {
+        self.pg_repo.get_by_id(id, plant_id, user_id).await
+    }
+
+    pub async fn update_pg(&self, id: Uuid, plant_id: Uuid, user_id: Uuid, dto: UpdateIssueDTO) -> Result<Option<PlantIssue>, ServiceError> {
+        let Some(mut issue) = self.pg_repo.get_by_id(id, plant_id, user_id).await? else {
+            return Ok(None);
+        };
+        apply_update(&mut issue, dto)?;
+        Ok(self.pg_repo.update(&issue).await?)
+    }
+
+    pub async fn add_treatment_pg(&self, id: Uuid, plant_id: Uuid, user_id: Uuid, dto: AddTreatmentDTO) -> Result<Option<PlantIssue>, String> {
+        let Some(mut issue) = self.pg_repo.get_by_id(id, plant_id, user_id).await? else {
+            return Ok(None);
+        };
+        add_treatment(&mut issue, dto);
+        self.pg_repo.update(&issue).await
+    }
+
+    pub async fn delete_pg(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.pg_repo.delete(id, plant_id, user_id).await
+    }
+
+    pub async fn affected_plants_pg(&self, user_id: Uuid, issue_type: &str) -> Result<Vec<AffectedPlant>, String> {
+        self.pg_repo.affected_plants(user_id, &normalize_issue_type(issue_type)).await
+    }
+
+    // Supabase methods
+    pub async fn create_sb(&self, plant_id: Uuid, user_id: Uuid, dto: CreateIssueDTO) -> Result<Option<PlantIssue>, String> {
+        self.sb_repo.create(&new_issue(plant_id, user_id, dto)).await
+    }
+
+    pub async fn get_all_sb(&self, plant_id: Uuid, user_id: Uuid, open: Option<bool>) -> Result<Vec<PlantIssue>, String> {
+        self.sb_repo.get_all_by_plant(plant_id, user_id, open).await
+    }
+
+    pub async fn get_by_id_sb(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantIssue>, String> {
+        self.sb_repo.get_by_id(id, plant_id, user_id).await
+    }
+
+    pub async fn update_sb(&self, id: Uuid, plant_id: Uuid, user_id: Uuid, dto: UpdateIssueDTO) -> Result<Option<PlantIssue>, ServiceError> {
+        let Some(mut issue) = self.sb_repo.get_by_id(id, plant_id, user_id).await? else {
+            return Ok(None);
+        };
+        apply_update(&mut issue, dto)?;
+        Ok(self.sb_repo.update(&issue).await?)
+    }
+
+    pub async fn add_treatment_sb(&self, id: Uuid, plant_id: Uuid, user_id: Uuid, dto: AddTreatmentDTO) -> Result<Option<PlantIssue>, String> {
+        let Some(mut issue) = self.sb_repo.get_by_id(id, plant_id, user_id).await? else {
+            return Ok(None);
+        };
+        add_treatment(&mut issue, dto);
+        self.sb_repo.update(&issue).await
+    }
+
+    pub async fn delete_sb(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.sb_repo.delete(id, plant_id, user_id).await
+    }
+
+    pub async fn affected_plants_sb(&self, user_id: Uuid, issue_type: &str) -> Result<Vec<AffectedPlant>, String> {
+        self.sb_repo.affected_plants(user_id, &normalize_issue_type(issue_type)).await
+    }
+}
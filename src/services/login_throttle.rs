@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone)]
+pub struct AttemptState {
+    pub failures: u32,
+    pub last_failed_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+// Where failed login attempts are counted (in-memory by default, Postgres when shared between instances)
+#[async_trait]
+pub trait AttemptStore: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<AttemptState>, String>;
+    // Returns the new failure count; counting restarts when the last failure is older than `window`
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, String>;
+    async fn lock_until(&self, key: &str, until: DateTime<Utc>) -> Result<(), String>;
+    async fn reset(&self, key: &str) -> Result<(), String>;
+}
+
+#[derive(Default)]
+pub struct InMemoryAttemptStore {
+    entries: Mutex<HashMap<String, AttemptState>>,
+}
+
+// Above this many tracked keys, stale entries are pruned on the next failure
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+#[async_trait]
+impl AttemptStore for InMemoryAttemptStore {
+    async fn get(&self, key: &str) -> Result<Option<AttemptState>, String> {
+        let entries = self.entries.lock().map_err(|e| e.to_string())?;
+        Ok(entries.get(key).cloned())
+    }
+
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, String> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        let now = Utc::now();
+
+        if entries.len() > MAX_TRACKED_KEYS {
+            entries.retain(|_, state| {
+                now - state.last_failed_at < window || state.locked_until.is_some_and(|until| until > now)
+            });
+        }
+
+        let state = entries.entry(key.to_string()).or_insert(AttemptState {
+            failures: 0,
+            last_failed_at: now,
+            locked_until: None,
+        });
+        if now - state.last_failed_at >= window {
+            state.failures = 0;
+        }
+        state.failures += 1;
+        state.last_failed_at = now;
+        Ok(state.failures)
+    }
+
+    async fn lock_until(&self, key: &str, until: DateTime<Utc>) -> Result<(), String> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        if let Some(state) = entries.get_mut(key) {
+            state.locked_until = Some(until);
+        }
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> Result<(), String> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        entries.remove(key);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThrottlePolicy {
+    pub free_attempts: u32,     // failures allowed before the first lockout
+    pub base_lockout: Duration, // doubled for every further failure
+    pub max_lockout: Duration,
+    pub window: Duration,       // failures older than this are forgotten
+}
+
+impl ThrottlePolicy {
+    fn lockout_for(&self, failures: u32) -> Option<Duration> {
+        if failures <= self.free_attempts {
+            return None;
+        }
+        let exponent = (failures - self.free_attempts - 1).min(16);
+        let lockout = self.base_lockout * 2i32.pow(exponent);
+        Some(lockout.min(self.max_lockout))
+    }
+}
+
+pub struct LoginThrottle {
+    pub store: Arc<dyn AttemptStore>,
+    pub email_policy: ThrottlePolicy,
+    pub ip_policy: ThrottlePolicy,
+}
+
+impl LoginThrottle {
+    pub fn new(store: Arc<dyn AttemptStore>) -> Self {
+        LoginThrottle {
+            store,
+            email_policy: ThrottlePolicy {
+                free_attempts: 5,
+                base_lockout: Duration::seconds(30),
+                max_lockout: Duration::minutes(15),
+                window: Duration::hours(1),
+            },
+            // One IP may legitimately serve several accounts (NAT, office), so it gets more slack
+            ip_policy: ThrottlePolicy {
+                free_attempts: 20,
+                base_lockout: Duration::seconds(30),
+                max_lockout: Duration::minutes(15),
+                window: Duration::hours(1),
+            },
+        }
+    }
+
+    fn keys(email: &str, ip: Option<&str>) -> Vec<String> {
+        let mut keys = vec![format!("email:{}", email)];
+        if let Some(ip) = ip {
+            keys.push(format!("ip:{}", ip));
+        }
+        keys
+    }
+
+    // Returns the number of seconds the caller has to wait, if any key is locked
+    pub async fn check(&self, email: &str, ip: Option<&str>) -> Result<Option<u64>, String> {
+        let now = Utc::now();
+        let mut retry_after: Option<u64> = None;
+
+        for key in Self::keys(email, ip) {
+            if let Some(until) = self.store.get(&key).await?.and_then(|state| state.locked_until)
+                && until > now
+            {
+                let secs = (until - now).num_seconds().max(1) as u64;
+                retry_after = Some(retry_after.map_or(secs, |current| current.max(secs)));
+            }
+        }
+
+        Ok(retry_after)
+    }
+
+    pub async fn record_failure(&self, email: &str, ip: Option<&str>) -> Result<(), String> {
+        for key in Self::keys(email, ip) {
+            let policy = if key.starts_with("ip:") { &self.ip_policy } else { &self.email_policy };
+            let failures = self.store.record_failure(&key, policy.window).await?;
+            if let Some(lockout) = policy.lockout_for(failures) {
+                self.store.lock_until(&key, Utc::now() + lockout).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // Only the account is cleared: one good login must not wipe the failures an IP piled up
+    // guessing other accounts
+    pub async fn record_success(&self, email: &str) -> Result<(), String> {
+        self.store.reset(&format!("email:{}", email)).await
+    }
+}
@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Anything that can deliver an outgoing email (SMTP, HTTP API, local dev sinks)
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Email) -> Result<(), String>;
+}
+
+// Development mailer - only writes the email to the application log
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Email) -> Result<(), String> {
+        log::info!("Email to {} | {}\n{}", email.to, email.subject, email.body);
+        Ok(())
+    }
+}
+
+// Development mailer - writes every email as a .eml file into a directory
+pub struct FileMailer {
+    pub dir: PathBuf,
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Email) -> Result<(), String> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| format!("Failed to create mail directory: {}", e))?;
+
+        let file_name = format!("{}-{}.eml", Utc::now().format("%Y%m%d%H%M%S"), Uuid::new_v4());
+        let content = format!(
+            "To: {}\r\nSubject: {}\r\nDate: {}\r\n\r\n{}\r\n",
+            email.to,
+            email.subject,
+            Utc::now().to_rfc2822(),
+            email.body
+        );
+
+        tokio::fs::write(self.dir.join(file_name), content)
+            .await
+            .map_err(|e| format!("Failed to write email file: {}", e))
+    }
+}
@@ -1,2 +1,21 @@
 pub mod plant_service;
-pub mod auth_service;
\ No newline at end of file
+pub mod auth_service;
+pub mod mailer;
+pub mod login_throttle;
+pub mod account_export;
+pub mod plant_transfer;
+pub mod care_schedule;
+pub mod ical;
+pub mod note_service;
+pub mod issue_service;
+pub mod weather;
+pub mod notifier;
+pub mod alert_service;
+pub mod sensor_service;
+pub mod mqtt_bridge;
+pub mod valve_driver;
+pub mod zone_service;
+pub mod webhook_service;
+pub mod event_bus;
+pub mod garden_service;
+pub mod share_service;
\ No newline at end of file
@@ -0,0 +1,428 @@
+use crate::config::{Backend, MqttConfig};
+use crate::dtos::sensor_dto::{IngestBatchDTO, IngestReadingDTO};
+use crate::dtos::validation::Validate;
+use crate::models::plant::Plant;
+use crate::services::care_schedule::{upcoming_tasks, CareKind};
+use crate::services::sensor_service::SensorService;
+use crate::services::weather::{plant_owners, WeatherService};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+// Incoming messages waiting for the worker; more than this and new ones are dropped
+const QUEUE_SIZE: usize = 256;
+const RECONNECT_DELAY_SECS: u64 = 5;
+// A device that reported a plant this recently is taken to control its valve
+const CONTROLLER_HORIZON_HOURS: i64 = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Moisture,
+    Temperature,
+    Light,
+}
+
+impl Metric {
+    const ALL: [Metric; 3] = [Metric::Moisture, Metric::Temperature, Metric::Light];
+
+    fn topic(&self) -> &'static str {
+        match self {
+            Metric::Moisture => "moisture",
+            Metric::Temperature => "temperature",
+            Metric::Light => "light",
+        }
+    }
+}
+
+// Payload of a reading message, e.g. {"plant_id": "...", "value": 41.5, "recorded_at": "2025-07-15T08:00:00Z"}
+#[derive(Debug, Deserialize)]
+pub struct MqttReading {
+    pub plant_id: Uuid,
+    pub value: f64,
+    pub recorded_at: Option<DateTime<Utc>>, // time of receipt when the device has no clock
+}
+
+// "<prefix>/<device_id>/<metric>" -> (device_id, metric)
+pub fn parse_topic(prefix: &str, topic: &str) -> Option<(Uuid, Metric)> {
+    let rest = topic.strip_prefix(prefix)?.strip_prefix('/')?;
+    let (device_id, metric) = rest.split_once('/')?;
+    let metric = Metric::ALL.into_iter().find(|m| m.topic() == metric)?;
+    Some((Uuid::parse_str(device_id).ok()?, metric))
+}
+
+// The same batch an HTTP ingest with a single reading would send
+fn reading_batch(metric: Metric, reading: MqttReading) -> IngestBatchDTO {
+    let value = Some(reading.value);
+    IngestBatchDTO {
+        readings: vec![IngestReadingDTO {
+            plant_id: reading.plant_id,
+            recorded_at: reading.recorded_at,
+            moisture_pct: value.filter(|_| metric == Metric::Moisture),
+            temperature_c: value.filter(|_| metric == Metric::Temperature),
+            light_lux: value.filter(|_| metric == Metric::Light),
+        }],
+    }
+}
+
+// Readings from MQTT controllers go through the same ingest as the HTTP API, and valves get a "water now"
+// command when a plant they sit next to is due. Devices are identified by the id in the topic, so the broker's
+// ACLs are what keep others from publishing for them. Uses the primary backend only.
+pub struct MqttBridge {
+    client: AsyncClient,
+    prefix: String,
+    backend: Backend,
+    sensors: Arc<SensorService>,
+    weather: Arc<WeatherService>,
+    sent: Mutex<HashSet<(Uuid, Uuid, NaiveDate)>>, // (device, plant, day) commands published by this process
+}
+
+impl MqttBridge {
+    // Connects in the background and keeps reconnecting; must be called inside the runtime
+    pub fn start(config: &MqttConfig, host: &str, backend: Backend, sensors: Arc<SensorService>, weather: Arc<WeatherService>) -> Arc<MqttBridge> {
+        let mut options = MqttOptions::new(config.client_id.clone(), host, config.port);
+        options.set_keep_alive(std::time::Duration::from_secs(30));
+        if let Some(username) = &config.username {
+            let password = config.password.as_ref().map(|p| p.expose().clone()).unwrap_or_default();
+            options.set_credentials(username.clone(), password);
+        }
+        let (client, mut eventloop) = AsyncClient::new(options, 64);
+
+        let bridge = Arc::new(MqttBridge {
+            client: client.clone(),
+            prefix: config.topic_prefix.clone(),
+            backend,
+            sensors,
+            weather,
+            sent: Mutex::new(HashSet::new()),
+        });
+
+        // The event loop only queues messages, so slow database work cannot stall keep-alives
+        let (queue, mut messages) = tokio::sync::mpsc::channel::<(String, Vec<u8>)>(QUEUE_SIZE);
+        let filters: Vec<String> = Metric::ALL.iter().map(|m| format!("{}/+/{}", config.topic_prefix, m.topic())).collect();
+        actix_web::rt::spawn(async move {
+            loop {
+                match eventloop.poll().await {
+                    // Subscriptions do not survive a reconnect with a clean session
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        log::info!("Connected to MQTT broker, subscribing to {}", filters.join(", "));
+                        let subscriptions = filters.iter().map(|f| rumqttc::SubscribeFilter::new(f.clone(), QoS::AtLeastOnce));
+                        if let Err(e) = client.try_subscribe_many(subscriptions) {
+                            log::warn!("MQTT subscribe failed: {}", e);
+                        }
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        if queue.try_send((publish.topic, publish.payload.to_vec())).is_err() {
+                            log::warn!("MQTT queue is full, dropping a message");
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        log::warn!("MQTT connection failed: {}", e);
+                        actix_web::rt::time::sleep(std::time::Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+                    }
+                }
+            }
+        });
+
+        let worker = bridge.clone();
+        actix_web::rt::spawn(async move {
+            while let Some((topic, payload)) = messages.recv().await {
+                if let Err(e) = worker.handle(&topic, &payload).await {
+                    log::warn!("Ignoring MQTT message on {}: {}", topic, e);
+                }
+            }
+        });
+
+        bridge
+    }
+
+    async fn handle(&self, topic: &str, payload: &[u8]) -> Result<(), String> {
+        let (device_id, metric) = parse_topic(&self.prefix, topic).ok_or("unexpected topic")?;
+        let reading: MqttReading = serde_json::from_slice(payload).map_err(|e| format!("invalid payload: {}", e))?;
+        let batch = reading_batch(metric, reading);
+        if let Err(errors) = batch.validate() {
+            return Err(format!("invalid reading: {}", json!(errors)));
+        }
+
+        let device = match self.backend {
+            Backend::Postgres => self.sensors.device_pg(device_id).await?,
+            Backend::Supabase => self.sensors.device_sb(device_id).await?,
+        };
+        let device = device.ok_or_else(|| format!("unknown device {}", device_id))?;
+        let report = match self.backend {
+            Backend::Postgres => self.sensors.ingest_pg(&device, batch).await?,
+            Backend::Supabase => self.sensors.ingest_sb(&device, batch).await?,
+        };
+
+        if !report.unknown_plants.is_empty() {
+            return Err(format!("plant {} does not belong to the device's owner", report.unknown_plants[0]));
+        }
+        log::debug!("MQTT reading from {}: {} stored, {} waterings, {} dry alerts",
+            device_id, report.stored, report.watering_events, report.dry_alerts);
+        Ok(())
+    }
+
+    // Publishes "water now" to the devices next to plants whose watering is due today, once per plant and day;
+    // returns how many commands went out
+    pub async fn publish_due(&self) -> Result<usize, String> {
+        let now = Utc::now();
+        let today = now.date_naive();
+        let since = now - Duration::hours(CONTROLLER_HORIZON_HOURS);
+        let device_plants = match self.backend {
+            Backend::Postgres => self.sensors.device_plants_pg(since).await?,
+            Backend::Supabase => self.sensors.device_plants_sb(since).await?,
+        };
+
+        let mut plants: Vec<Plant> = Vec::new();
+        for (_, plant) in &device_plants {
+            if !plants.iter().any(|p| p.id == plant.id) {
+                plants.push(plant.clone());
+            }
+        }
+        // Waterings the moisture sensors noticed, rain included, already moved the schedule on
+        let mut due: Vec<_> = upcoming_tasks(&plants, today, 0).into_iter()
+            .filter(|t| t.kind == CareKind::Water && t.due == today)
+            .collect();
+        // Rain forecast for today takes care of outdoor plants, as in the calendar
+        if self.weather.enabled() && !due.is_empty() {
+            let owner_ids = plant_owners(&plants);
+            let cities = match self.backend {
+                Backend::Postgres => self.sensors.owner_cities_pg(&owner_ids).await?,
+                Backend::Supabase => self.sensors.owner_cities_sb(&owner_ids).await?,
+            };
+            self.weather.mark_rain_skips_by_city(&mut due, &plants, &cities, today).await;
+            due.retain(|t| t.skip_reason.is_none());
+        }
+
+        let mut commands = Vec::new();
+        {
+            let mut sent = self.sent.lock().unwrap();
+            sent.retain(|(_, _, day)| *day == today);
+            for task in &due {
+                for (device_id, _) in device_plants.iter().filter(|(_, p)| p.id == task.plant_id) {
+                    if sent.insert((*device_id, task.plant_id, today)) {
+                        commands.push((*device_id, task));
+                    }
+                }
+            }
+        }
+
+        for (i, (device_id, task)) in commands.iter().enumerate() {
+            let payload = json!({
+                "command": "water_now",
+                "plant_id": task.plant_id,
+                "plant_name": task.plant_name,
+                "due": task.due,
+                "overdue": task.overdue,
+            });
+            let topic = format!("{}/{}/command", self.prefix, device_id);
+            if let Err(e) = self.client.publish(topic, QoS::AtLeastOnce, false, payload.to_string()).await {
+                // The rest is not marked as sent, so the next run tries again
+                let mut sent = self.sent.lock().unwrap();
+                for (device_id, task) in &commands[i..] {
+                    sent.remove(&(*device_id, task.plant_id, today));
+                }
+                return Err(format!("MQTT publish failed: {}", e));
+            }
+        }
+        Ok(commands.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WeatherConfig;
+    use crate::repositories::webhook_postgres::WebhookPostgresRepo;
+    use crate::repositories::webhook_supabase::WebhookSupabaseRepo;
+    use crate::secret::random_token;
+    use crate::services::alert_service::AlertService;
+    use crate::services::event_bus::EventBus;
+    use crate::services::notifier::Notifier;
+    use crate::services::plant_service::PlantService;
+    use crate::services::webhook_service::WebhookService;
+    use crate::test_support::{pg_pool, supabase, weather_config, webhooks_config, write_weather_fixture};
+    use deadpool_postgres::Pool;
+    use std::path::PathBuf;
+
+    const PREFIX: &str = "gardenary";
+
+    #[test]
+    fn parse_topic_reads_device_and_metric() {
+        let device_id = Uuid::new_v4();
+        for metric in Metric::ALL {
+            let topic = format!("{}/{}/{}", PREFIX, device_id, metric.topic());
+            assert_eq!(parse_topic(PREFIX, &topic), Some((device_id, metric)));
+        }
+    }
+
+    #[test]
+    fn parse_topic_rejects_other_prefixes() {
+        let device_id = Uuid::new_v4();
+        assert_eq!(parse_topic(PREFIX, &format!("other/{}/moisture", device_id)), None);
+        // The prefix has to be a whole topic level
+        assert_eq!(parse_topic(PREFIX, &format!("gardenary2/{}/moisture", device_id)), None);
+        assert_eq!(parse_topic(PREFIX, &format!("{}/moisture", device_id)), None);
+    }
+
+    #[test]
+    fn parse_topic_rejects_bad_device_ids() {
+        assert_eq!(parse_topic(PREFIX, "gardenary/not-a-uuid/moisture"), None);
+        assert_eq!(parse_topic(PREFIX, "gardenary//moisture"), None);
+    }
+
+    #[test]
+    fn parse_topic_rejects_unknown_metrics() {
+        let device_id = Uuid::new_v4();
+        assert_eq!(parse_topic(PREFIX, &format!("gardenary/{}/humidity", device_id)), None);
+        assert_eq!(parse_topic(PREFIX, &format!("gardenary/{}/command", device_id)), None);
+        assert_eq!(parse_topic(PREFIX, &format!("gardenary/{}/moisture/extra", device_id)), None);
+    }
+
+    #[test]
+    fn reading_batch_sets_only_the_topic_metric() {
+        let plant_id = Uuid::new_v4();
+        let recorded_at = Some(Utc::now());
+        let reading = || MqttReading { plant_id, value: 41.5, recorded_at };
+
+        let moisture = &reading_batch(Metric::Moisture, reading()).readings;
+        let temperature = &reading_batch(Metric::Temperature, reading()).readings;
+        let light = &reading_batch(Metric::Light, reading()).readings;
+
+        assert_eq!(moisture.len(), 1);
+        assert_eq!(moisture[0].plant_id, plant_id);
+        assert_eq!(moisture[0].recorded_at, recorded_at);
+        assert_eq!((moisture[0].moisture_pct, moisture[0].temperature_c, moisture[0].light_lux), (Some(41.5), None, None));
+        assert_eq!((temperature[0].moisture_pct, temperature[0].temperature_c, temperature[0].light_lux), (None, Some(41.5), None));
+        assert_eq!((light[0].moisture_pct, light[0].temperature_c, light[0].light_lux), (None, None, Some(41.5)));
+    }
+
+    #[test]
+    fn reading_batch_keeps_a_missing_time() {
+        let reading = MqttReading { plant_id: Uuid::new_v4(), value: 20.0, recorded_at: None };
+        assert_eq!(reading_batch(Metric::Temperature, reading).readings[0].recorded_at, None);
+    }
+
+    // Heavy rain in Berlin today, so outdoor plants there need no watering
+    fn write_fixture() -> PathBuf {
+        write_weather_fixture(&json!({
+            "Berlin": [{ "date": Utc::now().date_naive(), "precipitation_mm": 12.0, "temp_min_c": 12.0, "temp_max_c": 18.0 }]
+        }))
+    }
+
+    // The sensor service built the way main.rs builds it, without notification channels
+    fn sensor_service(pool: &Pool, weather: Arc<WeatherService>, config: &WeatherConfig) -> Arc<SensorService> {
+        let supabase = supabase();
+        let webhooks = WebhookService::new(
+            WebhookPostgresRepo { pool: pool.clone() },
+            WebhookSupabaseRepo { client: supabase.clone() },
+            &webhooks_config(),
+        ).unwrap();
+        let plants = PlantService::new(pool, &supabase, weather.clone(), Arc::new(webhooks), Arc::new(EventBus::default()));
+        let alerts = AlertService::new(pool, &supabase, weather, Arc::new(Notifier { channels: Vec::new() }), config);
+        Arc::new(SensorService::new(pool, &supabase, Arc::new(alerts), Arc::new(plants)))
+    }
+
+    // A Berlin user with an indoor and an outdoor plant and a device next to both, removed again by drop_user;
+    // returns (user_id, device_id, indoor plant_id, outdoor plant_id)
+    async fn create_garden(pool: &Pool) -> (Uuid, Uuid, Uuid, Uuid) {
+        crate::migrations::run_pending(pool).await.unwrap();
+        let client = pool.get().await.unwrap();
+        let (user_id, device_id, indoor_id, outdoor_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        client.execute(
+            "INSERT INTO users (id, name, email, password, city) VALUES ($1, 'MQTT Test', $2, 'x', 'Berlin')",
+            &[&user_id, &format!("mqtt-{}@example.com", user_id)],
+        ).await.unwrap();
+        for (plant_id, indoor) in [(indoor_id, true), (outdoor_id, false)] {
+            client.execute(
+                "INSERT INTO plants (id, name, plant_type, planted_date, age, user_id, status, indoor, created_at, updated_at)
+                 VALUES ($1, 'My Fern', 'Fern', NOW(), 0, $2, '{}', $3, NOW(), NOW())",
+                &[&plant_id, &user_id, &indoor],
+            ).await.unwrap();
+        }
+        client.execute(
+            "INSERT INTO sensor_devices (id, user_id, name, key_hash, key_prefix) VALUES ($1, $2, 'Balcony', $3, 'test')",
+            &[&device_id, &user_id, &random_token()],
+        ).await.unwrap();
+        (user_id, device_id, indoor_id, outdoor_id)
+    }
+
+    async fn drop_user(pool: &Pool, user_id: Uuid) {
+        let client = pool.get().await.unwrap();
+        client.execute("DELETE FROM users WHERE id = $1", &[&user_id]).await.unwrap();
+    }
+
+    // Plants the device has readings for
+    async fn reported_plants(pool: &Pool, device_id: Uuid) -> i64 {
+        let client = pool.get().await.unwrap();
+        client.query_one("SELECT COUNT(DISTINCT plant_id) FROM sensor_readings WHERE device_id = $1", &[&device_id])
+            .await.unwrap().get(0)
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs an MQTT broker on localhost:1883 and Postgres (PG_HOST, PG_USER, PG_PASS, PG_DB)"]
+    async fn bridge_stores_readings_and_publishes_water_now() {
+        let pool = pg_pool();
+        let fixture = write_fixture();
+        let weather_config = weather_config(&fixture);
+        let weather = Arc::new(WeatherService::from_config(&weather_config).unwrap());
+        let (user_id, device_id, indoor_id, outdoor_id) = create_garden(&pool).await;
+
+        // A topic prefix of its own, so other runs on the same broker do not interfere
+        let prefix = format!("gardenary-test-{}", Uuid::new_v4());
+        let config = MqttConfig {
+            host: Some("localhost".to_string()),
+            port: 1883,
+            client_id: format!("gardenary-bridge-{}", Uuid::new_v4()),
+            username: None,
+            password: None,
+            topic_prefix: prefix.clone(),
+            command_interval_minutes: 0,
+        };
+        let bridge = MqttBridge::start(&config, "localhost", Backend::Postgres, sensor_service(&pool, weather.clone(), &weather_config), weather);
+
+        // Plays the device: listens for commands and publishes readings
+        let (device, mut eventloop) = AsyncClient::new(MqttOptions::new(format!("gardenary-device-{}", Uuid::new_v4()), "localhost", 1883), 16);
+        device.subscribe(format!("{}/{}/command", prefix, device_id), QoS::AtLeastOnce).await.unwrap();
+        let (commands, mut received) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+        tokio::spawn(async move {
+            while let Ok(event) = eventloop.poll().await {
+                if let Event::Incoming(Packet::Publish(publish)) = event {
+                    let _ = commands.send(publish.payload.to_vec());
+                }
+            }
+        });
+
+        // The bridge subscribes once connected, so messages sent before that are lost; resend until both plants are in
+        let mut reported = 0;
+        for _ in 0..50 {
+            for plant_id in [indoor_id, outdoor_id] {
+                let payload = json!({ "plant_id": plant_id, "value": 21.5 });
+                device.publish(format!("{}/{}/temperature", prefix, device_id), QoS::AtLeastOnce, false, payload.to_string()).await.unwrap();
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            reported = reported_plants(&pool, device_id).await;
+            if reported == 2 {
+                break;
+            }
+        }
+
+        let sent = bridge.publish_due().await;
+        let again = bridge.publish_due().await;
+        let command = tokio::time::timeout(std::time::Duration::from_secs(10), received.recv()).await;
+        drop_user(&pool, user_id).await;
+        std::fs::remove_file(fixture).unwrap();
+
+        assert_eq!(reported, 2);
+        // The rain watered the outdoor plant, and a command goes out once per plant and day
+        assert_eq!(sent, Ok(1));
+        assert_eq!(again, Ok(0));
+        let command: serde_json::Value = serde_json::from_slice(&command.unwrap().unwrap()).unwrap();
+        assert_eq!(command["command"], "water_now");
+        assert_eq!(command["plant_id"], json!(indoor_id));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::dtos::note_dto::{CreateNoteDTO, JournalQuery, UpdateNoteDTO};
+use crate::dtos::plant_dto::UpdatePlantStatusDTO;
+use crate::errors::ServiceError;
+use crate::models::garden::can_edit;
+use crate::models::plant_note::{JournalEntry, PlantNote};
+use crate::repositories::garden_postgres::GardenPostgresRepo;
+use crate::repositories::garden_supabase::GardenSupabaseRepo;
+use crate::repositories::note_postgres::NotePostgresRepo;
+use crate::repositories::note_supabase::NoteSupabaseRepo;
+use crate::services::plant_service::PlantService;
+use chrono::{NaiveDate, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+// Notes are stored under the plant's owner, so garden members see and write the same notes
+pub struct NoteService {
+    pub pg_repo: NotePostgresRepo,
+    pub sb_repo: NoteSupabaseRepo,
+    pub garden_pg_repo: GardenPostgresRepo,
+    pub garden_sb_repo: GardenSupabaseRepo,
+    // Care actions are recorded like ticking the status by hand, with care events and webhooks
+    pub plants: Arc<PlantService>,
+}
+
+// Tags are trimmed, lowercased and deduplicated so filtering by tag is predictable
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+// DTO dates are validated by the handlers before they get here
+fn parse_date(date: Option<&str>) -> Option<NaiveDate> {
+    date.and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+fn forbidden() -> ServiceError {
+    ServiceError::Forbidden("Viewers cannot change notes of this plant".to_string())
+}
+
+// A note with a care action counts as doing that action on the note's date
+fn care_status(note: &PlantNote) -> Option<UpdatePlantStatusDTO> {
+    note.care_action.as_ref().map(|action| UpdatePlantStatusDTO {
+        date: note.date.format("%Y-%m-%d").to_string(),
+        status_type: action.clone(),
+        value: true,
+    })
+}
+
+impl NoteService {
+    // PostgreSQL methods
+    // The plant's owner and the user's role, when the user may see the plant
+    async fn access_pg(&self, plant_id: Uuid, user_id: Uuid) -> Result<Option<(Uuid, String)>, String> {
+        self.garden_pg_repo.plant_access(plant_id, user_id).await
+    }
+
+    // The plant's owner, when the user may change its notes
+    async fn editable_pg(&self, plant_id: Uuid, user_id: Uuid) -> Result<Option<Uuid>, ServiceError> {
+        match self.access_pg(plant_id, user_id).await? {
+            Some((_, role)) if !can_edit(&role) => Err(forbidden()),
+            access => Ok(access.map(|(owner_id, _)| owner_id)),
+        }
+    }
+
+    pub async fn create_pg(&self, plant_id: Uuid, user_id: Uuid, mut dto: CreateNoteDTO) -> Result<Option<PlantNote>, ServiceError> {
+        let Some(owner_id) = self.editable_pg(plant_id, user_id).await? else {
+            return Ok(None);
+        };
+        let date = parse_date(dto.date.as_deref()).unwrap_or_else(|| Utc::now().date_naive());
+        dto.tags = normalize_tags(dto.tags);
+
+        let note = self.pg_repo.create(plant_id, owner_id, date, dto).await?;
+        if let Some(status) = note.as_ref().and_then(care_status) {
+            self.plants.update_status_pg(plant_id, user_id, status).await?;
+        }
+        Ok(note)
+    }
+
+    pub async fn get_all_pg(&self, plant_id: Uuid, user_id: Uuid) -> Result<Vec<PlantNote>, String> {
+        match self.access_pg(plant_id, user_id).await? {
+            Some((owner_id, _)) => self.pg_repo.get_all_by_plant(plant_id, owner_id).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn get_by_id_pg(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantNote>, String> {
+        match self.access_pg(plant_id, user_id).await? {
+            Some((owner_id, _)) => self.pg_repo.get_by_id(id, plant_id, owner_id).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn update_pg(&self, id: Uuid, plant_id: Uuid, user_id: Uuid, mut dto: UpdateNoteDTO) -> Result<Option<PlantNote>, ServiceError> {
+        let Some(owner_id) = self.editable_pg(plant_id, user_id).await? else {
+            return Ok(None);
+        };
+        let date = parse_date(dto.date.as_deref());
+        let sets_care_action = dto.care_action.is_some();
+        dto.tags = dto.tags.map(normalize_tags);
+
+        let note = self.pg_repo.update(id, plant_id, owner_id, date, dto).await?;
+        if sets_care_action && let Some(status) = note.as_ref().and_then(care_status) {
+            self.plants.update_status_pg(plant_id, user_id, status).await?;
+        }
+        Ok(note)
+    }
+
+    pub async fn delete_pg(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(owner_id) = self.editable_pg(plant_id, user_id).await? else {
+            return Ok(0);
+        };
+        Ok(self.pg_repo.delete(id, plant_id, owner_id).await?)
+    }
+
+    // The user's own plants only, like the rest of the journal
+    pub async fn journal_pg(&self, user_id: Uuid, query: &JournalQuery) -> Result<Vec<JournalEntry>, String> {
+        self.pg_repo.journal(user_id, query).await
+    }
+
+    // Supabase methods
+    // The plant's owner and the user's role, when the user may see the plant
+    async fn access_sb(&self, plant_id: Uuid, user_id: Uuid) -> Result<Option<(Uuid, String)>, String> {
+        self.garden_sb_repo.plant_access(plant_id, user_id).await
+    }
+
+    // The plant's owner, when the user may change its notes
+    async fn editable_sb(&self, plant_id: Uuid, user_id: Uuid) -> Result<Option<Uuid>, ServiceError> {
+        match self.access_sb(plant_id, user_id).await? {
+            Some((_, role)) if !can_edit(&role) => Err(forbidden()),
+            access => Ok(access.map(|(owner_id, _)| owner_id)),
+        }
+    }
+
+    pub async fn create_sb(&self, plant_id: Uuid, user_id: Uuid, mut dto: CreateNoteDTO) -> Result<Option<PlantNote>, ServiceError> {
+        let Some(owner_id) = self.editable_sb(plant_id, user_id).await? else {
+            return Ok(None);
+        };
+        let date = parse_date(dto.date.as_deref()).unwrap_or_else(|| Utc::now().date_naive());
+        dto.tags = normalize_tags(dto.tags);
+
+        let note = self.sb_repo.create(plant_id, owner_id, date, dto).await?;
+        if let Some(status) = note.as_ref().and_then(care_status) {
+            self.plants.update_status_sb(plant_id, user_id, status).await?;
+        }
+        Ok(note)
+    }
+
+    pub async fn get_all_sb(&self, plant_id: Uuid, user_id: Uuid) -> Result<Vec<PlantNote>, String> {
+        match self.access_sb(plant_id, user_id).await? {
+            Some((owner_id, _)) => self.sb_repo.get_all_by_plant(plant_id, owner_id).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn get_by_id_sb(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantNote>, String> {
+        match self.access_sb(plant_id, user_id).await? {
+            Some((owner_id, _)) => self.sb_repo.get_by_id(id, plant_id, owner_id).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn update_sb(&self, id: Uuid, plant_id: Uuid, user_id: Uuid, mut dto: UpdateNoteDTO) -> Result<Option<PlantNote>, ServiceError> {
+        let Some(owner_id) = self.editable_sb(plant_id, user_id).await? else {
+            return Ok(None);
+        };
+        let date = parse_date(dto.date.as_deref());
+        let sets_care_action = dto.care_action.is_some();
+        dto.tags = dto.tags.map(normalize_tags);
+
+        let note = self.sb_repo.update(id, plant_id, owner_id, date, dto).await?;
+        if sets_care_action && let Some(status) = note.as_ref().and_then(care_status) {
+            self.plants.update_status_sb(plant_id, user_id, status).await?;
+        }
+        Ok(note)
+    }
+
+    pub async fn delete_sb(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, ServiceError> {
+        let Some(owner_id) = self.editable_sb(plant_id, user_id).await? else {
+            return Ok(0);
+        };
+        Ok(self.sb_repo.delete(id, plant_id, owner_id).await?)
+    }
+
+    // The user's own plants only, like the rest of the journal
+    pub async fn journal_sb(&self, user_id: Uuid, query: &JournalQuery) -> Result<Vec<JournalEntry>, String> {
+        self.sb_repo.journal(user_id, query).await
+    }
+}
@@ -0,0 +1,60 @@
+use crate::services::mailer::{Email, Mailer};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// Something a user should hear about even when they are not looking at the app
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub user_id: Uuid,
+    pub email: String,
+    pub event: String, // machine readable, e.g. "weather_alert"
+    pub subject: String,
+    pub body: String, // plain text for humans
+    pub data: serde_json::Value, // the same content for machines
+}
+
+// One way of reaching a user (email, webhooks, ...)
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn deliver(&self, notification: &Notification) -> Result<(), String>;
+}
+
+pub struct EmailChannel {
+    pub mailer: Arc<dyn Mailer>,
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn deliver(&self, notification: &Notification) -> Result<(), String> {
+        self.mailer.send(&Email {
+            to: notification.email.clone(),
+            subject: notification.subject.clone(),
+            body: notification.body.clone(),
+        }).await
+    }
+}
+
+pub struct Notifier {
+    pub channels: Vec<Arc<dyn NotificationChannel>>,
+}
+
+impl Notifier {
+    // Tries every channel; true when at least one of them delivered
+    pub async fn notify(&self, notification: &Notification) -> bool {
+        let mut delivered = false;
+        for channel in &self.channels {
+            match channel.deliver(notification).await {
+                Ok(()) => delivered = true,
+                Err(e) => log::warn!("Delivering {} to user {} via {} failed: {}",
+                    notification.event, notification.user_id, channel.name(), e),
+            }
+        }
+        delivered
+    }
+}
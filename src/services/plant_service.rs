@@ -1,70 +1,449 @@
-use crate::dtos::plant_dto::{CreatePlantDTO, UpdatePlantDTO, UpdatePlantStatusDTO};
+use crate::dtos::plant_dto::{CareEventQuery, CreatePlantDTO, UpdatePlantDTO, UpdatePlantStatusDTO};
+use crate::dtos::import_dto::{ImportAction, ImportOutcome, ImportReport, ImportRowError, PlantImportRow, TransferFormat};
+use crate::dtos::validation::ValidationErrors;
+use crate::errors::ServiceError;
+use crate::models::care_event::CareEvent;
+use crate::models::garden::{can_edit, OWNER};
 use crate::models::plant::Plant;
+use crate::services::plant_transfer::{self, MAX_IMPORT_ROWS};
 use crate::repositories::plant_postgres::PlantPostgresRepo;
 use crate::repositories::plant_supabase::PlantSupabaseRepo;
+use crate::repositories::care_event_postgres::CareEventPostgresRepo;
+use crate::repositories::care_event_supabase::CareEventSupabaseRepo;
+use crate::repositories::garden_postgres::GardenPostgresRepo;
+use crate::repositories::garden_supabase::GardenSupabaseRepo;
+use crate::repositories::supabase_client::SupabaseClient;
+use crate::services::event_bus::EventBus;
+use crate::services::weather::WeatherService;
+use crate::services::webhook_service::WebhookService;
+use chrono::{NaiveDate, Utc};
+use deadpool_postgres::Pool;
+use serde_json::json;
+use std::sync::Arc;
 use uuid::Uuid;
 
 pub struct PlantService {
     pub pg_repo: PlantPostgresRepo,
     pub sb_repo: PlantSupabaseRepo,
+    pub weather: Arc<WeatherService>,
+    pub care_pg_repo: CareEventPostgresRepo,
+    pub care_sb_repo: CareEventSupabaseRepo,
+    pub garden_pg_repo: GardenPostgresRepo,
+    pub garden_sb_repo: GardenSupabaseRepo,
+    pub webhooks: Arc<WebhookService>,
+    pub events: Arc<EventBus>,
+}
+
+// Ticking a status records a manual care event; unticking only changes the status
+fn manual_care_event(plant_id: Uuid, user_id: Uuid, dto: &UpdatePlantStatusDTO) -> Option<CareEvent> {
+    if !dto.value {
+        return None;
+    }
+    let date = NaiveDate::parse_from_str(&dto.date, "%Y-%m-%d").ok()?;
+    let now = Utc::now();
+    // Ticked for today: now; for another day the time of day is unknown
+    let occurred_at = if date == now.date_naive() { now } else { date.and_hms_opt(0, 0, 0)?.and_utc() };
+    Some(CareEvent {
+        id: Uuid::new_v4(),
+        plant_id,
+        user_id,
+        action: dto.status_type.clone(),
+        source: "manual".to_string(),
+        occurred_at,
+        details: json!({}),
+        created_at: now,
+    })
+}
+
+// The owner plus everyone in the plant's garden
+fn with_owner(owner_id: Uuid, mut members: Vec<Uuid>) -> Vec<Uuid> {
+    if !members.contains(&owner_id) {
+        members.insert(0, owner_id);
+    }
+    members
+}
+
+fn not_found() -> ServiceError {
+    ServiceError::Other("Plant not found".to_string())
+}
+
+// The status tick a care event stands for, so schedules and the dashboard need no special case for
+// sensors, zones or notes
+fn care_status(event: &CareEvent) -> UpdatePlantStatusDTO {
+    UpdatePlantStatusDTO {
+        date: event.occurred_at.format("%Y-%m-%d").to_string(),
+        status_type: event.action.clone(),
+        value: true,
+    }
+}
+
+// Harvests get their own event, every other care action is care.recorded
+fn care_event_name(event: &CareEvent) -> &'static str {
+    if event.action == "harvested" { "harvest.recorded" } else { "care.recorded" }
+}
+
+// Adds the day's weather and drops outdoor plants the rain already watered from need_watering
+async fn add_weather(stats: &mut serde_json::Value, weather: &WeatherService, plants: &[Plant], date: NaiveDate, city: Option<&str>) {
+    let days = weather.daily(city, date, date).await;
+    let skips = weather.rain_skips(plants, date, &days, Utc::now().date_naive());
+    if let Some(need_watering) = stats["need_watering"].as_u64() {
+        stats["need_watering"] = json!(need_watering.saturating_sub(skips.len() as u64));
+    }
+    stats["weather"] = json!(days.first());
+    stats["skip_watering"] = json!(skips);
+}
+
+// Parsed and validated import: rows ready to write, and rows that already failed
+struct PreparedImport {
+    total: usize,
+    rows: Vec<(usize, PlantImportRow)>,
+    errors: Vec<ImportRowError>,
+}
+
+fn prepare_import(user_id: Uuid, body: &[u8], format: TransferFormat) -> Result<PreparedImport, String> {
+    let parsed = match format {
+        TransferFormat::Json => plant_transfer::parse_json(body)?,
+        TransferFormat::Csv => plant_transfer::parse_csv(body)?,
+    };
+    if parsed.len() > MAX_IMPORT_ROWS {
+        return Err(format!("An import can contain at most {} plants", MAX_IMPORT_ROWS));
+    }
+    let total = parsed.len();
+    let (rows, errors) = plant_transfer::validate_rows(user_id, parsed);
+    Ok(PreparedImport { total, rows, errors })
+}
+
+// Plants the import wrote, with what was done to them
+fn written(outcomes: Vec<ImportOutcome>) -> impl Iterator<Item = (ImportAction, Plant)> {
+    outcomes.into_iter().flatten().filter_map(|(action, plant)| Some((action, plant?)))
+}
+
+fn import_event(action: ImportAction) -> &'static str {
+    match action {
+        ImportAction::Created => "plant.created",
+        ImportAction::Updated => "plant.updated",
+    }
+}
+
+fn import_report(
+    prepared: PreparedImport,
+    outcomes: &[ImportOutcome],
+    dry_run: bool,
+    committed: bool,
+) -> ImportReport {
+    let mut errors = prepared.errors;
+    let (mut created, mut updated) = (0, 0);
+    for ((row_number, row), outcome) in prepared.rows.into_iter().zip(outcomes) {
+        match outcome {
+            Ok((ImportAction::Created, _)) => created += 1,
+            Ok((ImportAction::Updated, _)) => updated += 1,
+            Err(message) => {
+                let mut row_errors = ValidationErrors::default();
+                row_errors.add("row", message.clone());
+                errors.push(ImportRowError { row: row_number, external_id: row.external_id, errors: row_errors });
+            }
+        }
+    }
+    errors.sort_by_key(|e| e.row);
+
+    ImportReport {
+        dry_run,
+        committed,
+        total: prepared.total,
+        created,
+        updated,
+        failed: errors.len(),
+        errors,
+    }
 }
 
 impl PlantService {
+    pub fn new(pg_pool: &Pool, supabase: &SupabaseClient, weather: Arc<WeatherService>, webhooks: Arc<WebhookService>, events: Arc<EventBus>) -> Self {
+        PlantService {
+            pg_repo: PlantPostgresRepo { pool: pg_pool.clone() },
+            sb_repo: PlantSupabaseRepo { client: supabase.clone() },
+            weather,
+            care_pg_repo: CareEventPostgresRepo { pool: pg_pool.clone() },
+            care_sb_repo: CareEventSupabaseRepo { client: supabase.clone() },
+            garden_pg_repo: GardenPostgresRepo { pool: pg_pool.clone() },
+            garden_sb_repo: GardenSupabaseRepo { client: supabase.clone() },
+            webhooks,
+            events,
+        }
+    }
+
     // PostgreSQL methods
     pub async fn add_pg(&self, dto: CreatePlantDTO) -> Result<Plant, String> {
-        self.pg_repo.add(dto).await
+        let plant = self.pg_repo.add(dto).await?;
+        self.emit_pg(plant.user_id, plant.id, "plant.created", json!(plant)).await;
+        Ok(plant)
     }
     
     pub async fn get_all_pg(&self, user_id: Uuid) -> Result<Vec<Plant>, String> {
         self.pg_repo.get_all_by_user(user_id).await
     }
     
+    // Own plants and plants in any garden the user belongs to
     pub async fn get_by_id_pg(&self, id: Uuid, user_id: Uuid) -> Result<Plant, String> {
-        self.pg_repo.get_by_id(id, user_id).await
+        let Some((owner_id, _)) = self.garden_pg_repo.plant_access(id, user_id).await? else {
+            return Err("Plant not found".to_string());
+        };
+        self.pg_repo.get_by_id(id, owner_id).await
     }
     
-    pub async fn update_pg(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantDTO) -> Result<Plant, String> {
-        self.pg_repo.update(id, user_id, dto).await
+    // Owners and garden editors; viewers are Forbidden
+    pub async fn update_pg(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantDTO) -> Result<Plant, ServiceError> {
+        let (owner_id, role) = self.garden_pg_repo.plant_access(id, user_id).await?.ok_or_else(not_found)?;
+        if !can_edit(&role) {
+            return Err(ServiceError::Forbidden("Viewers cannot change this plant".to_string()));
+        }
+        let plant = self.pg_repo.update(id, owner_id, dto).await?;
+        self.emit_pg(owner_id, id, "plant.updated", json!(plant)).await;
+        Ok(plant)
     }
     
-    pub async fn update_status_pg(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantStatusDTO) -> Result<Plant, String> {
-        self.pg_repo.update_status(id, user_id, dto).await
+    // The care event records who ticked the status, which for a shared plant need not be its owner
+    pub async fn update_status_pg(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantStatusDTO) -> Result<Plant, ServiceError> {
+        let (owner_id, role) = self.garden_pg_repo.plant_access(id, user_id).await?.ok_or_else(not_found)?;
+        if !can_edit(&role) {
+            return Err(ServiceError::Forbidden("Viewers cannot record care for this plant".to_string()));
+        }
+        if let Some(event) = manual_care_event(id, user_id, &dto) {
+            return Ok(self.record_care_pg(owner_id, event).await?.0);
+        }
+        let plant = self.pg_repo.update_status(id, owner_id, dto).await?;
+        // Dashboards follow every tick; webhooks only hear about the care events
+        self.events.publish(self.audience_pg(owner_id, id).await, "plant.updated", json!(plant));
+        Ok(plant)
+    }
+
+    // Every source of care goes through here (manual ticks, notes, sensors and zones): ticks the
+    // status, stores the care event and tells dashboards and webhooks. The caller checks access;
+    // owner_id is the plant's owner, event.user_id whoever did the care.
+    pub async fn record_care_pg(&self, owner_id: Uuid, event: CareEvent) -> Result<(Plant, CareEvent), String> {
+        let plant = self.pg_repo.update_status(event.plant_id, owner_id, care_status(&event)).await?;
+        let event = self.care_pg_repo.create(&event).await?;
+        self.events.publish(self.audience_pg(owner_id, plant.id).await, "plant.updated", json!(plant));
+        self.emit_pg(owner_id, plant.id, care_event_name(&event), json!(event)).await;
+        Ok((plant, event))
+    }
+
+    // Newest first, from manual status updates and sensors alike; empty for plants the user cannot see
+    pub async fn care_events_pg(&self, id: Uuid, user_id: Uuid, query: &CareEventQuery) -> Result<Vec<CareEvent>, String> {
+        if self.garden_pg_repo.plant_access(id, user_id).await?.is_none() {
+            return Ok(Vec::new());
+        }
+        self.care_pg_repo.get_all_by_plant(id, query.source.as_deref(), query.limit()).await
     }
     
-    pub async fn delete_pg(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
-        self.pg_repo.delete(id, user_id).await
+    // The plant's owner or the owner of its garden; 0 when the user cannot see the plant
+    pub async fn delete_pg(&self, id: Uuid, user_id: Uuid) -> Result<u64, ServiceError> {
+        let Some((owner_id, role)) = self.garden_pg_repo.plant_access(id, user_id).await? else {
+            return Ok(0);
+        };
+        if role != OWNER {
+            return Err(ServiceError::Forbidden("Only the plant's owner or the garden's owner can delete it".to_string()));
+        }
+        // Leaving the garden goes with the plant, so look up who hears about it first
+        let audience = self.audience_pg(owner_id, id).await;
+        let deleted = self.pg_repo.delete(id, owner_id).await?;
+        if deleted > 0 {
+            self.events.publish(audience, "plant.deleted", json!({ "id": id }));
+            self.notify_pg(owner_id, "plant.deleted", json!({ "id": id })).await;
+        }
+        Ok(deleted)
     }
     
-    pub async fn get_dashboard_stats_pg(&self, user_id: Uuid, date: &str) -> Result<serde_json::Value, String> {
-        self.pg_repo.get_dashboard_stats(user_id, date).await
+    // city is the user's, used for weather based watering advice
+    pub async fn get_dashboard_stats_pg(&self, user_id: Uuid, date: &str, city: Option<&str>) -> Result<serde_json::Value, String> {
+        let mut stats = self.pg_repo.get_dashboard_stats(user_id, date).await?;
+        if self.weather.enabled() && let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            let plants = self.pg_repo.get_all_by_user(user_id).await?;
+            add_weather(&mut stats, &self.weather, &plants, date, city).await;
+        }
+        Ok(stats)
+    }
+
+    // All or nothing: invalid rows stop the import before anything is written
+    pub async fn import_pg(&self, user_id: Uuid, body: &[u8], format: TransferFormat, dry_run: bool) -> Result<ImportReport, String> {
+        let prepared = prepare_import(user_id, body, format)?;
+        if !prepared.errors.is_empty() {
+            return Ok(import_report(prepared, &[], dry_run, false));
+        }
+
+        let rows = prepared.rows.iter().map(|(_, row)| row).collect::<Vec<_>>();
+        let outcomes = self.pg_repo.import(user_id, &rows, dry_run).await?;
+        let committed = !dry_run && outcomes.iter().all(|o| o.is_ok());
+        let report = import_report(prepared, &outcomes, dry_run, committed);
+        // Rolled back rows were never there for anyone to hear about
+        if committed {
+            for (action, plant) in written(outcomes) {
+                self.emit_pg(plant.user_id, plant.id, import_event(action), json!(plant)).await;
+            }
+        }
+        Ok(report)
+    }
+
+    // Who sees changes to the plant live; a failed lookup still reaches the owner
+    async fn audience_pg(&self, owner_id: Uuid, plant_id: Uuid) -> Vec<Uuid> {
+        match self.garden_pg_repo.plant_audience(plant_id).await {
+            Ok(members) => with_owner(owner_id, members),
+            Err(e) => {
+                log::warn!("Looking up garden members (Postgres) failed: {}", e);
+                vec![owner_id]
+            }
+        }
+    }
+
+    // Pushes the change to live dashboards and queues its webhooks
+    async fn emit_pg(&self, owner_id: Uuid, plant_id: Uuid, event: &str, data: serde_json::Value) {
+        self.events.publish(self.audience_pg(owner_id, plant_id).await, event, data.clone());
+        self.notify_pg(owner_id, event, data).await;
+    }
+
+    // Webhooks belong to the plant's owner; one that could not be queued should not fail the
+    // change it reports
+    async fn notify_pg(&self, owner_id: Uuid, event: &str, data: serde_json::Value) {
+        if let Err(e) = self.webhooks.emit_pg(owner_id, event, data).await {
+            log::warn!("Queueing {} webhooks (Postgres) failed: {}", event, e);
+        }
     }
 
     // Supabase methods
     pub async fn add_sb(&self, dto: CreatePlantDTO) -> Result<Plant, String> {
-        self.sb_repo.add(dto).await
+        let plant = self.sb_repo.add(dto).await?;
+        self.emit_sb(plant.user_id, plant.id, "plant.created", json!(plant)).await;
+        Ok(plant)
     }
     
     pub async fn get_all_sb(&self, user_id: Uuid) -> Result<Vec<Plant>, String> {
         self.sb_repo.get_all_by_user(user_id).await
     }
     
+    // Own plants and plants in any garden the user belongs to
     pub async fn get_by_id_sb(&self, id: Uuid, user_id: Uuid) -> Result<Plant, String> {
-        self.sb_repo.get_by_id(id, user_id).await
+        let Some((owner_id, _)) = self.garden_sb_repo.plant_access(id, user_id).await? else {
+            return Err("Plant not found".to_string());
+        };
+        self.sb_repo.get_by_id(id, owner_id).await
     }
     
-    pub async fn update_sb(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantDTO) -> Result<Plant, String> {
-        self.sb_repo.update(id, user_id, dto).await
+    // Owners and garden editors; viewers are Forbidden
+    pub async fn update_sb(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantDTO) -> Result<Plant, ServiceError> {
+        let (owner_id, role) = self.garden_sb_repo.plant_access(id, user_id).await?.ok_or_else(not_found)?;
+        if !can_edit(&role) {
+            return Err(ServiceError::Forbidden("Viewers cannot change this plant".to_string()));
+        }
+        let plant = self.sb_repo.update(id, owner_id, dto).await?;
+        self.emit_sb(owner_id, id, "plant.updated", json!(plant)).await;
+        Ok(plant)
     }
     
-    pub async fn update_status_sb(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantStatusDTO) -> Result<Plant, String> {
-        self.sb_repo.update_status(id, user_id, dto).await
+    // The care event records who ticked the status, which for a shared plant need not be its owner
+    pub async fn update_status_sb(&self, id: Uuid, user_id: Uuid, dto: UpdatePlantStatusDTO) -> Result<Plant, ServiceError> {
+        let (owner_id, role) = self.garden_sb_repo.plant_access(id, user_id).await?.ok_or_else(not_found)?;
+        if !can_edit(&role) {
+            return Err(ServiceError::Forbidden("Viewers cannot record care for this plant".to_string()));
+        }
+        if let Some(event) = manual_care_event(id, user_id, &dto) {
+            return Ok(self.record_care_sb(owner_id, event).await?.0);
+        }
+        let plant = self.sb_repo.update_status(id, owner_id, dto).await?;
+        // Dashboards follow every tick; webhooks only hear about the care events
+        self.events.publish(self.audience_sb(owner_id, id).await, "plant.updated", json!(plant));
+        Ok(plant)
+    }
+
+    // Every source of care goes through here (manual ticks, notes, sensors and zones): ticks the
+    // status, stores the care event and tells dashboards and webhooks. The caller checks access;
+    // owner_id is the plant's owner, event.user_id whoever did the care.
+    pub async fn record_care_sb(&self, owner_id: Uuid, event: CareEvent) -> Result<(Plant, CareEvent), String> {
+        let plant = self.sb_repo.update_status(event.plant_id, owner_id, care_status(&event)).await?;
+        let event = self.care_sb_repo.create(&event).await?;
+        self.events.publish(self.audience_sb(owner_id, plant.id).await, "plant.updated", json!(plant));
+        self.emit_sb(owner_id, plant.id, care_event_name(&event), json!(event)).await;
+        Ok((plant, event))
+    }
+
+    // Newest first, from manual status updates and sensors alike; empty for plants the user cannot see
+    pub async fn care_events_sb(&self, id: Uuid, user_id: Uuid, query: &CareEventQuery) -> Result<Vec<CareEvent>, String> {
+        if self.garden_sb_repo.plant_access(id, user_id).await?.is_none() {
+            return Ok(Vec::new());
+        }
+        self.care_sb_repo.get_all_by_plant(id, query.source.as_deref(), query.limit()).await
     }
     
-    pub async fn delete_sb(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
-        self.sb_repo.delete(id, user_id).await
+    // The plant's owner or the owner of its garden; 0 when the user cannot see the plant
+    pub async fn delete_sb(&self, id: Uuid, user_id: Uuid) -> Result<u64, ServiceError> {
+        let Some((owner_id, role)) = self.garden_sb_repo.plant_access(id, user_id).await? else {
+            return Ok(0);
+        };
+        if role != OWNER {
+            return Err(ServiceError::Forbidden("Only the plant's owner or the garden's owner can delete it".to_string()));
+        }
+        // Leaving the garden goes with the plant, so look up who hears about it first
+        let audience = self.audience_sb(owner_id, id).await;
+        let deleted = self.sb_repo.delete(id, owner_id).await?;
+        if deleted > 0 {
+            self.events.publish(audience, "plant.deleted", json!({ "id": id }));
+            self.notify_sb(owner_id, "plant.deleted", json!({ "id": id })).await;
+        }
+        Ok(deleted)
     }
     
-    pub async fn get_dashboard_stats_sb(&self, user_id: Uuid, date: &str) -> Result<serde_json::Value, String> {
-        self.sb_repo.get_dashboard_stats(user_id, date).await
+    // city is the user's, used for weather based watering advice
+    pub async fn get_dashboard_stats_sb(&self, user_id: Uuid, date: &str, city: Option<&str>) -> Result<serde_json::Value, String> {
+        let mut stats = self.sb_repo.get_dashboard_stats(user_id, date).await?;
+        if self.weather.enabled() && let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            let plants = self.sb_repo.get_all_by_user(user_id).await?;
+            add_weather(&mut stats, &self.weather, &plants, date, city).await;
+        }
+        Ok(stats)
+    }
+
+    // Invalid rows stop the import before anything is written, but a row failing while writing
+    // does not undo the rows before it (no transactions over PostgREST)
+    pub async fn import_sb(&self, user_id: Uuid, body: &[u8], format: TransferFormat, dry_run: bool) -> Result<ImportReport, String> {
+        let prepared = prepare_import(user_id, body, format)?;
+        if !prepared.errors.is_empty() {
+            return Ok(import_report(prepared, &[], dry_run, false));
+        }
+
+        let rows = prepared.rows.iter().map(|(_, row)| row).collect::<Vec<_>>();
+        let outcomes = self.sb_repo.import(user_id, &rows, dry_run).await?;
+        let committed = !dry_run && outcomes.iter().any(|o| o.is_ok());
+        let report = import_report(prepared, &outcomes, dry_run, committed);
+        // Every row is written on its own, so the ones that went through stay even when others failed
+        if !dry_run {
+            for (action, plant) in written(outcomes) {
+                self.emit_sb(plant.user_id, plant.id, import_event(action), json!(plant)).await;
+            }
+        }
+        Ok(report)
+    }
+
+    // Who sees changes to the plant live; a failed lookup still reaches the owner
+    async fn audience_sb(&self, owner_id: Uuid, plant_id: Uuid) -> Vec<Uuid> {
+        match self.garden_sb_repo.plant_audience(plant_id).await {
+            Ok(members) => with_owner(owner_id, members),
+            Err(e) => {
+                log::warn!("Looking up garden members (Supabase) failed: {}", e);
+                vec![owner_id]
+            }
+        }
+    }
+
+    // Pushes the change to live dashboards and queues its webhooks
+    async fn emit_sb(&self, owner_id: Uuid, plant_id: Uuid, event: &str, data: serde_json::Value) {
+        self.events.publish(self.audience_sb(owner_id, plant_id).await, event, data.clone());
+        self.notify_sb(owner_id, event, data).await;
+    }
+
+    // Webhooks belong to the plant's owner; one that could not be queued should not fail the
+    // change it reports
+    async fn notify_sb(&self, owner_id: Uuid, event: &str, data: serde_json::Value) {
+        if let Err(e) = self.webhooks.emit_sb(owner_id, event, data).await {
+            log::warn!("Queueing {} webhooks (Supabase) failed: {}", event, e);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,143 @@
+use crate::dtos::import_dto::{ImportRowError, PlantImportRow};
+use crate::dtos::validation::{Validate, ValidationErrors};
+use crate::models::plant::Plant;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub const MAX_IMPORT_ROWS: usize = 1000;
+
+// Flat CSV view of a plant; status is the JSON care history in a single column
+#[derive(Serialize)]
+struct CsvPlantRecord<'a> {
+    id: Uuid,
+    external_id: Option<&'a str>,
+    name: &'a str,
+    plant_type: &'a str,
+    image: Option<&'a str>,
+    planted_date: String,
+    age: i32,
+    indoor: bool,
+    status: String,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Deserialize)]
+struct CsvImportRecord {
+    external_id: Option<String>,
+    name: String,
+    plant_type: String,
+    image: Option<String>,
+    status: Option<String>,
+    #[serde(default)]
+    indoor: Option<bool>,
+}
+
+pub fn plants_to_csv(plants: &[Plant]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for plant in plants {
+        writer.serialize(CsvPlantRecord {
+            id: plant.id,
+            external_id: plant.external_id.as_deref(),
+            name: &plant.name,
+            plant_type: &plant.plant_type,
+            image: plant.image.as_deref(),
+            planted_date: plant.planted_date.to_rfc3339(),
+            age: plant.age,
+            indoor: plant.indoor,
+            status: plant.status.to_string(),
+            created_at: plant.created_at.to_rfc3339(),
+            updated_at: plant.updated_at.to_rfc3339(),
+        }).map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+fn row_error(field: &'static str, message: impl Into<String>) -> ValidationErrors {
+    let mut errors = ValidationErrors::default();
+    errors.add(field, message);
+    errors
+}
+
+// Blank cells mean "not set"
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+// A body that cannot be read at all is an Err, a broken row only fails that row
+pub fn parse_csv(body: &[u8]) -> Result<Vec<Result<PlantImportRow, ValidationErrors>>, String> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(body);
+    let headers = reader.headers().map_err(|e| format!("Invalid CSV header: {}", e))?.clone();
+    for column in ["name", "plant_type"] {
+        if !headers.iter().any(|h| h == column) {
+            return Err(format!("CSV header must contain a {} column", column));
+        }
+    }
+
+    Ok(reader.deserialize::<CsvImportRecord>().map(|record| {
+        let record = record.map_err(|e| row_error("row", e.to_string()))?;
+        let status = match non_empty(record.status) {
+            Some(text) => Some(serde_json::from_str(&text).map_err(|e| row_error("status", format!("must be JSON: {}", e)))?),
+            None => None,
+        };
+        Ok(PlantImportRow {
+            external_id: non_empty(record.external_id),
+            name: record.name,
+            plant_type: record.plant_type,
+            image: non_empty(record.image),
+            status,
+            indoor: record.indoor,
+        })
+    }).collect())
+}
+
+pub fn parse_json(body: &[u8]) -> Result<Vec<Result<PlantImportRow, ValidationErrors>>, String> {
+    let values: Vec<serde_json::Value> = serde_json::from_slice(body)
+        .map_err(|e| format!("Body must be a JSON array of plants: {}", e))?;
+
+    Ok(values.into_iter().map(|value| {
+        let mut row: PlantImportRow = serde_json::from_value(value).map_err(|e| row_error("row", e.to_string()))?;
+        row.external_id = non_empty(row.external_id);
+        Ok(row)
+    }).collect())
+}
+
+// Splits parsed rows into valid ones (with their row number, counted from 1) and per-row errors
+pub fn validate_rows(
+    user_id: Uuid,
+    parsed: Vec<Result<PlantImportRow, ValidationErrors>>,
+) -> (Vec<(usize, PlantImportRow)>, Vec<ImportRowError>) {
+    let mut valid = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen_external_ids = HashSet::new();
+
+    for (index, row) in parsed.into_iter().enumerate() {
+        let row_number = index + 1;
+        let row = match row {
+            Ok(row) => row,
+            Err(row_errors) => {
+                errors.push(ImportRowError { row: row_number, external_id: None, errors: row_errors });
+                continue;
+            }
+        };
+
+        let mut row_errors = match row.to_create_dto(user_id).validate() {
+            Ok(()) => ValidationErrors::default(),
+            Err(e) => e,
+        };
+        if let Some(status) = &row.status && !status.is_object() {
+            row_errors.add("status", "must be an object keyed by date");
+        }
+        if let Some(external_id) = &row.external_id && !seen_external_ids.insert(external_id.clone()) {
+            row_errors.add("external_id", "appears more than once in this file");
+        }
+
+        if row_errors.is_empty() {
+            valid.push((row_number, row));
+        } else {
+            errors.push(ImportRowError { row: row_number, external_id: row.external_id, errors: row_errors });
+        }
+    }
+    (valid, errors)
+}
@@ -0,0 +1,435 @@
+use crate::dtos::sensor_dto::{CreateDeviceDTO, IngestBatchDTO, ReadingsQuery, UpdateThresholdsDTO};
+use crate::models::care_event::CareEvent;
+use crate::models::plant::Plant;
+use crate::models::plant_alert::PlantAlert;
+use crate::models::sensor::{IngestReport, MetricSummary, PlantThresholds, ReadingBucket, RegisteredDevice, SensorDevice, SensorReading};
+use crate::repositories::care_event_postgres::CareEventPostgresRepo;
+use crate::repositories::care_event_supabase::CareEventSupabaseRepo;
+use crate::repositories::plant_postgres::PlantPostgresRepo;
+use crate::repositories::plant_supabase::PlantSupabaseRepo;
+use crate::repositories::sensor_postgres::SensorPostgresRepo;
+use crate::repositories::sensor_supabase::SensorSupabaseRepo;
+use crate::repositories::supabase_client::SupabaseClient;
+use crate::secret::random_token;
+use crate::services::alert_service::{new_alert, AlertService};
+use crate::services::plant_service::PlantService;
+use chrono::{DateTime, Duration, Utc};
+use deadpool_postgres::Pool;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct SensorService {
+    pub pg_repo: SensorPostgresRepo,
+    pub sb_repo: SensorSupabaseRepo,
+    pub care_pg_repo: CareEventPostgresRepo,
+    pub care_sb_repo: CareEventSupabaseRepo,
+    pub plant_pg_repo: PlantPostgresRepo,
+    pub plant_sb_repo: PlantSupabaseRepo,
+    pub alerts: Arc<AlertService>,
+    // Records detected waterings, so they reach dashboards and webhooks like manual care
+    pub plants: Arc<PlantService>,
+}
+
+// A rise of this many percentage points within the watering window counts as watering, unless the plant sets its own
+pub const DEFAULT_WATERING_RISE_PCT: f64 = 15.0;
+const WATERING_WINDOW_MINUTES: i64 = 120;
+
+const API_KEY_PREFIX: &str = "gds_";
+
+// Random enough that a plain SHA-256 is a safe way to store it
+fn new_api_key() -> String {
+    format!("{}{}", API_KEY_PREFIX, random_token())
+}
+
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.trim().as_bytes()))
+}
+
+fn new_device(user_id: Uuid, dto: CreateDeviceDTO, api_key: &str) -> SensorDevice {
+    SensorDevice {
+        id: Uuid::new_v4(),
+        user_id,
+        name: dto.name.trim().to_string(),
+        key_prefix: api_key.chars().take(API_KEY_PREFIX.len() + 6).collect(),
+        last_seen_at: None,
+        created_at: Utc::now(),
+    }
+}
+
+// Readings for plants the device's user owns; the rest are reported back as unknown
+fn accepted_readings(device: &SensorDevice, batch: IngestBatchDTO, owned: &[Uuid]) -> (Vec<SensorReading>, IngestReport) {
+    let now = Utc::now();
+    let mut report = IngestReport { received: batch.readings.len(), ..Default::default() };
+    let mut readings = Vec::with_capacity(batch.readings.len());
+    let mut seen = HashSet::new();
+
+    for reading in batch.readings {
+        if !owned.contains(&reading.plant_id) {
+            if !report.unknown_plants.contains(&reading.plant_id) {
+                report.unknown_plants.push(reading.plant_id);
+            }
+            continue;
+        }
+        let recorded_at = reading.recorded_at.unwrap_or(now);
+        // The same reading twice in one batch counts as a duplicate too
+        if !seen.insert((reading.plant_id, recorded_at)) {
+            report.duplicates += 1;
+            continue;
+        }
+        readings.push(SensorReading {
+            device_id: device.id,
+            plant_id: reading.plant_id,
+            recorded_at,
+            moisture_pct: reading.moisture_pct,
+            temperature_c: reading.temperature_c,
+            light_lux: reading.light_lux,
+        });
+    }
+    (readings, report)
+}
+
+fn plant_ids(batch: &IngestBatchDTO) -> Vec<Uuid> {
+    let mut ids: Vec<Uuid> = batch.readings.iter().map(|r| r.plant_id).collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+fn summarize(values: impl Iterator<Item = Option<f64>>) -> MetricSummary {
+    let values: Vec<f64> = values.flatten().collect();
+    if values.is_empty() {
+        return MetricSummary::default();
+    }
+    MetricSummary {
+        min: values.iter().copied().reduce(f64::min),
+        avg: Some(values.iter().sum::<f64>() / values.len() as f64),
+        max: values.iter().copied().reduce(f64::max),
+    }
+}
+
+// Same buckets as the Postgres query: aligned to multiples of bucket_secs since the Unix epoch, empty ones left out
+pub fn downsample(readings: &[SensorReading], bucket_secs: i64) -> Vec<ReadingBucket> {
+    let mut buckets: BTreeMap<i64, Vec<&SensorReading>> = BTreeMap::new();
+    for reading in readings {
+        let start = reading.recorded_at.timestamp().div_euclid(bucket_secs) * bucket_secs;
+        buckets.entry(start).or_default().push(reading);
+    }
+    buckets.into_iter()
+        .filter_map(|(start, readings)| Some(ReadingBucket {
+            start: DateTime::from_timestamp(start, 0)?,
+            count: readings.len() as i64,
+            moisture_pct: summarize(readings.iter().map(|r| r.moisture_pct)),
+            temperature_c: summarize(readings.iter().map(|r| r.temperature_c)),
+            light_lux: summarize(readings.iter().map(|r| r.light_lux)),
+        }))
+        .collect()
+}
+
+// A detected watering: when the rise was seen and the moisture before and after
+struct Watering {
+    at: DateTime<Utc>,
+    before: f64,
+    after: f64,
+}
+
+// Moisture at least `rise` above the lowest reading of the window before it; readings up to a detected
+// watering are not compared again, so one watering spread over several readings is found once
+fn detect_waterings(series: &[(DateTime<Utc>, f64)], rise: f64) -> Vec<Watering> {
+    let window = Duration::minutes(WATERING_WINDOW_MINUTES);
+    let mut waterings = Vec::new();
+    let mut start = 0;
+    for (i, &(at, value)) in series.iter().enumerate() {
+        let lowest = series[start..i].iter()
+            .filter(|(t, _)| at - *t <= window)
+            .map(|(_, v)| *v)
+            .reduce(f64::min);
+        if let Some(lowest) = lowest && value - lowest >= rise {
+            waterings.push(Watering { at, before: lowest, after: value });
+            start = i;
+        }
+    }
+    waterings
+}
+
+fn sensor_watering(plant_id: Uuid, user_id: Uuid, watering: &Watering) -> CareEvent {
+    CareEvent {
+        id: Uuid::new_v4(),
+        plant_id,
+        user_id,
+        action: "watered".to_string(),
+        source: "sensor".to_string(),
+        occurred_at: watering.at,
+        details: json!({ "moisture_before_pct": watering.before, "moisture_after_pct": watering.after }),
+        created_at: Utc::now(),
+    }
+}
+
+// First and last moisture reading time per plant in a stored batch
+fn moisture_spans(readings: &[SensorReading]) -> BTreeMap<Uuid, (DateTime<Utc>, DateTime<Utc>)> {
+    let mut spans: BTreeMap<Uuid, (DateTime<Utc>, DateTime<Utc>)> = BTreeMap::new();
+    for reading in readings.iter().filter(|r| r.moisture_pct.is_some()) {
+        let span = spans.entry(reading.plant_id).or_insert((reading.recorded_at, reading.recorded_at));
+        span.0 = span.0.min(reading.recorded_at);
+        span.1 = span.1.max(reading.recorded_at);
+    }
+    spans
+}
+
+fn new_thresholds(plant_id: Uuid, user_id: Uuid, moisture_min_pct: Option<f64>, watering_rise_pct: Option<f64>) -> PlantThresholds {
+    PlantThresholds {
+        plant_id,
+        user_id,
+        moisture_min_pct,
+        watering_rise_pct,
+        updated_at: Utc::now(),
+    }
+}
+
+impl SensorService {
+    pub fn new(pg_pool: &Pool, supabase: &SupabaseClient, alerts: Arc<AlertService>, plants: Arc<PlantService>) -> Self {
+        SensorService {
+            pg_repo: SensorPostgresRepo { pool: pg_pool.clone() },
+            sb_repo: SensorSupabaseRepo { client: supabase.clone() },
+            care_pg_repo: CareEventPostgresRepo { pool: pg_pool.clone() },
+            care_sb_repo: CareEventSupabaseRepo { client: supabase.clone() },
+            plant_pg_repo: PlantPostgresRepo { pool: pg_pool.clone() },
+            plant_sb_repo: PlantSupabaseRepo { client: supabase.clone() },
+            alerts,
+            plants,
+        }
+    }
+
+    // PostgreSQL methods
+    pub async fn register_device_pg(&self, user_id: Uuid, dto: CreateDeviceDTO) -> Result<RegisteredDevice, String> {
+        let api_key = new_api_key();
+        let device = self.pg_repo.create_device(&new_device(user_id, dto, &api_key), &hash_api_key(&api_key)).await?;
+        Ok(RegisteredDevice { device, api_key })
+    }
+
+    pub async fn get_devices_pg(&self, user_id: Uuid) -> Result<Vec<SensorDevice>, String> {
+        self.pg_repo.get_devices(user_id).await
+    }
+
+    pub async fn delete_device_pg(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.pg_repo.delete_device(id, user_id).await
+    }
+
+    pub async fn authenticate_pg(&self, api_key: &str) -> Result<Option<SensorDevice>, String> {
+        self.pg_repo.device_by_key_hash(&hash_api_key(api_key)).await
+    }
+
+    pub async fn device_pg(&self, id: Uuid) -> Result<Option<SensorDevice>, String> {
+        self.pg_repo.device_by_id(id).await
+    }
+
+    // Plants a device reported readings for since `since`, i.e. the plants it sits next to
+    pub async fn device_plants_pg(&self, since: DateTime<Utc>) -> Result<Vec<(Uuid, Plant)>, String> {
+        let pairs = self.pg_repo.device_plants(since).await?;
+        let mut plant_ids: Vec<Uuid> = pairs.iter().map(|(_, plant_id)| *plant_id).collect();
+        plant_ids.sort();
+        plant_ids.dedup();
+        let plants = self.plant_pg_repo.get_by_ids(&plant_ids).await?;
+        Ok(pairs.into_iter()
+            .filter_map(|(device_id, plant_id)| Some((device_id, plants.iter().find(|p| p.id == plant_id)?.clone())))
+            .collect())
+    }
+
+    pub async fn owner_cities_pg(&self, user_ids: &[Uuid]) -> Result<Vec<(Uuid, Option<String>)>, String> {
+        self.plant_pg_repo.owner_cities(user_ids).await
+    }
+
+    pub async fn ingest_pg(&self, device: &SensorDevice, batch: IngestBatchDTO) -> Result<IngestReport, String> {
+        let owned = self.pg_repo.owned_plants(device.user_id, &plant_ids(&batch)).await?;
+        let (readings, mut report) = accepted_readings(device, batch, &owned);
+        if !readings.is_empty() {
+            report.stored = self.pg_repo.insert_readings(&readings).await?;
+        }
+        report.duplicates += readings.len() - report.stored;
+        if report.stored > 0 {
+            (report.watering_events, report.dry_alerts) = self.evaluate_pg(device.user_id, &readings).await?;
+        }
+        Ok(report)
+    }
+
+    // Records waterings and raises dry alerts for the plants in a stored batch; returns (waterings, alerts)
+    async fn evaluate_pg(&self, user_id: Uuid, readings: &[SensorReading]) -> Result<(usize, usize), String> {
+        let window = Duration::minutes(WATERING_WINDOW_MINUTES);
+        let spans = moisture_spans(readings);
+        let plant_ids: Vec<Uuid> = spans.keys().copied().collect();
+        let thresholds = self.pg_repo.thresholds(&plant_ids).await?;
+
+        let mut waterings = 0;
+        let mut dry_alerts: Vec<PlantAlert> = Vec::new();
+        for (plant_id, (first, last)) in spans {
+            let limits = thresholds.iter().find(|t| t.plant_id == plant_id);
+            let rise = limits.and_then(|t| t.watering_rise_pct).unwrap_or(DEFAULT_WATERING_RISE_PCT);
+            let series = self.pg_repo.moisture_series(plant_id, first - window, last).await?;
+
+            // Resent or overlapping batches must not record the same watering twice
+            let known = self.care_pg_repo.get_in_range(plant_id, "watered", "sensor", first - window, last + window).await?;
+            for watering in detect_waterings(&series, rise) {
+                if known.iter().any(|e| (e.occurred_at - watering.at).abs() < window) {
+                    continue;
+                }
+                self.plants.record_care_pg(user_id, sensor_watering(plant_id, user_id, &watering)).await?;
+                waterings += 1;
+            }
+
+            if let Some(min) = limits.and_then(|t| t.moisture_min_pct)
+                && let Some(&(at, moisture)) = series.last()
+                && moisture < min
+            {
+                let plant = self.plant_pg_repo.get_by_id(plant_id, user_id).await?;
+                dry_alerts.push(new_alert(user_id, plant_id, &plant.name, "dry", at.date_naive(), moisture, min));
+            }
+        }
+
+        let alerts = if dry_alerts.is_empty() { 0 } else { self.alerts.raise_pg(&dry_alerts).await? };
+        Ok((waterings, alerts))
+    }
+
+    // Stored thresholds, or the defaults; None when the plant does not exist or belongs to someone else
+    pub async fn thresholds_pg(&self, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantThresholds>, String> {
+        if self.pg_repo.owned_plants(user_id, &[plant_id]).await?.is_empty() {
+            return Ok(None);
+        }
+        let stored = self.pg_repo.thresholds(&[plant_id]).await?.pop();
+        Ok(Some(stored.unwrap_or_else(|| new_thresholds(plant_id, user_id, None, None))))
+    }
+
+    pub async fn set_thresholds_pg(&self, plant_id: Uuid, user_id: Uuid, dto: UpdateThresholdsDTO) -> Result<Option<PlantThresholds>, String> {
+        if self.pg_repo.owned_plants(user_id, &[plant_id]).await?.is_empty() {
+            return Ok(None);
+        }
+        let thresholds = new_thresholds(plant_id, user_id, dto.moisture_min_pct, dto.watering_rise_pct);
+        self.pg_repo.set_thresholds(&thresholds).await.map(Some)
+    }
+
+    // None when the plant does not exist or belongs to someone else
+    pub async fn readings_pg(&self, plant_id: Uuid, user_id: Uuid, query: &ReadingsQuery) -> Result<Option<Vec<ReadingBucket>>, String> {
+        if self.pg_repo.owned_plants(user_id, &[plant_id]).await?.is_empty() {
+            return Ok(None);
+        }
+        let (from, to) = query.range();
+        self.pg_repo.readings(plant_id, from, to, query.bucket_secs()).await.map(Some)
+    }
+
+    // Supabase methods
+    pub async fn register_device_sb(&self, user_id: Uuid, dto: CreateDeviceDTO) -> Result<RegisteredDevice, String> {
+        let api_key = new_api_key();
+        let device = self.sb_repo.create_device(&new_device(user_id, dto, &api_key), &hash_api_key(&api_key)).await?;
+        Ok(RegisteredDevice { device, api_key })
+    }
+
+    pub async fn get_devices_sb(&self, user_id: Uuid) -> Result<Vec<SensorDevice>, String> {
+        self.sb_repo.get_devices(user_id).await
+    }
+
+    pub async fn delete_device_sb(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.sb_repo.delete_device(id, user_id).await
+    }
+
+    pub async fn authenticate_sb(&self, api_key: &str) -> Result<Option<SensorDevice>, String> {
+        self.sb_repo.device_by_key_hash(&hash_api_key(api_key)).await
+    }
+
+    pub async fn device_sb(&self, id: Uuid) -> Result<Option<SensorDevice>, String> {
+        self.sb_repo.device_by_id(id).await
+    }
+
+    // Plants a device reported readings for since `since`, i.e. the plants it sits next to
+    pub async fn device_plants_sb(&self, since: DateTime<Utc>) -> Result<Vec<(Uuid, Plant)>, String> {
+        let pairs = self.sb_repo.device_plants(since).await?;
+        let mut plant_ids: Vec<Uuid> = pairs.iter().map(|(_, plant_id)| *plant_id).collect();
+        plant_ids.sort();
+        plant_ids.dedup();
+        let plants = self.plant_sb_repo.get_by_ids(&plant_ids).await?;
+        Ok(pairs.into_iter()
+            .filter_map(|(device_id, plant_id)| Some((device_id, plants.iter().find(|p| p.id == plant_id)?.clone())))
+            .collect())
+    }
+
+    pub async fn owner_cities_sb(&self, user_ids: &[Uuid]) -> Result<Vec<(Uuid, Option<String>)>, String> {
+        self.plant_sb_repo.owner_cities(user_ids).await
+    }
+
+    pub async fn ingest_sb(&self, device: &SensorDevice, batch: IngestBatchDTO) -> Result<IngestReport, String> {
+        let owned = self.sb_repo.owned_plants(device.user_id, &plant_ids(&batch)).await?;
+        let (readings, mut report) = accepted_readings(device, batch, &owned);
+        if !readings.is_empty() {
+            report.stored = self.sb_repo.insert_readings(&readings).await?;
+        }
+        report.duplicates += readings.len() - report.stored;
+        if report.stored > 0 {
+            (report.watering_events, report.dry_alerts) = self.evaluate_sb(device.user_id, &readings).await?;
+        }
+        Ok(report)
+    }
+
+    // Records waterings and raises dry alerts for the plants in a stored batch; returns (waterings, alerts)
+    async fn evaluate_sb(&self, user_id: Uuid, readings: &[SensorReading]) -> Result<(usize, usize), String> {
+        let window = Duration::minutes(WATERING_WINDOW_MINUTES);
+        let spans = moisture_spans(readings);
+        let plant_ids: Vec<Uuid> = spans.keys().copied().collect();
+        let thresholds = self.sb_repo.thresholds(&plant_ids).await?;
+
+        let mut waterings = 0;
+        let mut dry_alerts: Vec<PlantAlert> = Vec::new();
+        for (plant_id, (first, last)) in spans {
+            let limits = thresholds.iter().find(|t| t.plant_id == plant_id);
+            let rise = limits.and_then(|t| t.watering_rise_pct).unwrap_or(DEFAULT_WATERING_RISE_PCT);
+            let series = self.sb_repo.moisture_series(plant_id, first - window, last).await?;
+
+            // Resent or overlapping batches must not record the same watering twice
+            let known = self.care_sb_repo.get_in_range(plant_id, "watered", "sensor", first - window, last + window).await?;
+            for watering in detect_waterings(&series, rise) {
+                if known.iter().any(|e| (e.occurred_at - watering.at).abs() < window) {
+                    continue;
+                }
+                self.plants.record_care_sb(user_id, sensor_watering(plant_id, user_id, &watering)).await?;
+                waterings += 1;
+            }
+
+            if let Some(min) = limits.and_then(|t| t.moisture_min_pct)
+                && let Some(&(at, moisture)) = series.last()
+                && moisture < min
+            {
+                let plant = self.plant_sb_repo.get_by_id(plant_id, user_id).await?;
+                dry_alerts.push(new_alert(user_id, plant_id, &plant.name, "dry", at.date_naive(), moisture, min));
+            }
+        }
+
+        let alerts = if dry_alerts.is_empty() { 0 } else { self.alerts.raise_sb(&dry_alerts).await? };
+        Ok((waterings, alerts))
+    }
+
+    // Stored thresholds, or the defaults; None when the plant does not exist or belongs to someone else
+    pub async fn thresholds_sb(&self, plant_id: Uuid, user_id: Uuid) -> Result<Option<PlantThresholds>, String> {
+        if self.sb_repo.owned_plants(user_id, &[plant_id]).await?.is_empty() {
+            return Ok(None);
+        }
+        let stored = self.sb_repo.thresholds(&[plant_id]).await?.pop();
+        Ok(Some(stored.unwrap_or_else(|| new_thresholds(plant_id, user_id, None, None))))
+    }
+
+    pub async fn set_thresholds_sb(&self, plant_id: Uuid, user_id: Uuid, dto: UpdateThresholdsDTO) -> Result<Option<PlantThresholds>, String> {
+        if self.sb_repo.owned_plants(user_id, &[plant_id]).await?.is_empty() {
+            return Ok(None);
+        }
+        let thresholds = new_thresholds(plant_id, user_id, dto.moisture_min_pct, dto.watering_rise_pct);
+        self.sb_repo.set_thresholds(&thresholds).await.map(Some)
+    }
+
+    // None when the plant does not exist or belongs to someone else
+    pub async fn readings_sb(&self, plant_id: Uuid, user_id: Uuid, query: &ReadingsQuery) -> Result<Option<Vec<ReadingBucket>>, String> {
+        if self.sb_repo.owned_plants(user_id, &[plant_id]).await?.is_empty() {
+            return Ok(None);
+        }
+        let (from, to) = query.range();
+        let readings = self.sb_repo.readings(plant_id, from, to).await?;
+        Ok(Some(downsample(&readings, query.bucket_secs())))
+    }
+}
@@ -0,0 +1,229 @@
+use crate::dtos::share_dto::ShareQuery;
+use crate::models::care_event::CareEvent;
+use crate::models::plant::Plant;
+use crate::models::plant_share::{CareSummary, GardenShare, GardenShareLink, PlantShare, ShareLink, SharedCareEvent, SharedGarden, SharedPlant};
+use crate::repositories::care_event_postgres::CareEventPostgresRepo;
+use crate::repositories::care_event_supabase::CareEventSupabaseRepo;
+use crate::repositories::garden_postgres::GardenPostgresRepo;
+use crate::repositories::garden_supabase::GardenSupabaseRepo;
+use crate::repositories::plant_postgres::PlantPostgresRepo;
+use crate::repositories::plant_supabase::PlantSupabaseRepo;
+use crate::repositories::share_postgres::SharePostgresRepo;
+use crate::repositories::share_supabase::ShareSupabaseRepo;
+use crate::secret::random_token;
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+// The care summary covers this many days
+const SUMMARY_DAYS: i64 = 30;
+// Care events looked at for the summary, plenty for daily care over SUMMARY_DAYS
+const SUMMARY_EVENT_LIMIT: i64 = 200;
+const RECENT_CARE_EVENTS: usize = 10;
+
+// Read-only public links to a plant or a whole garden, e.g. for a plant sitter
+pub struct ShareService {
+    pub pg_repo: SharePostgresRepo,
+    pub sb_repo: ShareSupabaseRepo,
+    pub plant_pg_repo: PlantPostgresRepo,
+    pub plant_sb_repo: PlantSupabaseRepo,
+    pub care_pg_repo: CareEventPostgresRepo,
+    pub care_sb_repo: CareEventSupabaseRepo,
+    pub garden_pg_repo: GardenPostgresRepo,
+    pub garden_sb_repo: GardenSupabaseRepo,
+    pub app_base_url: String,
+}
+
+fn new_share(plant_id: Uuid, user_id: Uuid, query: &ShareQuery) -> PlantShare {
+    let now = Utc::now();
+    PlantShare {
+        id: Uuid::new_v4(),
+        plant_id,
+        user_id,
+        token: random_token(),
+        expires_at: query.expires_in_days.map(|days| now + Duration::days(days)),
+        created_at: now,
+    }
+}
+
+fn new_garden_share(garden_id: Uuid, user_id: Uuid, query: &ShareQuery) -> GardenShare {
+    let now = Utc::now();
+    GardenShare {
+        id: Uuid::new_v4(),
+        garden_id,
+        user_id,
+        token: random_token(),
+        expires_at: query.expires_in_days.map(|days| now + Duration::days(days)),
+        created_at: now,
+    }
+}
+
+fn care_summary(events: &[CareEvent]) -> CareSummary {
+    let since = Utc::now() - Duration::days(SUMMARY_DAYS);
+    // Newest first, as the repo returns them
+    let recent = events.iter().filter(|e| e.occurred_at >= since).collect::<Vec<_>>();
+    let count = |action: &str| recent.iter().filter(|e| e.action == action).count();
+    let last = |action: &str| events.iter().find(|e| e.action == action).map(|e| e.occurred_at);
+
+    CareSummary {
+        since: since.date_naive(),
+        watered: count("watered"),
+        fertilized: count("fertilized"),
+        harvested: count("harvested"),
+        last_watered: last("watered"),
+        last_fertilized: last("fertilized"),
+        last_harvested: last("harvested"),
+        recent: recent.iter().take(RECENT_CARE_EVENTS).map(|e| SharedCareEvent {
+            action: e.action.clone(),
+            occurred_at: e.occurred_at,
+        }).collect(),
+    }
+}
+
+// Journal photos stay private, they can show the inside of a home or a face; only the plant's own image is shared
+fn shared_plant(plant: Plant, events: &[CareEvent], shared_until: Option<DateTime<Utc>>) -> SharedPlant {
+    SharedPlant {
+        name: plant.name,
+        plant_type: plant.plant_type,
+        age: plant.age,
+        planted_date: plant.planted_date.date_naive(),
+        indoor: plant.indoor,
+        image: plant.image.filter(|image| !image.is_empty()),
+        care: care_summary(events),
+        shared_until,
+    }
+}
+
+impl ShareService {
+    // The public view lives under the same backend prefix as the plant, e.g. "/pg"
+    fn link(&self, backend: &str, share: PlantShare) -> ShareLink {
+        let url = format!("{}/{}/shared/{}", self.app_base_url.trim_end_matches('/'), backend, share.token);
+        ShareLink { share, url }
+    }
+
+    fn garden_link(&self, backend: &str, share: GardenShare) -> GardenShareLink {
+        let url = format!("{}/{}/shared/gardens/{}", self.app_base_url.trim_end_matches('/'), backend, share.token);
+        GardenShareLink { share, url }
+    }
+
+    // PostgreSQL methods
+    // None when the plant does not exist or is not the user's own
+    pub async fn create_pg(&self, plant_id: Uuid, user_id: Uuid, query: &ShareQuery) -> Result<Option<ShareLink>, String> {
+        let share = self.pg_repo.create(&new_share(plant_id, user_id, query)).await?;
+        Ok(share.map(|share| self.link("pg", share)))
+    }
+
+    pub async fn get_all_pg(&self, plant_id: Uuid, user_id: Uuid) -> Result<Vec<ShareLink>, String> {
+        let shares = self.pg_repo.get_all_by_plant(plant_id, user_id).await?;
+        Ok(shares.into_iter().map(|share| self.link("pg", share)).collect())
+    }
+
+    pub async fn revoke_pg(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.pg_repo.delete(id, plant_id, user_id).await
+    }
+
+    // None for unknown, revoked and expired tokens
+    pub async fn shared_plant_pg(&self, token: &str) -> Result<Option<SharedPlant>, String> {
+        let Some(share) = self.pg_repo.get_valid(token).await? else {
+            return Ok(None);
+        };
+        let plant = self.plant_pg_repo.get_by_id(share.plant_id, share.user_id).await?;
+        let events = self.care_pg_repo.get_all_by_plant(share.plant_id, None, SUMMARY_EVENT_LIMIT).await?;
+        Ok(Some(shared_plant(plant, &events, share.expires_at)))
+    }
+
+    // None when the garden does not exist or the user is not its owner
+    pub async fn create_garden_pg(&self, garden_id: Uuid, user_id: Uuid, query: &ShareQuery) -> Result<Option<GardenShareLink>, String> {
+        let share = self.pg_repo.create_garden(&new_garden_share(garden_id, user_id, query)).await?;
+        Ok(share.map(|share| self.garden_link("pg", share)))
+    }
+
+    pub async fn get_all_garden_pg(&self, garden_id: Uuid, user_id: Uuid) -> Result<Vec<GardenShareLink>, String> {
+        let shares = self.pg_repo.get_all_by_garden(garden_id, user_id).await?;
+        Ok(shares.into_iter().map(|share| self.garden_link("pg", share)).collect())
+    }
+
+    pub async fn revoke_garden_pg(&self, id: Uuid, garden_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.pg_repo.delete_garden(id, garden_id, user_id).await
+    }
+
+    // The garden's plants as they are when the link is opened, so plants added later show up too
+    pub async fn shared_garden_pg(&self, token: &str) -> Result<Option<SharedGarden>, String> {
+        let Some(share) = self.pg_repo.get_valid_garden(token).await? else {
+            return Ok(None);
+        };
+        let Some(garden) = self.garden_pg_repo.get_by_id(share.garden_id).await? else {
+            return Ok(None);
+        };
+        let plant_ids = self.garden_pg_repo.plant_ids(garden.id).await?;
+        let mut plants = self.plant_pg_repo.get_by_ids(&plant_ids).await?;
+        plants.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut shared = Vec::with_capacity(plants.len());
+        for plant in plants {
+            let events = self.care_pg_repo.get_all_by_plant(plant.id, None, SUMMARY_EVENT_LIMIT).await?;
+            shared.push(shared_plant(plant, &events, share.expires_at));
+        }
+        Ok(Some(SharedGarden { name: garden.name, plants: shared, shared_until: share.expires_at }))
+    }
+
+    // Supabase methods
+    // None when the plant does not exist or is not the user's own
+    pub async fn create_sb(&self, plant_id: Uuid, user_id: Uuid, query: &ShareQuery) -> Result<Option<ShareLink>, String> {
+        let share = self.sb_repo.create(&new_share(plant_id, user_id, query)).await?;
+        Ok(share.map(|share| self.link("sb", share)))
+    }
+
+    pub async fn get_all_sb(&self, plant_id: Uuid, user_id: Uuid) -> Result<Vec<ShareLink>, String> {
+        let shares = self.sb_repo.get_all_by_plant(plant_id, user_id).await?;
+        Ok(shares.into_iter().map(|share| self.link("sb", share)).collect())
+    }
+
+    pub async fn revoke_sb(&self, id: Uuid, plant_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.sb_repo.delete(id, plant_id, user_id).await
+    }
+
+    // None for unknown, revoked and expired tokens
+    pub async fn shared_plant_sb(&self, token: &str) -> Result<Option<SharedPlant>, String> {
+        let Some(share) = self.sb_repo.get_valid(token).await? else {
+            return Ok(None);
+        };
+        let plant = self.plant_sb_repo.get_by_id(share.plant_id, share.user_id).await?;
+        let events = self.care_sb_repo.get_all_by_plant(share.plant_id, None, SUMMARY_EVENT_LIMIT).await?;
+        Ok(Some(shared_plant(plant, &events, share.expires_at)))
+    }
+
+    // None when the garden does not exist or the user is not its owner
+    pub async fn create_garden_sb(&self, garden_id: Uuid, user_id: Uuid, query: &ShareQuery) -> Result<Option<GardenShareLink>, String> {
+        let share = self.sb_repo.create_garden(&new_garden_share(garden_id, user_id, query)).await?;
+        Ok(share.map(|share| self.garden_link("sb", share)))
+    }
+
+    pub async fn get_all_garden_sb(&self, garden_id: Uuid, user_id: Uuid) -> Result<Vec<GardenShareLink>, String> {
+        let shares = self.sb_repo.get_all_by_garden(garden_id, user_id).await?;
+        Ok(shares.into_iter().map(|share| self.garden_link("sb", share)).collect())
+    }
+
+    pub async fn revoke_garden_sb(&self, id: Uuid, garden_id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.sb_repo.delete_garden(id, garden_id, user_id).await
+    }
+
+    // The garden's plants as they are when the link is opened, so plants added later show up too
+    pub async fn shared_garden_sb(&self, token: &str) -> Result<Option<SharedGarden>, String> {
+        let Some(share) = self.sb_repo.get_valid_garden(token).await? else {
+            return Ok(None);
+        };
+        let Some(garden) = self.garden_sb_repo.get_by_id(share.garden_id).await? else {
+            return Ok(None);
+        };
+        let plant_ids = self.garden_sb_repo.plant_ids(garden.id).await?;
+        let mut plants = self.plant_sb_repo.get_by_ids(&plant_ids).await?;
+        plants.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut shared = Vec::with_capacity(plants.len());
+        for plant in plants {
+            let events = self.care_sb_repo.get_all_by_plant(plant.id, None, SUMMARY_EVENT_LIMIT).await?;
+            shared.push(shared_plant(plant, &events, share.expires_at));
+        }
+        Ok(Some(SharedGarden { name: garden.name, plants: shared, shared_until: share.expires_at }))
+    }
+}
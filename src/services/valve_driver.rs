@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use std::time::Duration;
+
+// Switches the hardware of a zone; `valve` is the zone's valve address as the user entered it
+#[async_trait]
+pub trait ValveDriver: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Opens the valve, keeps it open for `minutes` and closes it again; Ok only once it is closed
+    async fn run(&self, valve: &str, minutes: u32) -> Result<(), String>;
+}
+
+// No hardware: logs the valve switching and waits. seconds_per_minute below 60 speeds runs up for testing.
+pub struct SimulatedValveDriver {
+    pub seconds_per_minute: u64,
+}
+
+#[async_trait]
+impl ValveDriver for SimulatedValveDriver {
+    fn name(&self) -> &'static str {
+        "simulated"
+    }
+
+    async fn run(&self, valve: &str, minutes: u32) -> Result<(), String> {
+        log::info!("[simulated valve {}] open for {} minutes", valve, minutes);
+        tokio::time::sleep(Duration::from_secs(self.seconds_per_minute * minutes as u64)).await;
+        log::info!("[simulated valve {}] closed", valve);
+        Ok(())
+    }
+}
@@ -0,0 +1,296 @@
+use crate::config::{WeatherConfig, WeatherProviderKind};
+use crate::models::plant::Plant;
+use crate::services::care_schedule::{CareKind, CareTask};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyWeather {
+    pub date: NaiveDate,
+    pub precipitation_mm: f64,
+    pub temp_min_c: f64,
+    pub temp_max_c: f64,
+}
+
+// Daily weather for a city; past days are observations, later days forecasts
+#[async_trait]
+pub trait WeatherProvider: Send + Sync {
+    // Days from..=to, days without data are left out
+    async fn daily(&self, city: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyWeather>, String>;
+}
+
+// Owners of the plants, each once
+pub fn plant_owners(plants: &[Plant]) -> Vec<Uuid> {
+    let mut owner_ids: Vec<Uuid> = plants.iter().map(|p| p.user_id).collect();
+    owner_ids.sort();
+    owner_ids.dedup();
+    owner_ids
+}
+
+// Local and test provider - reads a JSON file like {"Berlin": [{"date": "2025-07-15", "precipitation_mm": 20.0,
+// "temp_min_c": 14.0, "temp_max_c": 24.0}]}. The file is read on every call so it can be edited while running.
+pub struct FixtureWeatherProvider {
+    pub path: PathBuf,
+}
+
+#[async_trait]
+impl WeatherProvider for FixtureWeatherProvider {
+    async fn daily(&self, city: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyWeather>, String> {
+        let text = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|e| format!("Failed to read weather fixture {}: {}", self.path.display(), e))?;
+        let cities: HashMap<String, Vec<DailyWeather>> = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid weather fixture {}: {}", self.path.display(), e))?;
+
+        let days = cities.into_iter()
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case(city.trim()))
+            .map(|(_, days)| days)
+            .unwrap_or_default();
+        Ok(days.into_iter().filter(|d| d.date >= from && d.date <= to).collect())
+    }
+}
+
+#[derive(Deserialize)]
+struct GeocodingResponse {
+    #[serde(default)]
+    results: Vec<GeocodingResult>,
+}
+
+#[derive(Deserialize)]
+struct GeocodingResult {
+    latitude: f64,
+    longitude: f64,
+}
+
+#[derive(Deserialize)]
+struct ForecastResponse {
+    daily: ForecastDaily,
+}
+
+#[derive(Deserialize)]
+struct ForecastDaily {
+    time: Vec<NaiveDate>,
+    precipitation_sum: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    temperature_2m_max: Vec<Option<f64>>,
+}
+
+// How long fetched days are reused; dashboards are opened far more often than the weather changes
+const HTTP_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+// Cities are whatever users typed, so neither map may grow without bound
+const HTTP_CACHE_MAX_ENTRIES: usize = 1024;
+const MAX_GEOCODED_CITIES: usize = 1024;
+
+// (city, from, to) -> (fetched at, days)
+type ResponseCache = HashMap<(String, NaiveDate, NaiveDate), (Instant, Vec<DailyWeather>)>;
+
+// Open-Meteo compatible API: the city is geocoded once, then daily values are fetched by coordinates
+pub struct HttpWeatherProvider {
+    client: reqwest::Client,
+    geocoding_url: String,
+    forecast_url: String,
+    coordinates: Mutex<HashMap<String, (f64, f64)>>,
+    cache: Mutex<ResponseCache>,
+}
+
+impl HttpWeatherProvider {
+    pub fn new(geocoding_url: &str, forecast_url: &str) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(HttpWeatherProvider {
+            client,
+            geocoding_url: geocoding_url.to_string(),
+            forecast_url: forecast_url.to_string(),
+            coordinates: Mutex::new(HashMap::new()),
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    async fn locate(&self, city: &str) -> Result<Option<(f64, f64)>, String> {
+        if let Some(coordinates) = self.coordinates.lock().unwrap().get(city) {
+            return Ok(Some(*coordinates));
+        }
+
+        let response: GeocodingResponse = self.client.get(&self.geocoding_url)
+            .query(&[("name", city), ("count", "1")])
+            .send()
+            .await
+            .map_err(|e| format!("Geocoding request failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Geocoding request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid geocoding response: {}", e))?;
+
+        let found = response.results.first().map(|r| (r.latitude, r.longitude));
+        if let Some(coordinates) = found {
+            let mut known = self.coordinates.lock().unwrap();
+            // Coordinates do not go stale, any city can make room
+            if known.len() >= MAX_GEOCODED_CITIES
+                && let Some(city) = known.keys().next().cloned()
+            {
+                known.remove(&city);
+            }
+            known.insert(city.to_string(), coordinates);
+        }
+        Ok(found)
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for HttpWeatherProvider {
+    async fn daily(&self, city: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyWeather>, String> {
+        let city = city.trim().to_lowercase();
+        let key = (city.clone(), from, to);
+        if let Some((fetched_at, days)) = self.cache.lock().unwrap().get(&key)
+            && fetched_at.elapsed() < HTTP_CACHE_TTL
+        {
+            return Ok(days.clone());
+        }
+
+        let Some((latitude, longitude)) = self.locate(&city).await? else {
+            return Ok(Vec::new()); // unknown city, nothing to report
+        };
+
+        let response: ForecastResponse = self.client.get(&self.forecast_url)
+            .query(&[
+                ("latitude", latitude.to_string()),
+                ("longitude", longitude.to_string()),
+                ("daily", "precipitation_sum,temperature_2m_min,temperature_2m_max".to_string()),
+                ("timezone", "auto".to_string()),
+                ("start_date", from.to_string()),
+                ("end_date", to.to_string()),
+            ])
+            .send()
+            .await
+            .map_err(|e| format!("Weather request failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Weather request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid weather response: {}", e))?;
+
+        let daily = response.daily;
+        let days: Vec<DailyWeather> = daily.time.iter().enumerate()
+            .filter_map(|(i, date)| Some(DailyWeather {
+                date: *date,
+                precipitation_mm: daily.precipitation_sum.get(i).copied().flatten()?,
+                temp_min_c: daily.temperature_2m_min.get(i).copied().flatten()?,
+                temp_max_c: daily.temperature_2m_max.get(i).copied().flatten()?,
+            }))
+            .collect();
+
+        let mut cache = self.cache.lock().unwrap();
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < HTTP_CACHE_TTL);
+        if cache.len() >= HTTP_CACHE_MAX_ENTRIES
+            && let Some(oldest) = cache.iter().min_by_key(|(_, (fetched_at, _))| *fetched_at).map(|(key, _)| key.clone())
+        {
+            cache.remove(&oldest);
+        }
+        cache.insert(key, (Instant::now(), days.clone()));
+        Ok(days)
+    }
+}
+
+// An outdoor plant that does not need watering on the given day
+#[derive(Debug, Clone, Serialize)]
+pub struct WateringSkip {
+    pub plant_id: Uuid,
+    pub plant_name: String,
+    pub reason: String,
+}
+
+pub struct WeatherService {
+    pub provider: Option<Arc<dyn WeatherProvider>>,
+    pub rain_threshold_mm: f64, // more rain than this counts as watering outdoor plants
+}
+
+impl WeatherService {
+    pub fn from_config(config: &WeatherConfig) -> Result<Self, String> {
+        let provider: Option<Arc<dyn WeatherProvider>> = match config.provider {
+            WeatherProviderKind::Http => Some(Arc::new(HttpWeatherProvider::new(&config.geocoding_url, &config.forecast_url)?)),
+            WeatherProviderKind::Fixture => Some(Arc::new(FixtureWeatherProvider { path: config.fixture_path.clone().into() })),
+            WeatherProviderKind::None => None,
+        };
+        Ok(WeatherService { provider, rain_threshold_mm: config.rain_threshold_mm })
+    }
+
+    // Best effort: no provider, no city or a failing provider all mean "no weather data"
+    pub async fn daily(&self, city: Option<&str>, from: NaiveDate, to: NaiveDate) -> Vec<DailyWeather> {
+        let (Some(provider), Some(city)) = (&self.provider, city.map(str::trim).filter(|c| !c.is_empty())) else {
+            return Vec::new();
+        };
+        match provider.daily(city, from, to).await {
+            Ok(days) => days,
+            Err(e) => {
+                log::warn!("Weather lookup for {:?} failed: {}", city, e);
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    fn rain_reason(&self, day: &DailyWeather, today: NaiveDate) -> Option<String> {
+        if day.precipitation_mm <= self.rain_threshold_mm {
+            return None;
+        }
+        Some(if day.date <= today {
+            format!("skip watering: rained {:.1} mm", day.precipitation_mm)
+        } else {
+            format!("skip watering: {:.1} mm rain forecast", day.precipitation_mm)
+        })
+    }
+
+    // Marks watering tasks of outdoor plants on rainy days
+    pub fn mark_rain_skips(&self, tasks: &mut [CareTask], plants: &[Plant], weather: &[DailyWeather], today: NaiveDate) {
+        let outdoor: HashMap<Uuid, bool> = plants.iter().map(|p| (p.id, !p.indoor)).collect();
+        for task in tasks.iter_mut().filter(|t| t.kind == CareKind::Water) {
+            if !outdoor.get(&task.plant_id).copied().unwrap_or(false) {
+                continue;
+            }
+            if let Some(day) = weather.iter().find(|d| d.date == task.due) {
+                task.skip_reason = self.rain_reason(day, today);
+            }
+        }
+    }
+
+    // mark_rain_skips for plants of many users, checking the forecast once per city; `cities` holds
+    // (user_id, city) of the owners, see plant_owners
+    pub async fn mark_rain_skips_by_city(&self, tasks: &mut [CareTask], plants: &[Plant], cities: &[(Uuid, Option<String>)], today: NaiveDate) {
+        let mut by_city: BTreeMap<String, Vec<Plant>> = BTreeMap::new();
+        for (user_id, city) in cities {
+            let Some(city) = city.as_deref().map(|c| c.trim().to_lowercase()).filter(|c| !c.is_empty()) else {
+                continue;
+            };
+            by_city.entry(city).or_default().extend(plants.iter().filter(|p| p.user_id == *user_id).cloned());
+        }
+        for (city, plants) in by_city {
+            let forecast = self.daily(Some(&city), today, today).await;
+            self.mark_rain_skips(tasks, &plants, &forecast, today);
+        }
+    }
+
+    // Outdoor plants not watered yet on `date` that the rain took care of
+    pub fn rain_skips(&self, plants: &[Plant], date: NaiveDate, weather: &[DailyWeather], today: NaiveDate) -> Vec<WateringSkip> {
+        let Some(reason) = weather.iter().find(|d| d.date == date).and_then(|d| self.rain_reason(d, today)) else {
+            return Vec::new();
+        };
+        let day_key = date.format("%Y-%m-%d").to_string();
+        plants.iter()
+            .filter(|p| !p.indoor)
+            .filter(|p| !p.status.get(&day_key).and_then(|s| s.get("watered")).and_then(|v| v.as_bool()).unwrap_or(false))
+            .map(|p| WateringSkip { plant_id: p.id, plant_name: p.name.clone(), reason: reason.clone() })
+            .collect()
+    }
+}
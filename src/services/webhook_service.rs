@@ -0,0 +1,338 @@
+use crate::config::WebhooksConfig;
+use crate::dtos::webhook_dto::{check_target, is_public_ip, CreateWebhookDTO, DeliveriesQuery, UpdateWebhookDTO, WEBHOOK_EVENTS};
+use crate::models::webhook::{DueDelivery, RegisteredWebhook, Webhook, WebhookDelivery};
+use crate::repositories::webhook_postgres::WebhookPostgresRepo;
+use crate::repositories::webhook_supabase::WebhookSupabaseRepo;
+use crate::secret::random_token;
+use chrono::{Duration, Utc};
+use hmac::{Hmac, KeyInit, Mac};
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use serde_json::json;
+use sha2::Sha256;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+const SECRET_PREFIX: &str = "whsec_";
+// Deliveries picked up per query; the worker keeps going until the queue has nothing due
+const DELIVERY_BATCH: i64 = 50;
+const MAX_RETRY_DELAY_SECONDS: i64 = 6 * 3600;
+
+pub const SIGNATURE_HEADER: &str = "X-Gardenary-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Gardenary-Timestamp";
+pub const EVENT_HEADER: &str = "X-Gardenary-Event";
+pub const DELIVERY_HEADER: &str = "X-Gardenary-Delivery";
+
+pub struct WebhookService {
+    pub pg_repo: WebhookPostgresRepo,
+    pub sb_repo: WebhookSupabaseRepo,
+    client: reqwest::Client,
+    max_attempts: i32,
+    retry_base_seconds: i64,
+    // Woken when deliveries are queued so they go out without waiting for the next poll
+    pub wake: Notify,
+}
+
+// Resolves webhook hosts like the system resolver but drops loopback, private and link-local
+// addresses, so a name that was public at registration cannot be re-pointed into our network
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs = tokio::net::lookup_host((host.as_str(), 0)).await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect::<Vec<SocketAddr>>();
+            if addrs.is_empty() {
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+// Unlike device API keys the secret is stored as it is (webhooks.secret): every delivery is signed
+// with it, so a hash would be useless. The database is trusted with it the same way it holds the
+// Supabase key; it never leaves through the API again, only secret_prefix does.
+fn new_secret() -> String {
+    format!("{}{}", SECRET_PREFIX, random_token())
+}
+
+// Hex HMAC-SHA256 of "<timestamp>.<body>"; receivers recompute it with their secret and compare
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+// Doubles with every failed attempt: base, 2x base, 4x base, ... capped at six hours
+fn retry_delay(base_seconds: i64, attempts: i32) -> Duration {
+    let factor = 1i64 << (attempts - 1).clamp(0, 20);
+    Duration::seconds(base_seconds.saturating_mul(factor).min(MAX_RETRY_DELAY_SECONDS))
+}
+
+fn new_webhook(user_id: Uuid, dto: CreateWebhookDTO, secret: &str) -> Webhook {
+    let now = Utc::now();
+    Webhook {
+        id: Uuid::new_v4(),
+        user_id,
+        url: dto.url.trim().to_string(),
+        secret_prefix: secret.chars().take(SECRET_PREFIX.len() + 6).collect(),
+        events: dedup(dto.events.unwrap_or_else(|| WEBHOOK_EVENTS.iter().map(|e| e.to_string()).collect())),
+        active: true,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn apply_update(mut webhook: Webhook, dto: UpdateWebhookDTO) -> Webhook {
+    if let Some(url) = dto.url {
+        webhook.url = url.trim().to_string();
+    }
+    if let Some(events) = dto.events {
+        webhook.events = dedup(events);
+    }
+    if let Some(active) = dto.active {
+        webhook.active = active;
+    }
+    webhook.updated_at = Utc::now();
+    webhook
+}
+
+fn dedup(mut events: Vec<String>) -> Vec<String> {
+    events.sort();
+    events.dedup();
+    events
+}
+
+// One delivery per webhook, each with its own id in the payload so receivers can drop duplicates
+fn new_deliveries(webhooks: &[Webhook], event: &str, data: &serde_json::Value) -> Vec<WebhookDelivery> {
+    let now = Utc::now();
+    webhooks.iter().map(|webhook| {
+        let id = Uuid::new_v4();
+        WebhookDelivery {
+            id,
+            webhook_id: webhook.id,
+            user_id: webhook.user_id,
+            event: event.to_string(),
+            payload: json!({ "id": id, "event": event, "created_at": now, "data": data }),
+            status: "pending".to_string(),
+            attempts: 0,
+            next_attempt_at: now,
+            last_status_code: None,
+            last_error: None,
+            created_at: now,
+            delivered_at: None,
+        }
+    }).collect()
+}
+
+impl WebhookService {
+    pub fn new(pg_repo: WebhookPostgresRepo, sb_repo: WebhookSupabaseRepo, config: &WebhooksConfig) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy() // a proxy would resolve the host itself, past the resolver below
+            .dns_resolver(Arc::new(PublicOnlyResolver))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(WebhookService {
+            pg_repo,
+            sb_repo,
+            client,
+            max_attempts: config.max_attempts as i32,
+            retry_base_seconds: config.retry_base_seconds as i64,
+            wake: Notify::new(),
+        })
+    }
+
+    // Sends one delivery and returns it with the outcome filled in, ready to be stored
+    async fn attempt(&self, due: DueDelivery) -> WebhookDelivery {
+        let DueDelivery { mut delivery, url, secret } = due;
+        let body = serde_json::to_vec(&delivery.payload).unwrap_or_default();
+        let timestamp = Utc::now().timestamp();
+
+        // IP literals never reach the resolver, and webhooks registered before the check may point anywhere
+        let target = reqwest::Url::parse(&url).map_err(|e| e.to_string())
+            .and_then(|u| check_target(&u).map_err(|e| format!("Webhook URL {}", e)));
+        if let Err(e) = target {
+            delivery.attempts += 1;
+            delivery.status = "failed".to_string();
+            delivery.last_status_code = None;
+            delivery.last_error = Some(e);
+            return delivery;
+        }
+
+        let result = self.client.post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &delivery.event)
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, format!("sha256={}", sign(&secret, timestamp, &body)))
+            .body(body)
+            .send()
+            .await;
+
+        delivery.attempts += 1;
+        let now = Utc::now();
+        match result {
+            Ok(response) if response.status().is_success() => {
+                delivery.status = "delivered".to_string();
+                delivery.last_status_code = Some(response.status().as_u16() as i32);
+                delivery.last_error = None;
+                delivery.delivered_at = Some(now);
+                return delivery;
+            }
+            Ok(response) => {
+                delivery.last_status_code = Some(response.status().as_u16() as i32);
+                delivery.last_error = Some(format!("Endpoint answered {}", response.status()));
+            }
+            Err(e) => {
+                delivery.last_status_code = None;
+                delivery.last_error = Some(e.to_string());
+            }
+        }
+
+        if delivery.attempts >= self.max_attempts {
+            delivery.status = "failed".to_string();
+        } else {
+            delivery.next_attempt_at = now + retry_delay(self.retry_base_seconds, delivery.attempts);
+        }
+        delivery
+    }
+
+    // PostgreSQL methods
+    pub async fn create_pg(&self, user_id: Uuid, dto: CreateWebhookDTO) -> Result<RegisteredWebhook, String> {
+        let secret = new_secret();
+        let webhook = self.pg_repo.create(&new_webhook(user_id, dto, &secret), &secret).await?;
+        Ok(RegisteredWebhook { webhook, secret })
+    }
+
+    pub async fn get_all_pg(&self, user_id: Uuid) -> Result<Vec<Webhook>, String> {
+        self.pg_repo.get_all_by_user(user_id).await
+    }
+
+    pub async fn get_by_id_pg(&self, id: Uuid, user_id: Uuid) -> Result<Option<Webhook>, String> {
+        self.pg_repo.get_by_id(id, user_id).await
+    }
+
+    pub async fn update_pg(&self, id: Uuid, user_id: Uuid, dto: UpdateWebhookDTO) -> Result<Option<Webhook>, String> {
+        let Some(webhook) = self.pg_repo.get_by_id(id, user_id).await? else {
+            return Ok(None);
+        };
+        self.pg_repo.update(&apply_update(webhook, dto)).await
+    }
+
+    pub async fn delete_pg(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.pg_repo.delete(id, user_id).await
+    }
+
+    // None when the webhook does not exist
+    pub async fn deliveries_pg(&self, id: Uuid, user_id: Uuid, query: &DeliveriesQuery) -> Result<Option<Vec<WebhookDelivery>>, String> {
+        if self.pg_repo.get_by_id(id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        self.pg_repo.get_deliveries(id, user_id, query.status.as_deref(), query.limit()).await.map(Some)
+    }
+
+    // Queues the event for every active webhook of the user that wants it
+    pub async fn emit_pg(&self, user_id: Uuid, event: &str, data: serde_json::Value) -> Result<usize, String> {
+        let webhooks = self.pg_repo.subscribers(user_id, event).await?;
+        if webhooks.is_empty() {
+            return Ok(0);
+        }
+        let deliveries = new_deliveries(&webhooks, event, &data);
+        self.pg_repo.enqueue(&deliveries).await?;
+        self.wake.notify_one();
+        Ok(deliveries.len())
+    }
+
+    // Sends everything due, one at a time; returns how many were delivered
+    pub async fn deliver_due_pg(&self) -> Result<usize, String> {
+        let mut delivered = 0;
+        loop {
+            let due = self.pg_repo.due_deliveries(DELIVERY_BATCH).await?;
+            let more = due.len() as i64 == DELIVERY_BATCH;
+            for d in due {
+                let delivery = self.attempt(d).await;
+                self.pg_repo.record_attempt(&delivery).await?;
+                if delivery.status == "delivered" {
+                    delivered += 1;
+                }
+            }
+            if !more {
+                return Ok(delivered);
+            }
+        }
+    }
+
+    // Supabase methods
+    pub async fn create_sb(&self, user_id: Uuid, dto: CreateWebhookDTO) -> Result<RegisteredWebhook, String> {
+        let secret = new_secret();
+        let webhook = self.sb_repo.create(&new_webhook(user_id, dto, &secret), &secret).await?;
+        Ok(RegisteredWebhook { webhook, secret })
+    }
+
+    pub async fn get_all_sb(&self, user_id: Uuid) -> Result<Vec<Webhook>, String> {
+        self.sb_repo.get_all_by_user(user_id).await
+    }
+
+    pub async fn get_by_id_sb(&self, id: Uuid, user_id: Uuid) -> Result<Option<Webhook>, String> {
+        self.sb_repo.get_by_id(id, user_id).await
+    }
+
+    pub async fn update_sb(&self, id: Uuid, user_id: Uuid, dto: UpdateWebhookDTO) -> Result<Option<Webhook>, String> {
+        let Some(webhook) = self.sb_repo.get_by_id(id, user_id).await? else {
+            return Ok(None);
+        };
+        self.sb_repo.update(&apply_update(webhook, dto)).await
+    }
+
+    pub async fn delete_sb(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.sb_repo.delete(id, user_id).await
+    }
+
+    // None when the webhook does not exist
+    pub async fn deliveries_sb(&self, id: Uuid, user_id: Uuid, query: &DeliveriesQuery) -> Result<Option<Vec<WebhookDelivery>>, String> {
+        if self.sb_repo.get_by_id(id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        self.sb_repo.get_deliveries(id, user_id, query.status.as_deref(), query.limit()).await.map(Some)
+    }
+
+    // Queues the event for every active webhook of the user that wants it
+    pub async fn emit_sb(&self, user_id: Uuid, event: &str, data: serde_json::Value) -> Result<usize, String> {
+        let webhooks = self.sb_repo.subscribers(user_id, event).await?;
+        if webhooks.is_empty() {
+            return Ok(0);
+        }
+        let deliveries = new_deliveries(&webhooks, event, &data);
+        self.sb_repo.enqueue(&deliveries).await?;
+        self.wake.notify_one();
+        Ok(deliveries.len())
+    }
+
+    // Sends everything due, one at a time; returns how many were delivered
+    pub async fn deliver_due_sb(&self) -> Result<usize, String> {
+        let mut delivered = 0;
+        loop {
+            let due = self.sb_repo.due_deliveries(DELIVERY_BATCH).await?;
+            let more = due.len() as i64 == DELIVERY_BATCH;
+            for d in due {
+                let delivery = self.attempt(d).await;
+                self.sb_repo.record_attempt(&delivery).await?;
+                if delivery.status == "delivered" {
+                    delivered += 1;
+                }
+            }
+            if !more {
+                return Ok(delivered);
+            }
+        }
+    }
+}
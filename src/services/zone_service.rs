@@ -0,0 +1,398 @@
+use crate::dtos::validation::ValidationErrors;
+use crate::dtos::zone_dto::{CreateZoneDTO, UpdateZoneDTO, DEFAULT_RUN_MINUTES};
+use crate::errors::ServiceError;
+use crate::models::care_event::CareEvent;
+use crate::models::plant::Plant;
+use crate::models::zone::{Zone, ZoneRun};
+use crate::repositories::plant_postgres::PlantPostgresRepo;
+use crate::repositories::plant_supabase::PlantSupabaseRepo;
+use crate::repositories::zone_postgres::ZonePostgresRepo;
+use crate::repositories::zone_supabase::ZoneSupabaseRepo;
+use crate::services::care_schedule::{upcoming_tasks, CareKind, CareTask};
+use crate::services::plant_service::PlantService;
+use crate::services::valve_driver::ValveDriver;
+use crate::services::weather::{plant_owners, WeatherService};
+use chrono::{NaiveDate, Utc};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ZoneService {
+    pub pg_repo: ZonePostgresRepo,
+    pub sb_repo: ZoneSupabaseRepo,
+    pub plant_pg_repo: PlantPostgresRepo,
+    pub plant_sb_repo: PlantSupabaseRepo,
+    // Records finished runs as care, so they reach dashboards and webhooks like manual care
+    pub plants: Arc<PlantService>,
+    pub driver: Arc<dyn ValveDriver>,
+    // Scheduled runs leave out outdoor plants the rain took care of
+    pub weather: Arc<WeatherService>,
+}
+
+fn dedup(mut plant_ids: Vec<Uuid>) -> Vec<Uuid> {
+    let mut seen = Vec::with_capacity(plant_ids.len());
+    plant_ids.retain(|id| {
+        let new = !seen.contains(id);
+        seen.push(*id);
+        new
+    });
+    plant_ids
+}
+
+fn new_zone(user_id: Uuid, dto: CreateZoneDTO) -> Zone {
+    let now = Utc::now();
+    Zone {
+        id: Uuid::new_v4(),
+        user_id,
+        name: dto.name.trim().to_string(),
+        valve: dto.valve.trim().to_string(),
+        plant_ids: dedup(dto.plant_ids),
+        run_minutes: dto.run_minutes.unwrap_or(DEFAULT_RUN_MINUTES),
+        scheduled: dto.scheduled,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn apply_update(zone: &mut Zone, dto: UpdateZoneDTO) {
+    if let Some(name) = dto.name {
+        zone.name = name.trim().to_string();
+    }
+    if let Some(valve) = dto.valve {
+        zone.valve = valve.trim().to_string();
+    }
+    if let Some(plant_ids) = dto.plant_ids {
+        zone.plant_ids = dedup(plant_ids);
+    }
+    if let Some(run_minutes) = dto.run_minutes {
+        zone.run_minutes = run_minutes;
+    }
+    if let Some(scheduled) = dto.scheduled {
+        zone.scheduled = scheduled;
+    }
+    zone.updated_at = Utc::now();
+}
+
+// Every id in plant_ids has to be one of the user's plants
+fn check_owned(plant_ids: &[Uuid], owned: &[Plant]) -> Result<(), ServiceError> {
+    let unknown: Vec<String> = plant_ids.iter()
+        .filter(|id| !owned.iter().any(|p| p.id == **id))
+        .map(|id| id.to_string())
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    let mut errors = ValidationErrors::default();
+    errors.add("plant_ids", format!("unknown plants: {}", unknown.join(", ")));
+    Err(ServiceError::Validation(errors))
+}
+
+fn new_run(zone: &Zone, minutes: i32, triggered_by: &str) -> ZoneRun {
+    ZoneRun {
+        id: Uuid::new_v4(),
+        zone_id: zone.id,
+        user_id: zone.user_id,
+        minutes,
+        triggered_by: triggered_by.to_string(),
+        status: "running".to_string(),
+        error: None,
+        started_at: Utc::now(),
+        finished_at: None,
+    }
+}
+
+fn finished(mut run: ZoneRun, result: Result<(), String>) -> ZoneRun {
+    run.finished_at = Some(Utc::now());
+    match result {
+        Ok(()) => run.status = "completed".to_string(),
+        Err(e) => {
+            run.status = "failed".to_string();
+            run.error = Some(e);
+        }
+    }
+    run
+}
+
+fn zone_watering(plant_id: Uuid, zone: &Zone, run: &ZoneRun) -> CareEvent {
+    let now = Utc::now();
+    CareEvent {
+        id: Uuid::new_v4(),
+        plant_id,
+        user_id: zone.user_id,
+        action: "watered".to_string(),
+        source: "zone".to_string(),
+        occurred_at: run.finished_at.unwrap_or(now),
+        details: json!({ "zone_id": zone.id, "zone_name": zone.name, "run_id": run.id, "minutes": run.minutes }),
+        created_at: now,
+    }
+}
+
+// Watering due today, rained out ones included
+fn waterings_due(plants: &[Plant], today: NaiveDate) -> Vec<CareTask> {
+    upcoming_tasks(plants, today, 0).into_iter()
+        .filter(|t| t.kind == CareKind::Water && t.due == today)
+        .collect()
+}
+
+// Whether a plant of the zone that still belongs to its owner is among the due waterings
+fn due_today(zone: &Zone, plants: &[Plant], due: &[CareTask]) -> bool {
+    due.iter().any(|t| {
+        zone.plant_ids.contains(&t.plant_id) && plants.iter().any(|p| p.id == t.plant_id && p.user_id == zone.user_id)
+    })
+}
+
+fn ran_on_schedule(runs: &[ZoneRun], today: NaiveDate) -> bool {
+    runs.iter().any(|r| r.triggered_by == "schedule" && r.started_at.date_naive() == today)
+}
+
+impl ZoneService {
+    // PostgreSQL methods
+    pub async fn create_pg(&self, user_id: Uuid, dto: CreateZoneDTO) -> Result<Zone, ServiceError> {
+        let zone = new_zone(user_id, dto);
+        let owned = self.owned_plants_pg(user_id, &zone.plant_ids).await?;
+        check_owned(&zone.plant_ids, &owned)?;
+        Ok(self.pg_repo.create(&zone).await?)
+    }
+
+    pub async fn get_all_pg(&self, user_id: Uuid) -> Result<Vec<Zone>, String> {
+        self.pg_repo.get_all_by_user(user_id).await
+    }
+
+    pub async fn get_by_id_pg(&self, id: Uuid, user_id: Uuid) -> Result<Option<Zone>, String> {
+        self.pg_repo.get_by_id(id, user_id).await
+    }
+
+    pub async fn update_pg(&self, id: Uuid, user_id: Uuid, dto: UpdateZoneDTO) -> Result<Option<Zone>, ServiceError> {
+        let Some(mut zone) = self.pg_repo.get_by_id(id, user_id).await? else {
+            return Ok(None);
+        };
+        apply_update(&mut zone, dto);
+        let owned = self.owned_plants_pg(user_id, &zone.plant_ids).await?;
+        check_owned(&zone.plant_ids, &owned)?;
+        Ok(self.pg_repo.update(&zone).await?)
+    }
+
+    pub async fn delete_pg(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.pg_repo.delete(id, user_id).await
+    }
+
+    // None when the zone does not exist or belongs to someone else
+    pub async fn get_runs_pg(&self, id: Uuid, user_id: Uuid, limit: i64) -> Result<Option<Vec<ZoneRun>>, String> {
+        if self.pg_repo.get_by_id(id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        self.pg_repo.get_runs(id, user_id, limit).await.map(Some)
+    }
+
+    // Starts a run and returns right away; the driver runs it in the background
+    pub async fn run_pg(self: &Arc<Self>, id: Uuid, user_id: Uuid, minutes: Option<i32>, triggered_by: &str) -> Result<Option<ZoneRun>, ServiceError> {
+        let Some(zone) = self.pg_repo.get_by_id(id, user_id).await? else {
+            return Ok(None);
+        };
+        let run = new_run(&zone, minutes.unwrap_or(zone.run_minutes), triggered_by);
+        let Some(run) = self.pg_repo.start_run(&run).await? else {
+            return Err(ServiceError::Conflict("Zone is already running".to_string()));
+        };
+
+        let svc = self.clone();
+        let started = run.clone();
+        actix_web::rt::spawn(async move { svc.complete_pg(zone, started).await });
+        Ok(Some(run))
+    }
+
+    // Waits for the driver, then logs a watering for every plant in the zone if the run went through
+    async fn complete_pg(&self, zone: Zone, run: ZoneRun) {
+        let result = self.driver.run(&zone.valve, run.minutes as u32).await;
+        let run = match self.pg_repo.finish_run(&finished(run, result)).await {
+            Ok(Some(run)) => run,
+            Ok(None) => return, // the zone was deleted meanwhile
+            Err(e) => {
+                log::warn!("Finishing run of zone {} (Postgres) failed: {}", zone.id, e);
+                return;
+            }
+        };
+        if run.status != "completed" {
+            log::warn!("Run of zone {} failed: {}", zone.id, run.error.as_deref().unwrap_or("unknown error"));
+            return;
+        }
+
+        let plants = match self.owned_plants_pg(zone.user_id, &zone.plant_ids).await {
+            Ok(plants) => plants,
+            Err(e) => {
+                log::warn!("Loading plants of zone {} (Postgres) failed: {}", zone.id, e);
+                return;
+            }
+        };
+        for plant in plants {
+            let event = zone_watering(plant.id, &zone, &run);
+            if let Err(e) = self.plants.record_care_pg(zone.user_id, event).await {
+                log::warn!("Logging watering of plant {} (Postgres) failed: {}", plant.id, e);
+            }
+        }
+    }
+
+    // Starts scheduled zones with a plant due for watering today that the rain did not take care of, at most
+    // one scheduled run per zone and day; returns how many runs were started
+    pub async fn run_due_pg(self: &Arc<Self>) -> Result<usize, String> {
+        let today = Utc::now().date_naive();
+        let zones = self.pg_repo.scheduled_zones().await?;
+        let mut plant_ids: Vec<Uuid> = zones.iter().flat_map(|z| z.plant_ids.iter().copied()).collect();
+        plant_ids.sort();
+        plant_ids.dedup();
+        let plants = self.plant_pg_repo.get_by_ids(&plant_ids).await?;
+        let mut due = waterings_due(&plants, today);
+        if self.weather.enabled() && !due.is_empty() {
+            let cities = self.plant_pg_repo.owner_cities(&plant_owners(&plants)).await?;
+            self.weather.mark_rain_skips_by_city(&mut due, &plants, &cities, today).await;
+            due.retain(|t| t.skip_reason.is_none());
+        }
+
+        let mut started = 0;
+        for zone in zones.iter().filter(|z| due_today(z, &plants, &due)) {
+            if ran_on_schedule(&self.pg_repo.get_runs(zone.id, zone.user_id, 10).await?, today) {
+                continue;
+            }
+            // One broken valve must not hold up the other zones
+            match self.run_pg(zone.id, zone.user_id, None, "schedule").await {
+                Ok(Some(_)) => started += 1,
+                Ok(None) | Err(ServiceError::Conflict(_)) => {} // deleted or already running
+                Err(e) => log::warn!("Starting scheduled run of zone {} (Postgres) failed: {}", zone.id, e),
+            }
+        }
+        Ok(started)
+    }
+
+    pub async fn fail_interrupted_pg(&self) -> Result<u64, String> {
+        self.pg_repo.fail_interrupted().await
+    }
+
+    async fn owned_plants_pg(&self, user_id: Uuid, plant_ids: &[Uuid]) -> Result<Vec<Plant>, String> {
+        let plants = self.plant_pg_repo.get_by_ids(plant_ids).await?;
+        Ok(plants.into_iter().filter(|p| p.user_id == user_id).collect())
+    }
+
+    // Supabase methods
+    pub async fn create_sb(&self, user_id: Uuid, dto: CreateZoneDTO) -> Result<Zone, ServiceError> {
+        let zone = new_zone(user_id, dto);
+        let owned = self.owned_plants_sb(user_id, &zone.plant_ids).await?;
+        check_owned(&zone.plant_ids, &owned)?;
+        Ok(self.sb_repo.create(&zone).await?)
+    }
+
+    pub async fn get_all_sb(&self, user_id: Uuid) -> Result<Vec<Zone>, String> {
+        self.sb_repo.get_all_by_user(user_id).await
+    }
+
+    pub async fn get_by_id_sb(&self, id: Uuid, user_id: Uuid) -> Result<Option<Zone>, String> {
+        self.sb_repo.get_by_id(id, user_id).await
+    }
+
+    pub async fn update_sb(&self, id: Uuid, user_id: Uuid, dto: UpdateZoneDTO) -> Result<Option<Zone>, ServiceError> {
+        let Some(mut zone) = self.sb_repo.get_by_id(id, user_id).await? else {
+            return Ok(None);
+        };
+        apply_update(&mut zone, dto);
+        let owned = self.owned_plants_sb(user_id, &zone.plant_ids).await?;
+        check_owned(&zone.plant_ids, &owned)?;
+        Ok(self.sb_repo.update(&zone).await?)
+    }
+
+    pub async fn delete_sb(&self, id: Uuid, user_id: Uuid) -> Result<u64, String> {
+        self.sb_repo.delete(id, user_id).await
+    }
+
+    // None when the zone does not exist or belongs to someone else
+    pub async fn get_runs_sb(&self, id: Uuid, user_id: Uuid, limit: i64) -> Result<Option<Vec<ZoneRun>>, String> {
+        if self.sb_repo.get_by_id(id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        self.sb_repo.get_runs(id, user_id, limit).await.map(Some)
+    }
+
+    // Starts a run and returns right away; the driver runs it in the background
+    pub async fn run_sb(self: &Arc<Self>, id: Uuid, user_id: Uuid, minutes: Option<i32>, triggered_by: &str) -> Result<Option<ZoneRun>, ServiceError> {
+        let Some(zone) = self.sb_repo.get_by_id(id, user_id).await? else {
+            return Ok(None);
+        };
+        let run = new_run(&zone, minutes.unwrap_or(zone.run_minutes), triggered_by);
+        let Some(run) = self.sb_repo.start_run(&run).await? else {
+            return Err(ServiceError::Conflict("Zone is already running".to_string()));
+        };
+
+        let svc = self.clone();
+        let started = run.clone();
+        actix_web::rt::spawn(async move { svc.complete_sb(zone, started).await });
+        Ok(Some(run))
+    }
+
+    // Waits for the driver, then logs a watering for every plant in the zone if the run went through
+    async fn complete_sb(&self, zone: Zone, run: ZoneRun) {
+        let result = self.driver.run(&zone.valve, run.minutes as u32).await;
+        let run = match self.sb_repo.finish_run(&finished(run, result)).await {
+            Ok(Some(run)) => run,
+            Ok(None) => return, // the zone was deleted meanwhile
+            Err(e) => {
+                log::warn!("Finishing run of zone {} (Supabase) failed: {}", zone.id, e);
+                return;
+            }
+        };
+        if run.status != "completed" {
+            log::warn!("Run of zone {} failed: {}", zone.id, run.error.as_deref().unwrap_or("unknown error"));
+            return;
+        }
+
+        let plants = match self.owned_plants_sb(zone.user_id, &zone.plant_ids).await {
+            Ok(plants) => plants,
+            Err(e) => {
+                log::warn!("Loading plants of zone {} (Supabase) failed: {}", zone.id, e);
+                return;
+            }
+        };
+        for plant in plants {
+            let event = zone_watering(plant.id, &zone, &run);
+            if let Err(e) = self.plants.record_care_sb(zone.user_id, event).await {
+                log::warn!("Logging watering of plant {} (Supabase) failed: {}", plant.id, e);
+            }
+        }
+    }
+
+    // Starts scheduled zones with a plant due for watering today that the rain did not take care of, at most
+    // one scheduled run per zone and day; returns how many runs were started
+    pub async fn run_due_sb(self: &Arc<Self>) -> Result<usize, String> {
+        let today = Utc::now().date_naive();
+        let zones = self.sb_repo.scheduled_zones().await?;
+        let mut plant_ids: Vec<Uuid> = zones.iter().flat_map(|z| z.plant_ids.iter().copied()).collect();
+        plant_ids.sort();
+        plant_ids.dedup();
+        let plants = self.plant_sb_repo.get_by_ids(&plant_ids).await?;
+        let mut due = waterings_due(&plants, today);
+        if self.weather.enabled() && !due.is_empty() {
+            let cities = self.plant_sb_repo.owner_cities(&plant_owners(&plants)).await?;
+            self.weather.mark_rain_skips_by_city(&mut due, &plants, &cities, today).await;
+            due.retain(|t| t.skip_reason.is_none());
+        }
+
+        let mut started = 0;
+        for zone in zones.iter().filter(|z| due_today(z, &plants, &due)) {
+            if ran_on_schedule(&self.sb_repo.get_runs(zone.id, zone.user_id, 10).await?, today) {
+                continue;
+            }
+            // One broken valve must not hold up the other zones
+            match self.run_sb(zone.id, zone.user_id, None, "schedule").await {
+                Ok(Some(_)) => started += 1,
+                Ok(None) | Err(ServiceError::Conflict(_)) => {} // deleted or already running
+                Err(e) => log::warn!("Starting scheduled run of zone {} (Supabase) failed: {}", zone.id, e),
+            }
+        }
+        Ok(started)
+    }
+
+    pub async fn fail_interrupted_sb(&self) -> Result<u64, String> {
+        self.sb_repo.fail_interrupted().await
+    }
+
+    async fn owned_plants_sb(&self, user_id: Uuid, plant_ids: &[Uuid]) -> Result<Vec<Plant>, String> {
+        let plants = self.plant_sb_repo.get_by_ids(plant_ids).await?;
+        Ok(plants.into_iter().filter(|p| p.user_id == user_id).collect())
+    }
+}
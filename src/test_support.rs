@@ -0,0 +1,53 @@
+// Helpers shared by the unit tests
+use crate::config::{WeatherConfig, WeatherProviderKind, WebhooksConfig};
+use crate::repositories::supabase_client::SupabaseClient;
+use crate::secret::Secret;
+use deadpool_postgres::{Config, Pool};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const FROST_C: f64 = 2.0;
+pub const HEAT_C: f64 = 32.0;
+pub const RAIN_MM: f64 = 5.0;
+
+// Creating the pool does not connect, so tests that never touch the repos need no database
+pub fn pg_pool() -> Pool {
+    let env = |name: &str, default: &str| std::env::var(name).unwrap_or_else(|_| default.to_string());
+    let mut cfg = Config::new();
+    cfg.host = Some(env("PG_HOST", "localhost"));
+    cfg.user = Some(env("PG_USER", "postgres"));
+    cfg.password = std::env::var("PG_PASS").ok();
+    cfg.dbname = Some(env("PG_DB", "postgres"));
+    cfg.create_pool(None, tokio_postgres::NoTls).unwrap()
+}
+
+// Points at a closed port, for tests that only use the Postgres side
+pub fn supabase() -> SupabaseClient {
+    SupabaseClient::new("http://localhost:9", Secret::new("x".to_string())).unwrap()
+}
+
+// Writes a FixtureWeatherProvider file to a fresh temp path; the test removes it again
+pub fn write_weather_fixture(fixture: &serde_json::Value) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gardenary-weather-{}.json", Uuid::new_v4()));
+    std::fs::write(&path, fixture.to_string()).unwrap();
+    path
+}
+
+// Weather from the fixture file, alerts three days ahead
+pub fn weather_config(fixture: &Path) -> WeatherConfig {
+    WeatherConfig {
+        provider: WeatherProviderKind::Fixture,
+        fixture_path: fixture.to_string_lossy().into_owned(),
+        geocoding_url: String::new(),
+        forecast_url: String::new(),
+        rain_threshold_mm: RAIN_MM,
+        frost_threshold_c: FROST_C,
+        heat_threshold_c: HEAT_C,
+        alert_horizon_days: 3,
+        alert_interval_minutes: 0,
+    }
+}
+
+pub fn webhooks_config() -> WebhooksConfig {
+    WebhooksConfig { max_attempts: 1, retry_base_seconds: 1, timeout_seconds: 1, poll_interval_seconds: 1 }
+}
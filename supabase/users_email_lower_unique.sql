@@ -0,0 +1,18 @@
+-- Supabase counterpart of migrations/0002_users_email_lower_unique.up.sql; the migration runner only
+-- targets Postgres, so run this once in the Supabase SQL editor.
+
+-- 1. Accounts that only differ by case. Merge or delete these by hand first, the index below fails otherwise.
+SELECT lower(trim(email)) AS email, array_agg(id ORDER BY created_at) AS user_ids
+FROM users
+GROUP BY lower(trim(email))
+HAVING count(*) > 1;
+
+-- 2. Emails are stored lowercased; fold the rows that do not clash with another account.
+UPDATE users u SET email = lower(trim(u.email))
+WHERE u.email <> lower(trim(u.email))
+  AND NOT EXISTS (
+      SELECT 1 FROM users o WHERE o.id <> u.id AND lower(trim(o.email)) = lower(trim(u.email))
+  );
+
+-- 3. Registration relies on this for the 409, after its own pre-insert check.
+CREATE UNIQUE INDEX IF NOT EXISTS users_email_lower_key ON users (lower(email));